@@ -0,0 +1,118 @@
+//! 并发读取基准测试：模拟多个窗口同时读取商品列表，
+//! 对比旧的 `Arc<Mutex<Database>>`（所有命令串行）与直接共享连接池
+//!
+//! 运行：cargo bench --bench concurrency
+
+use std::sync::Arc;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tokio::runtime::Runtime;
+use tokio::sync::Mutex;
+
+#[allow(dead_code, unused_imports)] // database 下各模块的 tests 子模块在基准测试里只编译不运行
+#[path = "../src/database/mod.rs"]
+mod database;
+
+use database::seed::{self, SeedOptions};
+use database::{config::DATABASE_PATH_ENV, Database, DatabaseConfig};
+
+const PRODUCT_COUNT: i64 = 5_000;
+const CONCURRENT_READERS: [usize; 3] = [1, 10, 100];
+
+const LIST_QUERY: &str = r#"
+    SELECT p.id, p.name, p.price, c.name as category_name
+    FROM products p
+    LEFT JOIN categories c ON p.category_id = c.id
+    ORDER BY p.created_at DESC
+    LIMIT 50 OFFSET $1
+"#;
+
+async fn setup_database() -> Database {
+    let dir = std::env::temp_dir().join(format!("rust-admin-bench-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create bench dir");
+    std::env::set_var(DATABASE_PATH_ENV, dir.join("bench.db"));
+
+    let config = DatabaseConfig::resolve(None, None).expect("resolve bench database");
+    let db = Database::new(&config).await.expect("open bench database");
+
+    // 基准测试针对 SQLite 的连接池配置
+    let pool = db.sqlite().expect("bench requires the SQLite backend");
+    let existing = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM products")
+        .fetch_one(pool)
+        .await
+        .unwrap();
+    if existing == 0 {
+        let options = SeedOptions {
+            products: PRODUCT_COUNT as usize,
+            orders: 0,
+            logs: 0,
+            ..SeedOptions::default()
+        };
+        seed::seed_demo_data(&db.pool, &options).await.expect("seed bench database");
+    }
+
+    db
+}
+
+fn bench_parallel_reads(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    let db = rt.block_on(setup_database());
+    let locked = Arc::new(Mutex::new(db.clone()));
+
+    let mut group = c.benchmark_group("parallel_reads");
+
+    for readers in CONCURRENT_READERS {
+        // 旧实现：每个命令在整个执行期间持有全局锁
+        group.bench_with_input(BenchmarkId::new("global_mutex", readers), &readers, |b, &readers| {
+            b.iter(|| {
+                rt.block_on(async {
+                    let tasks: Vec<_> = (0..readers)
+                        .map(|i| {
+                            let locked = locked.clone();
+                            tokio::spawn(async move {
+                                let db = locked.lock().await;
+                                sqlx::query(LIST_QUERY)
+                                    .bind((i as i64 * 50) % PRODUCT_COUNT)
+                                    .fetch_all(db.sqlite().unwrap())
+                                    .await
+                                    .unwrap()
+                                    .len()
+                            })
+                        })
+                        .collect();
+                    for task in tasks {
+                        task.await.unwrap();
+                    }
+                })
+            })
+        });
+
+        // 新实现：直接共享连接池
+        group.bench_with_input(BenchmarkId::new("shared_pool", readers), &readers, |b, &readers| {
+            b.iter(|| {
+                rt.block_on(async {
+                    let tasks: Vec<_> = (0..readers)
+                        .map(|i| {
+                            let db = db.clone();
+                            tokio::spawn(async move {
+                                sqlx::query(LIST_QUERY)
+                                    .bind((i as i64 * 50) % PRODUCT_COUNT)
+                                    .fetch_all(db.sqlite().unwrap())
+                                    .await
+                                    .unwrap()
+                                    .len()
+                            })
+                        })
+                        .collect();
+                    for task in tasks {
+                        task.await.unwrap();
+                    }
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parallel_reads);
+criterion_main!(benches);
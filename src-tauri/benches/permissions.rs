@@ -0,0 +1,54 @@
+//! 权限检查基准测试：对比每次解析 JSON 的旧实现与按角色缓存的编译结果
+//!
+//! 运行：cargo bench --bench permissions
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[allow(dead_code, unused_imports)] // 模块内的单元测试不在基准测试中运行
+#[path = "../src/utils/permissions.rs"]
+mod permissions;
+
+use permissions::{CompiledPermissions, PermissionCache};
+
+const MANAGER_PERMISSIONS: &str = r#"["dashboard:read", "user:read", "user:write", "product:read", "product:write", "order:read", "order:write", "category:read", "category:write"]"#;
+
+// 旧实现：每次检查都重新解析权限 JSON（省略了原来的 println!，它只会让差距更大）
+fn legacy_has_permission(user_permissions: &str, required_permission: &str) -> bool {
+    if user_permissions.contains('*') {
+        return true;
+    }
+
+    if let Ok(permissions) = serde_json::from_str::<Vec<String>>(user_permissions) {
+        permissions.contains(&required_permission.to_string())
+    } else {
+        false
+    }
+}
+
+fn bench_permission_check(c: &mut Criterion) {
+    let mut group = c.benchmark_group("permission_check");
+
+    group.bench_function("legacy_parse_json", |b| {
+        b.iter(|| legacy_has_permission(black_box(MANAGER_PERMISSIONS), black_box("category:write")))
+    });
+
+    let compiled = CompiledPermissions::compile(MANAGER_PERMISSIONS);
+    group.bench_function("compiled", |b| {
+        b.iter(|| compiled.allows(black_box("category:write")))
+    });
+
+    let cache = PermissionCache::default();
+    cache.insert(2, MANAGER_PERMISSIONS);
+    group.bench_function("cached_lookup", |b| {
+        b.iter(|| {
+            cache
+                .get(black_box(2))
+                .is_some_and(|p| p.allows(black_box("category:write")))
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_permission_check);
+criterion_main!(benches);
@@ -0,0 +1,140 @@
+-- 初始表结构（PostgreSQL），字段类型与 SQLite 版本对应：
+-- INTEGER PRIMARY KEY AUTOINCREMENT -> BIGSERIAL，REAL -> DOUBLE PRECISION，DATETIME -> TIMESTAMPTZ
+CREATE TABLE IF NOT EXISTS roles (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    permissions TEXT NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS users (
+    id BIGSERIAL PRIMARY KEY,
+    username TEXT NOT NULL UNIQUE,
+    email TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    phone TEXT,
+    address TEXT,
+    avatar TEXT,
+    role_id BIGINT NOT NULL DEFAULT 3 REFERENCES roles (id),
+    status INTEGER NOT NULL DEFAULT 1,
+    failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_login TIMESTAMPTZ,
+    locked_until TIMESTAMPTZ,
+    last_login TIMESTAMPTZ,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS categories (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    parent_id BIGINT REFERENCES categories (id),
+    sort_order INTEGER DEFAULT 0,
+    status INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS products (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    price DOUBLE PRECISION NOT NULL,
+    description TEXT,
+    category_id BIGINT REFERENCES categories (id),
+    stock INTEGER NOT NULL DEFAULT 0,
+    images TEXT,
+    status INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS orders (
+    id BIGSERIAL PRIMARY KEY,
+    order_no TEXT NOT NULL UNIQUE,
+    user_id BIGINT NOT NULL REFERENCES users (id),
+    total_amount DOUBLE PRECISION NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS order_items (
+    id BIGSERIAL PRIMARY KEY,
+    order_id BIGINT NOT NULL REFERENCES orders (id),
+    product_id BIGINT NOT NULL REFERENCES products (id),
+    product_name TEXT NOT NULL,
+    price DOUBLE PRECISION NOT NULL,
+    quantity INTEGER NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS system_settings (
+    id BIGSERIAL PRIMARY KEY,
+    setting_key TEXT NOT NULL UNIQUE,
+    setting_value TEXT NOT NULL,
+    setting_type TEXT NOT NULL DEFAULT 'string',
+    description TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS login_logs (
+    id BIGSERIAL PRIMARY KEY,
+    username TEXT NOT NULL,
+    ip_address TEXT,
+    user_agent TEXT,
+    success INTEGER NOT NULL DEFAULT 0,
+    failure_reason TEXT,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS menus (
+    id BIGSERIAL PRIMARY KEY,
+    parent_id BIGINT REFERENCES menus (id),
+    name TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    path TEXT,
+    icon TEXT,
+    permission TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    visible INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 默认设置
+INSERT INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('system_name', 'Rust Admin', 'string', '系统名称'),
+    ('system_description', '基于 Tauri + Vue 3 的后台管理系统', 'string', '系统描述'),
+    ('system_version', '1.0.0', 'string', '系统版本'),
+    ('theme_color', '#1890ff', 'string', '主题颜色'),
+    ('language', 'zh-CN', 'string', '系统语言'),
+    ('page_size', '10', 'number', '默认页面大小'),
+    ('max_login_attempts', '5', 'number', '最大登录尝试次数'),
+    ('lockout_duration', '300', 'number', '账户锁定时间（秒）'),
+    ('reset_attempts_after', '3600', 'number', '重置尝试次数的时间间隔（秒）')
+ON CONFLICT DO NOTHING;
+
+-- 默认角色
+INSERT INTO roles (id, name, permissions)
+VALUES 
+    (1, '超级管理员', '["*"]'),
+    (2, '管理员', '["dashboard:read", "user:read", "user:write", "product:read", "product:write", "order:read", "order:write", "category:read", "category:write"]'),
+    (3, '普通用户', '["dashboard:read", "order:read"]')
+ON CONFLICT DO NOTHING;
+
+-- 默认菜单（与前端路由保持一致）
+INSERT INTO menus (id, parent_id, name, title, path, icon, permission, sort_order)
+VALUES 
+    (1, NULL, 'Dashboard', 'menu.dashboard', '/dashboard', 'DashboardOutlined', 'dashboard:read', 1),
+    (2, NULL, 'Users', 'menu.users', '/users', 'UserOutlined', 'user:read', 2),
+    (3, NULL, 'Products', 'menu.products', '/products', 'ShoppingOutlined', 'product:read', 3),
+    (4, NULL, 'Orders', 'menu.orders', '/orders', 'ShoppingCartOutlined', 'order:read', 4),
+    (5, NULL, 'Categories', 'menu.categories', '/categories', 'AppstoreOutlined', 'category:read', 5),
+    (6, NULL, 'Settings', 'menu.settings', '/settings', 'SettingOutlined', 'settings:read', 6)
+ON CONFLICT DO NOTHING;
+
+-- 显式指定了 id，需要把序列推进到当前最大值之后
+SELECT setval(pg_get_serial_sequence('roles', 'id'), (SELECT MAX(id) FROM roles));
+SELECT setval(pg_get_serial_sequence('menus', 'id'), (SELECT MAX(id) FROM menus));
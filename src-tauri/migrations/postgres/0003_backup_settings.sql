@@ -0,0 +1,8 @@
+-- 定时备份设置（PostgreSQL 后端不执行定时备份，保留设置项以便两种后端的设置一致）
+INSERT INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('backup_enabled', 'true', 'boolean', '启用定时备份'),
+    ('backup_interval_hours', '24', 'number', '定时备份间隔（小时）'),
+    ('backup_retention_count', '7', 'number', '保留的备份数量'),
+    ('backup_compress', 'true', 'boolean', '压缩备份文件')
+ON CONFLICT DO NOTHING;
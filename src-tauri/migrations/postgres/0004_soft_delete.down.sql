@@ -0,0 +1,14 @@
+DELETE FROM system_settings WHERE setting_key = 'recycle_bin_retention_days';
+
+-- 不删除回收站中的数据：回滚后它们会作为普通数据重新出现，避免丢失订单依赖的记录
+
+DROP INDEX IF EXISTS idx_users_deleted_at;
+DROP INDEX IF EXISTS idx_products_deleted_at;
+DROP INDEX IF EXISTS idx_categories_deleted_at;
+
+ALTER TABLE users DROP COLUMN deleted_at;
+ALTER TABLE users DROP COLUMN deleted_by;
+ALTER TABLE products DROP COLUMN deleted_at;
+ALTER TABLE products DROP COLUMN deleted_by;
+ALTER TABLE categories DROP COLUMN deleted_at;
+ALTER TABLE categories DROP COLUMN deleted_by;
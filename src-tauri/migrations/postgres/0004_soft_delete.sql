@@ -0,0 +1,17 @@
+-- 软删除：删除只记录删除时间和操作人，回收站可恢复，超过保留期后才永久清除
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN deleted_by BIGINT;
+ALTER TABLE products ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE products ADD COLUMN deleted_by BIGINT;
+ALTER TABLE categories ADD COLUMN deleted_at TIMESTAMPTZ;
+ALTER TABLE categories ADD COLUMN deleted_by BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_users_deleted_at ON users(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_products_deleted_at ON products(deleted_at);
+CREATE INDEX IF NOT EXISTS idx_categories_deleted_at ON categories(deleted_at);
+
+-- 回收站保留期
+INSERT INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('recycle_bin_retention_days', '30', 'number', '回收站保留天数（0 表示不自动清除）')
+ON CONFLICT DO NOTHING;
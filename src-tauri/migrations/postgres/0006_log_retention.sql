@@ -0,0 +1,11 @@
+-- 登录日志按时间清除时使用
+CREATE INDEX IF NOT EXISTS idx_login_logs_created_at ON login_logs (created_at);
+
+-- 日志保留期与归档
+INSERT INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('log_retention_days', '180', 'number', '操作日志保留天数（0 表示永久保留）'),
+    ('login_log_retention_days', '90', 'number', '登录日志保留天数（0 表示永久保留）'),
+    ('log_archive_enabled', 'false', 'boolean', '清除前把日志归档到压缩文件'),
+    ('log_retention_status', '', 'json', '最近一次日志清除的结果')
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,3 @@
+-- 全文检索索引只在 SQLite 上建立（FTS5 trigram）。PostgreSQL 的列表搜索使用 ILIKE 匹配原表，
+-- 不需要额外的表结构，此迁移只用于保持两种方言的版本号一致
+SELECT 1;
@@ -0,0 +1,21 @@
+-- 收回店铺管理的权限
+UPDATE roles SET permissions = (permissions::jsonb - 'tenant:manage')::text
+WHERE permissions::jsonb ? 'tenant:manage';
+
+-- 回滚后所有店铺的数据合并为一个店铺，店铺单独的设置丢弃
+DELETE FROM system_settings WHERE tenant_id <> 0;
+ALTER TABLE system_settings DROP CONSTRAINT IF EXISTS system_settings_tenant_key;
+ALTER TABLE system_settings DROP COLUMN tenant_id;
+ALTER TABLE system_settings ADD CONSTRAINT system_settings_setting_key_key UNIQUE (setting_key);
+
+DROP INDEX IF EXISTS idx_categories_tenant_id;
+DROP INDEX IF EXISTS idx_products_tenant_id;
+DROP INDEX IF EXISTS idx_orders_tenant_id;
+
+ALTER TABLE categories DROP COLUMN tenant_id;
+ALTER TABLE products DROP COLUMN tenant_id;
+ALTER TABLE orders DROP COLUMN tenant_id;
+ALTER TABLE users DROP COLUMN current_tenant_id;
+
+DROP TABLE IF EXISTS tenant_users;
+DROP TABLE IF EXISTS tenants;
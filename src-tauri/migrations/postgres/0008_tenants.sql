@@ -0,0 +1,47 @@
+-- 多店铺：一个安装中经营多个店铺（租户），分类、商品、订单按 tenant_id 隔离，
+-- 用户账号全局共享，通过 tenant_users 加入一个或多个店铺
+CREATE TABLE IF NOT EXISTS tenants (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    code TEXT NOT NULL UNIQUE,
+    status INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    version BIGINT NOT NULL DEFAULT 1
+);
+
+INSERT INTO tenants (id, name, code) VALUES (1, '默认店铺', 'default') ON CONFLICT DO NOTHING;
+SELECT setval(pg_get_serial_sequence('tenants', 'id'), (SELECT MAX(id) FROM tenants));
+
+CREATE TABLE IF NOT EXISTS tenant_users (
+    tenant_id BIGINT NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_users_user_id ON tenant_users(user_id);
+
+-- 已有用户都加入默认店铺
+INSERT INTO tenant_users (tenant_id, user_id) SELECT 1, id FROM users ON CONFLICT DO NOTHING;
+
+-- 用户最近一次切换到的店铺
+ALTER TABLE users ADD COLUMN current_tenant_id BIGINT;
+
+-- 已有数据归入默认店铺
+ALTER TABLE categories ADD COLUMN tenant_id BIGINT NOT NULL DEFAULT 1 REFERENCES tenants(id);
+ALTER TABLE products ADD COLUMN tenant_id BIGINT NOT NULL DEFAULT 1 REFERENCES tenants(id);
+ALTER TABLE orders ADD COLUMN tenant_id BIGINT NOT NULL DEFAULT 1 REFERENCES tenants(id);
+
+CREATE INDEX IF NOT EXISTS idx_categories_tenant_id ON categories(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_products_tenant_id ON products(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_orders_tenant_id ON orders(tenant_id);
+
+-- 设置按店铺覆盖：tenant_id 为 0 的是全局设置，店铺的同名设置优先
+ALTER TABLE system_settings ADD COLUMN tenant_id BIGINT NOT NULL DEFAULT 0;
+ALTER TABLE system_settings DROP CONSTRAINT IF EXISTS system_settings_setting_key_key;
+ALTER TABLE system_settings ADD CONSTRAINT system_settings_tenant_key UNIQUE (tenant_id, setting_key);
+
+-- 店铺管理的权限授予默认的管理员角色
+UPDATE roles SET permissions = (permissions::jsonb || '["tenant:manage"]'::jsonb)::text
+WHERE id = 2 AND NOT permissions::jsonb ? 'tenant:manage';
@@ -0,0 +1,3 @@
+-- 同步用于多台桌面安装（各自的 SQLite 文件）之间交换数据。使用 PostgreSQL 时各门店直接连接同一个数据库，
+-- 不需要同步，此迁移只用于保持两种方言的版本号一致
+SELECT 1;
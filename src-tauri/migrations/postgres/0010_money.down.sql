@@ -0,0 +1,9 @@
+DELETE FROM system_settings WHERE setting_key IN ('currency', 'money_rounding');
+
+ALTER TABLE order_items ALTER COLUMN price TYPE DOUBLE PRECISION USING price / 100.0;
+
+ALTER TABLE orders DROP COLUMN IF EXISTS currency;
+ALTER TABLE orders ALTER COLUMN total_amount TYPE DOUBLE PRECISION USING total_amount / 100.0;
+
+ALTER TABLE products DROP COLUMN IF EXISTS currency;
+ALTER TABLE products ALTER COLUMN price TYPE DOUBLE PRECISION USING price / 100.0;
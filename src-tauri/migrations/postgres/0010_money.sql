@@ -0,0 +1,15 @@
+-- 金额改为最小货币单位（分）的整数；先转为 numeric 再乘 100，四舍五入不受二进制浮点误差影响
+ALTER TABLE products ALTER COLUMN price TYPE BIGINT USING ROUND(price::numeric * 100)::BIGINT;
+ALTER TABLE products ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'CNY';
+
+ALTER TABLE orders ALTER COLUMN total_amount TYPE BIGINT USING ROUND(total_amount::numeric * 100)::BIGINT;
+ALTER TABLE orders ADD COLUMN IF NOT EXISTS currency TEXT NOT NULL DEFAULT 'CNY';
+
+-- 订单明细的币种与所属订单相同
+ALTER TABLE order_items ALTER COLUMN price TYPE BIGINT USING ROUND(price::numeric * 100)::BIGINT;
+
+INSERT INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('currency', 'CNY', 'string', '新建商品和订单使用的币种'),
+    ('money_rounding', 'half_up', 'string', '金额小数位超出币种精度时的舍入方式（half_up、half_even、down、up）')
+ON CONFLICT DO NOTHING;
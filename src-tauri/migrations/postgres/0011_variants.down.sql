@@ -0,0 +1,6 @@
+ALTER TABLE order_items DROP COLUMN IF EXISTS sku;
+ALTER TABLE order_items DROP COLUMN IF EXISTS variant_id;
+
+DROP TABLE IF EXISTS product_variants;
+
+ALTER TABLE products DROP COLUMN IF EXISTS variant_options;
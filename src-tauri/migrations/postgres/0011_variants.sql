@@ -0,0 +1,29 @@
+-- 商品规格：products.variant_options 为规格项的定义（JSON），例如
+-- [{"name":"尺码","values":["S","M","L"]},{"name":"颜色","values":["红","黑"]}]
+ALTER TABLE products ADD COLUMN IF NOT EXISTS variant_options TEXT;
+
+-- 每个规格组合一行，options 为 {"尺码":"M","颜色":"红"}；price 为空时按商品价格出售。
+-- 删除规格只做软删除，已下单的明细仍然可以引用；SKU 和条码在店铺内唯一
+CREATE TABLE IF NOT EXISTS product_variants (
+    id BIGSERIAL PRIMARY KEY,
+    tenant_id BIGINT NOT NULL REFERENCES tenants(id),
+    product_id BIGINT NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    sku TEXT NOT NULL,
+    barcode TEXT,
+    options TEXT NOT NULL,
+    price BIGINT,
+    stock INTEGER NOT NULL DEFAULT 0,
+    image TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_variants_product_id ON product_variants(product_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_product_variants_sku ON product_variants(tenant_id, sku) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_product_variants_barcode ON product_variants(tenant_id, barcode) WHERE deleted_at IS NULL AND barcode IS NOT NULL;
+
+-- 订单明细可以指向具体的规格，sku 为下单时的快照
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS variant_id BIGINT REFERENCES product_variants(id);
+ALTER TABLE order_items ADD COLUMN IF NOT EXISTS sku TEXT;
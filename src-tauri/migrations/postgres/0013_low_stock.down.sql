@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS low_stock_alerts;
+ALTER TABLE categories DROP COLUMN reorder_threshold;
+ALTER TABLE products DROP COLUMN reorder_threshold;
@@ -0,0 +1,12 @@
+-- 库存预警值：商品未设置时使用所属分类的值，都没有设置时不预警
+ALTER TABLE products ADD COLUMN reorder_threshold INTEGER;
+ALTER TABLE categories ADD COLUMN reorder_threshold INTEGER;
+
+-- 已经发出预警、库存仍未恢复的商品。库存回到预警值以上时删除，下次跌破时再提醒一次
+CREATE TABLE IF NOT EXISTS low_stock_alerts (
+    product_id BIGINT PRIMARY KEY REFERENCES products(id) ON DELETE CASCADE,
+    tenant_id BIGINT NOT NULL REFERENCES tenants(id),
+    stock INTEGER NOT NULL,
+    threshold INTEGER NOT NULL,
+    alerted_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,139 @@
+-- 初始表结构：与引入迁移台账之前的数据库保持一致，已有数据库可以直接通过
+CREATE TABLE IF NOT EXISTS roles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    permissions TEXT NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL UNIQUE,
+    email TEXT NOT NULL UNIQUE,
+    password_hash TEXT NOT NULL,
+    phone TEXT,
+    address TEXT,
+    avatar TEXT,
+    role_id INTEGER NOT NULL DEFAULT 3,
+    status INTEGER NOT NULL DEFAULT 1,
+    failed_login_attempts INTEGER NOT NULL DEFAULT 0,
+    last_failed_login DATETIME,
+    locked_until DATETIME,
+    last_login DATETIME,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (role_id) REFERENCES roles (id)
+);
+
+CREATE TABLE IF NOT EXISTS categories (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    parent_id INTEGER,
+    sort_order INTEGER DEFAULT 0,
+    status INTEGER NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (parent_id) REFERENCES categories(id)
+);
+
+CREATE TABLE IF NOT EXISTS products (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    price REAL NOT NULL,
+    description TEXT,
+    category_id INTEGER,
+    stock INTEGER NOT NULL DEFAULT 0,
+    images TEXT,
+    status INTEGER NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (category_id) REFERENCES categories(id)
+);
+
+CREATE TABLE IF NOT EXISTS orders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_no TEXT NOT NULL UNIQUE,
+    user_id INTEGER NOT NULL,
+    total_amount REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE TABLE IF NOT EXISTS order_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    product_name TEXT NOT NULL,
+    price REAL NOT NULL,
+    quantity INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (order_id) REFERENCES orders(id),
+    FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+CREATE TABLE IF NOT EXISTS system_settings (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    setting_key TEXT NOT NULL UNIQUE,
+    setting_value TEXT NOT NULL,
+    setting_type TEXT NOT NULL DEFAULT 'string',
+    description TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS login_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL,
+    ip_address TEXT,
+    user_agent TEXT,
+    success INTEGER NOT NULL DEFAULT 0,
+    failure_reason TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS menus (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    parent_id INTEGER,
+    name TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    path TEXT,
+    icon TEXT,
+    permission TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    visible INTEGER NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (parent_id) REFERENCES menus(id)
+);
+
+-- 默认设置
+INSERT OR IGNORE INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('system_name', 'Rust Admin', 'string', '系统名称'),
+    ('system_description', '基于 Tauri + Vue 3 的后台管理系统', 'string', '系统描述'),
+    ('system_version', '1.0.0', 'string', '系统版本'),
+    ('theme_color', '#1890ff', 'string', '主题颜色'),
+    ('language', 'zh-CN', 'string', '系统语言'),
+    ('page_size', '10', 'number', '默认页面大小'),
+    ('max_login_attempts', '5', 'number', '最大登录尝试次数'),
+    ('lockout_duration', '300', 'number', '账户锁定时间（秒）'),
+    ('reset_attempts_after', '3600', 'number', '重置尝试次数的时间间隔（秒）');
+
+-- 默认角色
+INSERT OR IGNORE INTO roles (id, name, permissions)
+VALUES 
+    (1, '超级管理员', '["*"]'),
+    (2, '管理员', '["dashboard:read", "user:read", "user:write", "product:read", "product:write", "order:read", "order:write", "category:read", "category:write"]'),
+    (3, '普通用户', '["dashboard:read", "order:read"]');
+
+-- 默认菜单（与前端路由保持一致）
+INSERT OR IGNORE INTO menus (id, parent_id, name, title, path, icon, permission, sort_order)
+VALUES 
+    (1, NULL, 'Dashboard', 'menu.dashboard', '/dashboard', 'DashboardOutlined', 'dashboard:read', 1),
+    (2, NULL, 'Users', 'menu.users', '/users', 'UserOutlined', 'user:read', 2),
+    (3, NULL, 'Products', 'menu.products', '/products', 'ShoppingOutlined', 'product:read', 3),
+    (4, NULL, 'Orders', 'menu.orders', '/orders', 'ShoppingCartOutlined', 'order:read', 4),
+    (5, NULL, 'Categories', 'menu.categories', '/categories', 'AppstoreOutlined', 'category:read', 5),
+    (6, NULL, 'Settings', 'menu.settings', '/settings', 'SettingOutlined', 'settings:read', 6);
@@ -0,0 +1,2 @@
+DELETE FROM system_settings
+WHERE setting_key IN ('backup_enabled', 'backup_interval_hours', 'backup_retention_count', 'backup_compress');
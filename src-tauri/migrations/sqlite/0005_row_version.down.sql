@@ -0,0 +1,4 @@
+ALTER TABLE users DROP COLUMN version;
+ALTER TABLE products DROP COLUMN version;
+ALTER TABLE categories DROP COLUMN version;
+ALTER TABLE orders DROP COLUMN version;
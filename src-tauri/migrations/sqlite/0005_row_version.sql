@@ -0,0 +1,5 @@
+-- 乐观并发控制：每次修改递增版本号，客户端提交修改时带回读取时的版本号，版本不一致则拒绝覆盖
+ALTER TABLE users ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE products ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE categories ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE orders ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
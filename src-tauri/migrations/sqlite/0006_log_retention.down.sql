@@ -0,0 +1,4 @@
+DELETE FROM system_settings
+WHERE setting_key IN ('log_retention_days', 'login_log_retention_days', 'log_archive_enabled', 'log_retention_status');
+
+DROP INDEX IF EXISTS idx_login_logs_created_at;
@@ -0,0 +1,15 @@
+DROP TRIGGER IF EXISTS products_fts_insert;
+DROP TRIGGER IF EXISTS products_fts_update;
+DROP TRIGGER IF EXISTS products_fts_delete;
+DROP TRIGGER IF EXISTS categories_fts_update;
+DROP TRIGGER IF EXISTS categories_fts_delete;
+DROP TRIGGER IF EXISTS users_fts_insert;
+DROP TRIGGER IF EXISTS users_fts_update;
+DROP TRIGGER IF EXISTS users_fts_delete;
+DROP TRIGGER IF EXISTS orders_fts_insert;
+DROP TRIGGER IF EXISTS orders_fts_update;
+DROP TRIGGER IF EXISTS orders_fts_delete;
+
+DROP TABLE IF EXISTS products_fts;
+DROP TABLE IF EXISTS users_fts;
+DROP TABLE IF EXISTS orders_fts;
@@ -0,0 +1,63 @@
+-- 全文检索：trigram 分词按三个字符切分，中文无需词典即可检索，MATCH 与 LIKE 都能使用索引。
+-- 索引表的 rowid 与原表 id 相同，由触发器与原表保持同步
+CREATE VIRTUAL TABLE IF NOT EXISTS products_fts USING fts5(name, description, category_name, tokenize = 'trigram');
+CREATE VIRTUAL TABLE IF NOT EXISTS users_fts USING fts5(username, email, tokenize = 'trigram');
+CREATE VIRTUAL TABLE IF NOT EXISTS orders_fts USING fts5(order_no, tokenize = 'trigram');
+
+CREATE TRIGGER IF NOT EXISTS products_fts_insert AFTER INSERT ON products BEGIN
+    INSERT INTO products_fts (rowid, name, description, category_name)
+    VALUES (new.id, new.name, new.description, (SELECT name FROM categories WHERE id = new.category_id));
+END;
+
+CREATE TRIGGER IF NOT EXISTS products_fts_update AFTER UPDATE OF name, description, category_id ON products BEGIN
+    UPDATE products_fts
+    SET name = new.name,
+        description = new.description,
+        category_name = (SELECT name FROM categories WHERE id = new.category_id)
+    WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS products_fts_delete AFTER DELETE ON products BEGIN
+    DELETE FROM products_fts WHERE rowid = old.id;
+END;
+
+-- 分类改名或被永久删除时，同步其下商品的分类名
+CREATE TRIGGER IF NOT EXISTS categories_fts_update AFTER UPDATE OF name ON categories BEGIN
+    UPDATE products_fts SET category_name = new.name
+    WHERE rowid IN (SELECT id FROM products WHERE category_id = new.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS categories_fts_delete AFTER DELETE ON categories BEGIN
+    UPDATE products_fts SET category_name = NULL
+    WHERE rowid IN (SELECT id FROM products WHERE category_id = old.id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS users_fts_insert AFTER INSERT ON users BEGIN
+    INSERT INTO users_fts (rowid, username, email) VALUES (new.id, new.username, new.email);
+END;
+
+CREATE TRIGGER IF NOT EXISTS users_fts_update AFTER UPDATE OF username, email ON users BEGIN
+    UPDATE users_fts SET username = new.username, email = new.email WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS users_fts_delete AFTER DELETE ON users BEGIN
+    DELETE FROM users_fts WHERE rowid = old.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS orders_fts_insert AFTER INSERT ON orders BEGIN
+    INSERT INTO orders_fts (rowid, order_no) VALUES (new.id, new.order_no);
+END;
+
+CREATE TRIGGER IF NOT EXISTS orders_fts_update AFTER UPDATE OF order_no ON orders BEGIN
+    UPDATE orders_fts SET order_no = new.order_no WHERE rowid = new.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS orders_fts_delete AFTER DELETE ON orders BEGIN
+    DELETE FROM orders_fts WHERE rowid = old.id;
+END;
+
+-- 为已有数据建立索引
+INSERT INTO products_fts (rowid, name, description, category_name)
+SELECT p.id, p.name, p.description, c.name FROM products p LEFT JOIN categories c ON p.category_id = c.id;
+INSERT INTO users_fts (rowid, username, email) SELECT id, username, email FROM users;
+INSERT INTO orders_fts (rowid, order_no) SELECT id, order_no FROM orders;
@@ -0,0 +1,32 @@
+-- 收回店铺管理的权限
+UPDATE roles SET permissions = (SELECT json_group_array(value) FROM json_each(roles.permissions) WHERE value <> 'tenant:manage')
+WHERE EXISTS (SELECT 1 FROM json_each(roles.permissions) WHERE value = 'tenant:manage');
+
+-- 回滚后所有店铺的数据合并为一个店铺，店铺单独的设置丢弃
+CREATE TABLE system_settings_old (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    setting_key TEXT NOT NULL UNIQUE,
+    setting_value TEXT NOT NULL,
+    setting_type TEXT NOT NULL DEFAULT 'string',
+    description TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO system_settings_old (id, setting_key, setting_value, setting_type, description, created_at, updated_at)
+SELECT id, setting_key, setting_value, setting_type, description, created_at, updated_at FROM system_settings WHERE tenant_id = 0;
+
+DROP TABLE system_settings;
+ALTER TABLE system_settings_old RENAME TO system_settings;
+
+DROP INDEX IF EXISTS idx_categories_tenant_id;
+DROP INDEX IF EXISTS idx_products_tenant_id;
+DROP INDEX IF EXISTS idx_orders_tenant_id;
+
+ALTER TABLE categories DROP COLUMN tenant_id;
+ALTER TABLE products DROP COLUMN tenant_id;
+ALTER TABLE orders DROP COLUMN tenant_id;
+ALTER TABLE users DROP COLUMN current_tenant_id;
+
+DROP TABLE IF EXISTS tenant_users;
+DROP TABLE IF EXISTS tenants;
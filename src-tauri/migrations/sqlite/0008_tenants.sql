@@ -0,0 +1,63 @@
+-- 多店铺：一个安装中经营多个店铺（租户），分类、商品、订单按 tenant_id 隔离，
+-- 用户账号全局共享，通过 tenant_users 加入一个或多个店铺
+CREATE TABLE IF NOT EXISTS tenants (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    code TEXT NOT NULL UNIQUE,
+    status INTEGER NOT NULL DEFAULT 1,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    version INTEGER NOT NULL DEFAULT 1
+);
+
+INSERT OR IGNORE INTO tenants (id, name, code) VALUES (1, '默认店铺', 'default');
+
+CREATE TABLE IF NOT EXISTS tenant_users (
+    tenant_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (tenant_id, user_id),
+    FOREIGN KEY (tenant_id) REFERENCES tenants(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_users_user_id ON tenant_users(user_id);
+
+-- 已有用户都加入默认店铺
+INSERT OR IGNORE INTO tenant_users (tenant_id, user_id) SELECT 1, id FROM users;
+
+-- 用户最近一次切换到的店铺
+ALTER TABLE users ADD COLUMN current_tenant_id INTEGER;
+
+-- 已有数据归入默认店铺。SQLite 启用外键时不能添加带非空默认值的外键列，店铺是否存在由应用保证
+ALTER TABLE categories ADD COLUMN tenant_id INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE products ADD COLUMN tenant_id INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE orders ADD COLUMN tenant_id INTEGER NOT NULL DEFAULT 1;
+
+CREATE INDEX IF NOT EXISTS idx_categories_tenant_id ON categories(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_products_tenant_id ON products(tenant_id);
+CREATE INDEX IF NOT EXISTS idx_orders_tenant_id ON orders(tenant_id);
+
+-- 设置按店铺覆盖：tenant_id 为 0 的是全局设置，店铺的同名设置优先。
+-- setting_key 上的唯一约束无法直接修改，重建表
+CREATE TABLE system_settings_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id INTEGER NOT NULL DEFAULT 0,
+    setting_key TEXT NOT NULL,
+    setting_value TEXT NOT NULL,
+    setting_type TEXT NOT NULL DEFAULT 'string',
+    description TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (tenant_id, setting_key)
+);
+
+INSERT INTO system_settings_new (id, tenant_id, setting_key, setting_value, setting_type, description, created_at, updated_at)
+SELECT id, 0, setting_key, setting_value, setting_type, description, created_at, updated_at FROM system_settings;
+
+DROP TABLE system_settings;
+ALTER TABLE system_settings_new RENAME TO system_settings;
+
+-- 店铺管理的权限授予默认的管理员角色
+UPDATE roles SET permissions = json_insert(permissions, '$[#]', 'tenant:manage')
+WHERE id = 2 AND NOT EXISTS (SELECT 1 FROM json_each(roles.permissions) WHERE value = 'tenant:manage');
@@ -0,0 +1,27 @@
+DELETE FROM system_settings WHERE setting_key IN ('sync_hub_url', 'sync_hub_token', 'sync_interval_minutes', 'sync_node_id', 'sync_clock', 'sync_pulled_seq', 'sync_status');
+
+DROP TRIGGER IF EXISTS order_items_sync_insert;
+DROP TRIGGER IF EXISTS order_items_sync_update;
+DROP TRIGGER IF EXISTS order_items_sync_delete;
+DROP TRIGGER IF EXISTS categories_sync_insert;
+DROP TRIGGER IF EXISTS categories_sync_update;
+DROP TRIGGER IF EXISTS categories_sync_delete;
+DROP TRIGGER IF EXISTS products_sync_insert;
+DROP TRIGGER IF EXISTS products_sync_update;
+DROP TRIGGER IF EXISTS products_sync_delete;
+DROP TRIGGER IF EXISTS orders_sync_insert;
+DROP TRIGGER IF EXISTS orders_sync_update;
+DROP TRIGGER IF EXISTS orders_sync_delete;
+
+DROP TABLE IF EXISTS sync_applying;
+DROP TABLE IF EXISTS sync_conflicts;
+DROP TABLE IF EXISTS sync_rows;
+DROP TABLE IF EXISTS sync_outbox;
+
+DROP INDEX IF EXISTS idx_categories_sync_id;
+DROP INDEX IF EXISTS idx_products_sync_id;
+DROP INDEX IF EXISTS idx_orders_sync_id;
+
+ALTER TABLE categories DROP COLUMN sync_id;
+ALTER TABLE products DROP COLUMN sync_id;
+ALTER TABLE orders DROP COLUMN sync_id;
@@ -0,0 +1,140 @@
+-- 多台安装之间的同步：本地的修改由触发器记入 sync_outbox，同步时推送到中心节点，
+-- 再拉取其他安装推送的修改，按混合逻辑时钟（HLC）以后写入者为准合并
+
+-- 跨安装识别同一行数据的全局 ID
+ALTER TABLE categories ADD COLUMN sync_id TEXT;
+ALTER TABLE products ADD COLUMN sync_id TEXT;
+ALTER TABLE orders ADD COLUMN sync_id TEXT;
+
+UPDATE categories SET sync_id = lower(hex(randomblob(16)));
+UPDATE products SET sync_id = lower(hex(randomblob(16)));
+UPDATE orders SET sync_id = lower(hex(randomblob(16)));
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_sync_id ON categories(sync_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_sync_id ON products(sync_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_orders_sync_id ON orders(sync_id);
+
+-- 待推送的本地修改，同一行的多次修改推送时合并为一条；changed_at 为毫秒时间戳。
+-- 本地新增的行还没有 sync_id，推送前按 row_id 补上
+CREATE TABLE IF NOT EXISTS sync_outbox (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    row_id INTEGER NOT NULL,
+    sync_id TEXT,
+    changed_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_outbox_row ON sync_outbox(entity, row_id);
+
+-- 每行最后一次生效的修改的 HLC，收到的修改与它比较决定是否覆盖
+CREATE TABLE IF NOT EXISTS sync_rows (
+    entity TEXT NOT NULL,
+    sync_id TEXT NOT NULL,
+    hlc TEXT NOT NULL,
+    PRIMARY KEY (entity, sync_id)
+);
+
+-- 收到但没有生效的修改，保留下来供查看
+CREATE TABLE IF NOT EXISTS sync_conflicts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    entity TEXT NOT NULL,
+    sync_id TEXT NOT NULL,
+    local_hlc TEXT,
+    remote_hlc TEXT NOT NULL,
+    remote_node TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    payload TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_sync_conflicts_entity ON sync_conflicts(entity);
+
+-- 写入收到的修改期间（同一个事务内）存在一行，触发器据此跳过记录，避免把收到的修改再推送回去
+CREATE TABLE IF NOT EXISTS sync_applying (
+    active INTEGER NOT NULL
+);
+
+-- categories：新增的行在推送前才分配 sync_id。不能在触发器里更新刚插入的行，
+-- 否则同一条 INSERT ... RETURNING 之后立即查询可能读不到这一行
+CREATE TRIGGER IF NOT EXISTS categories_sync_insert AFTER INSERT ON categories
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('categories', NEW.id, NEW.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+-- 还没有 sync_id 的行已经有一条待推送的新增记录，推送时读取的是最新数据；分配 sync_id 的那次更新也不记录
+CREATE TRIGGER IF NOT EXISTS categories_sync_update AFTER UPDATE ON categories
+WHEN OLD.sync_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('categories', NEW.id, NEW.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+CREATE TRIGGER IF NOT EXISTS categories_sync_delete AFTER DELETE ON categories
+WHEN OLD.sync_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('categories', OLD.id, OLD.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+-- products：同 categories
+CREATE TRIGGER IF NOT EXISTS products_sync_insert AFTER INSERT ON products
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('products', NEW.id, NEW.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+CREATE TRIGGER IF NOT EXISTS products_sync_update AFTER UPDATE ON products
+WHEN OLD.sync_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('products', NEW.id, NEW.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+CREATE TRIGGER IF NOT EXISTS products_sync_delete AFTER DELETE ON products
+WHEN OLD.sync_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('products', OLD.id, OLD.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+-- orders：同 categories
+CREATE TRIGGER IF NOT EXISTS orders_sync_insert AFTER INSERT ON orders
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('orders', NEW.id, NEW.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+CREATE TRIGGER IF NOT EXISTS orders_sync_update AFTER UPDATE ON orders
+WHEN OLD.sync_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('orders', NEW.id, NEW.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+CREATE TRIGGER IF NOT EXISTS orders_sync_delete AFTER DELETE ON orders
+WHEN OLD.sync_id IS NOT NULL AND NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) VALUES ('orders', OLD.id, OLD.sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER));
+END;
+
+-- 订单明细随订单一起同步，明细变化记为订单的修改
+CREATE TRIGGER IF NOT EXISTS order_items_sync_insert AFTER INSERT ON order_items
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at)
+    SELECT 'orders', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM orders WHERE id = NEW.order_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS order_items_sync_update AFTER UPDATE ON order_items
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at)
+    SELECT 'orders', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM orders WHERE id = NEW.order_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS order_items_sync_delete AFTER DELETE ON order_items
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at)
+    SELECT 'orders', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM orders WHERE id = OLD.order_id;
+END;
+
+-- 已有的数据在第一次同步时推送
+INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) SELECT 'categories', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM categories ORDER BY id;
+INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) SELECT 'products', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM products ORDER BY id;
+INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at) SELECT 'orders', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM orders ORDER BY id;
+
+-- 同步设置：中心节点地址为空时不同步
+INSERT OR IGNORE INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('sync_hub_url', '', 'string', '同步中心节点地址'),
+    ('sync_hub_token', '', 'string', '同步中心节点的访问令牌'),
+    ('sync_interval_minutes', '5', 'number', '自动同步间隔（分钟，0 表示只手动同步）'),
+    ('sync_node_id', '', 'string', '本安装的同步节点 ID'),
+    ('sync_clock', '', 'string', '本地混合逻辑时钟的最新值'),
+    ('sync_pulled_seq', '0', 'number', '已拉取到的中心节点变更序号'),
+    ('sync_status', '', 'json', '最近一次同步的结果');
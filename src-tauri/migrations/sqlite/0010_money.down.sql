@@ -0,0 +1,18 @@
+DELETE FROM system_settings WHERE setting_key IN ('currency', 'money_rounding');
+
+ALTER TABLE order_items ADD COLUMN price_real REAL NOT NULL DEFAULT 0;
+UPDATE order_items SET price_real = price / 100.0;
+ALTER TABLE order_items DROP COLUMN price;
+ALTER TABLE order_items RENAME COLUMN price_real TO price;
+
+ALTER TABLE orders DROP COLUMN currency;
+ALTER TABLE orders ADD COLUMN total_amount_real REAL NOT NULL DEFAULT 0;
+UPDATE orders SET total_amount_real = total_amount / 100.0;
+ALTER TABLE orders DROP COLUMN total_amount;
+ALTER TABLE orders RENAME COLUMN total_amount_real TO total_amount;
+
+ALTER TABLE products DROP COLUMN currency;
+ALTER TABLE products ADD COLUMN price_real REAL NOT NULL DEFAULT 0;
+UPDATE products SET price_real = price / 100.0;
+ALTER TABLE products DROP COLUMN price;
+ALTER TABLE products RENAME COLUMN price_real TO price;
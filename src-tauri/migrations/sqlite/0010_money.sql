@@ -0,0 +1,24 @@
+-- 金额改为最小货币单位（分）的整数，避免 REAL 累加产生的误差；商品和订单记录各自的币种。
+-- SQLite 不能修改列的类型：先写入新列，删除旧列后再改回原来的列名
+ALTER TABLE products ADD COLUMN price_minor INTEGER NOT NULL DEFAULT 0;
+UPDATE products SET price_minor = CAST(ROUND(price * 100) AS INTEGER);
+ALTER TABLE products DROP COLUMN price;
+ALTER TABLE products RENAME COLUMN price_minor TO price;
+ALTER TABLE products ADD COLUMN currency TEXT NOT NULL DEFAULT 'CNY';
+
+ALTER TABLE orders ADD COLUMN total_amount_minor INTEGER NOT NULL DEFAULT 0;
+UPDATE orders SET total_amount_minor = CAST(ROUND(total_amount * 100) AS INTEGER);
+ALTER TABLE orders DROP COLUMN total_amount;
+ALTER TABLE orders RENAME COLUMN total_amount_minor TO total_amount;
+ALTER TABLE orders ADD COLUMN currency TEXT NOT NULL DEFAULT 'CNY';
+
+-- 订单明细的币种与所属订单相同
+ALTER TABLE order_items ADD COLUMN price_minor INTEGER NOT NULL DEFAULT 0;
+UPDATE order_items SET price_minor = CAST(ROUND(price * 100) AS INTEGER);
+ALTER TABLE order_items DROP COLUMN price;
+ALTER TABLE order_items RENAME COLUMN price_minor TO price;
+
+INSERT OR IGNORE INTO system_settings (setting_key, setting_value, setting_type, description)
+VALUES 
+    ('currency', 'CNY', 'string', '新建商品和订单使用的币种'),
+    ('money_rounding', 'half_up', 'string', '金额小数位超出币种精度时的舍入方式（half_up、half_even、down、up）');
@@ -0,0 +1,10 @@
+DROP TRIGGER IF EXISTS product_variants_sync_insert;
+DROP TRIGGER IF EXISTS product_variants_sync_update;
+DROP TRIGGER IF EXISTS product_variants_sync_delete;
+
+ALTER TABLE order_items DROP COLUMN sku;
+ALTER TABLE order_items DROP COLUMN variant_id;
+
+DROP TABLE IF EXISTS product_variants;
+
+ALTER TABLE products DROP COLUMN variant_options;
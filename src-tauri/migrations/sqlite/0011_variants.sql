@@ -0,0 +1,49 @@
+-- 商品规格：products.variant_options 为规格项的定义（JSON），例如
+-- [{"name":"尺码","values":["S","M","L"]},{"name":"颜色","values":["红","黑"]}]
+ALTER TABLE products ADD COLUMN variant_options TEXT;
+
+-- 每个规格组合一行，options 为 {"尺码":"M","颜色":"红"}；price 为空时按商品价格出售。
+-- 删除规格只做软删除，已下单的明细仍然可以引用；SKU 和条码在店铺内唯一
+CREATE TABLE IF NOT EXISTS product_variants (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id INTEGER NOT NULL REFERENCES tenants(id),
+    product_id INTEGER NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    sku TEXT NOT NULL,
+    barcode TEXT,
+    options TEXT NOT NULL,
+    price INTEGER,
+    stock INTEGER NOT NULL DEFAULT 0,
+    image TEXT,
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    deleted_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_variants_product_id ON product_variants(product_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_product_variants_sku ON product_variants(tenant_id, sku) WHERE deleted_at IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_product_variants_barcode ON product_variants(tenant_id, barcode) WHERE deleted_at IS NULL AND barcode IS NOT NULL;
+
+-- 订单明细可以指向具体的规格，sku 为下单时的快照。
+-- SQLite 不能删除带外键约束的列，这里不声明外键，否则回滚时无法删除该列
+ALTER TABLE order_items ADD COLUMN variant_id INTEGER;
+ALTER TABLE order_items ADD COLUMN sku TEXT;
+
+-- 规格随商品一起同步，规格的变化记为商品的修改
+CREATE TRIGGER IF NOT EXISTS product_variants_sync_insert AFTER INSERT ON product_variants
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at)
+    SELECT 'products', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM products WHERE id = NEW.product_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS product_variants_sync_update AFTER UPDATE ON product_variants
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at)
+    SELECT 'products', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM products WHERE id = NEW.product_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS product_variants_sync_delete AFTER DELETE ON product_variants
+WHEN NOT EXISTS (SELECT 1 FROM sync_applying) BEGIN
+    INSERT INTO sync_outbox (entity, row_id, sync_id, changed_at)
+    SELECT 'products', id, sync_id, CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER) FROM products WHERE id = OLD.product_id;
+END;
@@ -0,0 +1,26 @@
+-- 库存流水：库存的每次变化记一行，quantity 为变化量（入库为正、出库为负），stock_after 为变化后的库存。
+-- variant_id 为空的流水属于商品本身，否则属于该规格；商品或规格的 stock 应始终等于其流水之和
+CREATE TABLE IF NOT EXISTS stock_movements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tenant_id INTEGER NOT NULL REFERENCES tenants(id),
+    product_id INTEGER NOT NULL REFERENCES products(id) ON DELETE CASCADE,
+    variant_id INTEGER REFERENCES product_variants(id) ON DELETE CASCADE,
+    movement_type TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    stock_after INTEGER NOT NULL,
+    reference TEXT,
+    order_id INTEGER REFERENCES orders(id) ON DELETE SET NULL,
+    actor_id INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    note TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_product ON stock_movements(product_id, variant_id);
+CREATE INDEX IF NOT EXISTS idx_stock_movements_order ON stock_movements(order_id);
+
+-- 升级前的库存没有来历，记为一笔期初调整，之后的对账才能平衡
+INSERT INTO stock_movements (tenant_id, product_id, movement_type, quantity, stock_after, note)
+SELECT tenant_id, id, 'adjustment', stock, stock, '期初库存' FROM products WHERE stock <> 0;
+
+INSERT INTO stock_movements (tenant_id, product_id, variant_id, movement_type, quantity, stock_after, note)
+SELECT tenant_id, product_id, id, 'adjustment', stock, stock, '期初库存' FROM product_variants WHERE stock <> 0;
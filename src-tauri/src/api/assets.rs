@@ -0,0 +1,40 @@
+use std::path::Path;
+use tauri::State;
+use crate::AppState;
+use crate::api::ApiResponse;
+use crate::api::auth::require_permission;
+use crate::models::asset::{Asset, AssetCleanup};
+use crate::services::AssetService;
+
+/// 导入商品图片，返回保存到商品 images 或规格 image 中的引用
+#[tauri::command]
+pub async fn import_product_image(
+    state: State<'_, AppState>,
+    token: String,
+    path: String,
+) -> Result<ApiResponse<Asset>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "product:write").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let asset_dir = db.asset_dir();
+    ApiResponse::from_result(AssetService::new(&db.pool, &asset_dir).import(Path::new(&path)).await)
+}
+
+/// 立即清理不再被商品引用的图片，不必等待每天的自动清理
+#[tauri::command]
+pub async fn cleanup_assets(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<AssetCleanup>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let asset_dir = db.asset_dir();
+    ApiResponse::from_result(AssetService::new(&db.pool, &asset_dir).collect_garbage().await)
+}
@@ -1,125 +1,28 @@
 use tauri::State;
-use crate::{AppState, models::user::*, models::role::Role, utils::permissions::Permission};
+use crate::{AppState, models::user::*};
+use crate::utils::permissions::{CompiledPermissions, Permission, PermissionCache};
+use crate::utils::masking::{mask_text, PiiPolicy};
 use crate::api::ApiResponse;
-use chrono::{Duration, Utc};
 use crate::database::Database;
+use crate::repositories::{RoleRepository, UserRepository};
+use crate::services::{AuthService, TenantService};
+use std::sync::Arc;
 
 #[tauri::command]
 pub async fn login(
     state: State<'_, AppState>,
     request: LoginRequest,
 ) -> Result<ApiResponse<LoginResponse>, String> {
-    println!("Login attempt for username: {}", request.username);
+    println!("Login attempt for username: {}", mask_text(&request.username));
     
-    // 获取数据库连接
-    let db = state.db.lock().await;
-    
-    // 获取系统设置
-    let max_attempts = get_system_setting(&db, "max_login_attempts", "5").await.parse::<i32>().unwrap_or(5);
-    let lockout_duration = get_system_setting(&db, "lockout_duration", "300").await.parse::<i64>().unwrap_or(300);
-    let reset_attempts_after = get_system_setting(&db, "reset_attempts_after", "3600").await.parse::<i64>().unwrap_or(3600);
-    
-    // 查找用户
-    let user_result = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE username = ? OR email = ?"
-    )
-    .bind(&request.username)
-    .bind(&request.username)
-    .fetch_optional(&db.pool)
-    .await
-    .map_err(|e| {
-        println!("Database error: {}", e);
-        e.to_string()
-    })?;
-
-    let mut user = match user_result {
-        Some(user) => user,
-        None => {
-            println!("User not found: {}", request.username);
-            return Ok(ApiResponse::error("用户名或密码错误".to_string()));
-        }
-    };
-
-    // 检查用户是否被锁定
-    if let Some(locked_until) = user.locked_until {
-        if Utc::now() < locked_until {
-            let remaining_seconds = (locked_until - Utc::now()).num_seconds();
-            return Ok(ApiResponse::error(format!("账户已被锁定，请在 {} 分钟后重试", remaining_seconds / 60)));
-        } else {
-            // 解锁用户
-            user.locked_until = None;
-            user.failed_login_attempts = 0;
-            update_user_login_status(&db, &user).await?;
-        }
-    }
-
-    // 检查是否需要重置登录尝试次数
-    if let Some(last_failed) = user.last_failed_login {
-        if (Utc::now() - last_failed).num_seconds() > reset_attempts_after {
-            user.failed_login_attempts = 0;
-            update_user_login_status(&db, &user).await?;
-        }
-    }
+    let db = &state.db;
 
-    // 验证密码
-    let password_valid = bcrypt::verify(&request.password, &user.password_hash)
-        .map_err(|e| e.to_string())?;
-
-    if !password_valid {
-        // 密码错误，增加失败次数
-        user.failed_login_attempts += 1;
-        user.last_failed_login = Some(Utc::now());
-
-        // 检查是否需要锁定账户
-        if user.failed_login_attempts >= max_attempts {
-            user.locked_until = Some(Utc::now() + Duration::seconds(lockout_duration));
-            update_user_login_status(&db, &user).await?;
-            return Ok(ApiResponse::error(format!("登录失败次数过多，账户已被锁定 {} 分钟", lockout_duration / 60)));
-        } else {
-            update_user_login_status(&db, &user).await?;
-            let remaining_attempts = max_attempts - user.failed_login_attempts;
-            return Ok(ApiResponse::error(format!("用户名或密码错误，还有 {} 次尝试机会", remaining_attempts)));
-        }
-    }
-
-    // 登录成功，重置失败次数
-    user.failed_login_attempts = 0;
-    user.last_failed_login = None;
-    user.locked_until = None;
-    user.last_login = Some(Utc::now());
-    update_user_login_status(&db, &user).await?;
-
-    // 获取用户角色
-    let role = sqlx::query_as::<_, Role>(
-        "SELECT * FROM roles WHERE id = ?"
-    )
-    .bind(user.role_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    // 创建用户响应
-    let user_response = UserResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        phone: user.phone,
-        address: user.address,
-        avatar: user.avatar,
-        role_id: user.role_id,
-        role_name: role.name,
-        permissions: role.permissions,
-        status: user.status,
-        created_at: user.created_at,
-        updated_at: user.updated_at,
-    };
-
-    let login_response = LoginResponse {
+    let result = AuthService::new(&db.pool).login(&request).await.map(|user| LoginResponse {
         token: format!("mock_token_{}", user.id),
-        user: user_response,
-    };
+        user,
+    });
 
-    Ok(ApiResponse::success(login_response))
+    ApiResponse::from_result(result)
 }
 
 #[tauri::command]
@@ -127,83 +30,85 @@ pub async fn logout() -> Result<ApiResponse<()>, String> {
     Ok(ApiResponse::success(()))
 }
 
+/// 当前登录用户自己的资料；用户 ID 取自 token，不能查询其他用户
 #[tauri::command]
 pub async fn get_current_user(
     state: State<'_, AppState>,
-    user_id: i64,
+    token: String,
 ) -> Result<ApiResponse<UserResponse>, String> {
-    let db = state.db.lock().await;
-    
-    let user = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE id = ?"
-    )
-    .bind(user_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let role = sqlx::query_as::<_, Role>(
-        "SELECT * FROM roles WHERE id = ?"
-    )
-    .bind(user.role_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let user_response = UserResponse {
-        id: user.id,
-        username: user.username,
-        email: user.email,
-        phone: user.phone,
-        address: user.address,
-        avatar: user.avatar,
-        role_id: user.role_id,
-        role_name: role.name,
-        permissions: role.permissions,
-        status: user.status,
-        created_at: user.created_at,
-        updated_at: user.updated_at,
+    let db = &state.db;
+    let user_id = match parse_token(&token) {
+        Some(user_id) => user_id,
+        None => return Ok(ApiResponse::error("无效的登录凭证".to_string())),
     };
 
-    Ok(ApiResponse::success(user_response))
+    ApiResponse::from_result(AuthService::new(&db.pool).profile(user_id).await)
+}
+
+// 辅助函数：从 token 中解析用户 ID
+pub fn parse_token(token: &str) -> Option<i64> {
+    token.strip_prefix("mock_token_")?.parse::<i64>().ok()
+}
+
+// 辅助函数：获取 token 对应用户的权限（按角色缓存编译结果）
+pub async fn get_token_permissions(
+    db: &Database,
+    cache: &PermissionCache,
+    token: &str,
+) -> Result<Arc<CompiledPermissions>, String> {
+    let user_id = parse_token(token).ok_or_else(|| "无效的登录凭证".to_string())?;
+
+    let role_id = UserRepository::new(&db.pool)
+        .active_role_id(user_id)
+        .await?
+        .ok_or_else(|| "用户不存在或已被禁用".to_string())?;
+
+    if let Some(permissions) = cache.get(role_id) {
+        return Ok(permissions);
+    }
+
+    let permissions_json = RoleRepository::new(&db.pool)
+        .permissions(role_id)
+        .await?
+        .unwrap_or_else(|| "[]".to_string());
+
+    Ok(cache.insert(role_id, &permissions_json))
 }
 
-// 辅助函数：更新用户登录状态
-async fn update_user_login_status(
-    db: &Database, 
-    user: &User
+// 辅助函数：校验当前用户是否拥有指定权限
+pub async fn require_permission(
+    db: &Database,
+    cache: &PermissionCache,
+    token: &str,
+    permission: &str,
 ) -> Result<(), String> {
-    sqlx::query(
-        r#"
-        UPDATE users 
-        SET failed_login_attempts = ?, last_failed_login = ?, locked_until = ?, last_login = ?
-        WHERE id = ?
-        "#
-    )
-    .bind(user.failed_login_attempts)
-    .bind(user.last_failed_login)
-    .bind(user.locked_until)
-    .bind(user.last_login)
-    .bind(user.id)
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(())
+    let permissions = get_token_permissions(db, cache, token).await?;
+    if permissions.allows(permission) {
+        Ok(())
+    } else {
+        Err("没有权限执行此操作".to_string())
+    }
+}
+
+// 辅助函数：获取 token 对应用户当前所在的店铺，业务数据的读写都限定在该店铺内
+pub async fn get_current_tenant(db: &Database, token: &str) -> Result<i64, String> {
+    let user_id = parse_token(token).ok_or_else(|| "无效的登录凭证".to_string())?;
+
+    Ok(TenantService::new(&db.pool).current(user_id).await?)
 }
 
-// 辅助函数：获取系统设置
-async fn get_system_setting(db: &Database, key: &str, default: &str) -> String {
-    let result = sqlx::query_scalar::<_, String>(
-        "SELECT setting_value FROM system_settings WHERE setting_key = ?"
-    )
-    .bind(key)
-    .fetch_optional(&db.pool)
-    .await;
-
-    match result {
-        Ok(Some(value)) => value,
-        _ => default.to_string(),
+// 辅助函数：获取 token 对应用户的个人信息脱敏策略，无法识别身份时默认脱敏
+pub async fn get_pii_policy(
+    db: &Database,
+    cache: &PermissionCache,
+    token: Option<&str>,
+) -> PiiPolicy {
+    match token {
+        Some(token) => match get_token_permissions(db, cache, token).await {
+            Ok(permissions) => PiiPolicy::from_permissions(&permissions),
+            Err(_) => PiiPolicy::masked(),
+        },
+        None => PiiPolicy::masked(),
     }
 }
 
@@ -220,26 +125,17 @@ pub async fn get_user_permissions(
     state: State<'_, AppState>,
     user_id: i64,
 ) -> Result<ApiResponse<crate::api::PaginatedResponse<String>>, String> {
-    let db = state.db.lock().await;
+    let db = &state.db;
     
-    let user = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE id = ?"
-    )
-    .bind(user_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let role = sqlx::query_as::<_, Role>(
-        "SELECT * FROM roles WHERE id = ?"
-    )
-    .bind(user.role_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    // 解析权限
-    let user_permissions = Permission::get_permissions(&role.permissions);
+    let user = UserRepository::new(&db.pool).find(user_id).await?;
+    let role = RoleRepository::new(&db.pool).find(user.role_id).await?;
+
+    // 解析权限（优先使用缓存的编译结果）
+    let user_permissions = state
+        .permission_cache
+        .get(role.id)
+        .unwrap_or_else(|| state.permission_cache.insert(role.id, &role.permissions))
+        .expand();
     let permissions: Vec<String> = user_permissions.into_iter().collect();
 
     Ok(ApiResponse::success(crate::api::PaginatedResponse {
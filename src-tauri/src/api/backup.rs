@@ -0,0 +1,100 @@
+use std::time::Duration;
+use tauri::{AppHandle, State};
+use crate::AppState;
+use crate::api::ApiResponse;
+use crate::api::auth::require_permission;
+use crate::database::backup::{self, BackupInfo, BackupOptions, BackupSettings, RestoreResult};
+
+#[tauri::command]
+pub async fn create_backup(
+    state: State<'_, AppState>,
+    token: String,
+    options: BackupOptions,
+) -> Result<ApiResponse<BackupInfo>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match backup::create_backup(db, &options).await {
+        Ok(info) => Ok(ApiResponse::success(info)),
+        Err(e) => Ok(ApiResponse::error(format!("备份失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn list_backups(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<BackupInfo>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let backups = backup::list_backups(db).map_err(|e| e.to_string())?;
+
+    Ok(ApiResponse::success(backups))
+}
+
+#[tauri::command]
+pub async fn restore_backup(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    token: String,
+    file_name: String,
+    passphrase: Option<String>,
+) -> Result<ApiResponse<RestoreResult>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match backup::restore_backup(db, &file_name, passphrase.as_deref()).await {
+        Ok(result) => {
+            // 数据已整体替换，稍后重启应用以重新加载权限缓存等内存状态
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                app_handle.restart();
+            });
+            Ok(ApiResponse::success(result))
+        }
+        Err(e) => Ok(ApiResponse::error(format!("恢复失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_backup_settings(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<BackupSettings>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let settings = backup::load_settings(db).await.map_err(|e| e.to_string())?;
+
+    Ok(ApiResponse::success(settings))
+}
+
+#[tauri::command]
+pub async fn save_backup_settings(
+    state: State<'_, AppState>,
+    token: String,
+    settings: BackupSettings,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    backup::save_settings(db, &settings).await.map_err(|e| e.to_string())?;
+
+    Ok(ApiResponse::success(()))
+}
@@ -1,32 +1,29 @@
 use tauri::State;
 use crate::{AppState, models::log::*, models::user::LoginAttempt};
 use crate::api::{ApiResponse, PaginatedResponse};
+use crate::api::auth::{get_pii_policy, require_permission};
+use crate::repositories::LogRepository;
+use crate::services::LogRetentionService;
+use crate::transaction;
 
 #[tauri::command]
 pub async fn get_logs(
     state: State<'_, AppState>,
     page: Option<i32>,
     per_page: Option<i32>,
+    token: Option<String>,
 ) -> Result<ApiResponse<PaginatedResponse<Log>>, String> {
     let page = page.unwrap_or(1);
     let per_page = per_page.unwrap_or(10);
     let offset = (page - 1) * per_page;
-    
-    let db = state.db.lock().await;
-
-    let logs = sqlx::query_as::<_, Log>(
-        "SELECT * FROM logs ORDER BY created_at DESC LIMIT ? OFFSET ?"
-    )
-    .bind(per_page)
-    .bind(offset)
-    .fetch_all(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM logs")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+
+    let db = &state.db;
+    let mut repository = LogRepository::new(&db.pool);
+
+    let mut logs = repository.list(per_page, offset).await?;
+    get_pii_policy(db, &state.permission_cache, token.as_deref()).await.apply_all(&mut logs);
+
+    let total = repository.count().await?;
 
     let response = PaginatedResponse {
         items: logs,
@@ -44,58 +41,33 @@ pub async fn create_log(
     state: State<'_, AppState>,
     request: CreateLogRequest,
 ) -> Result<ApiResponse<Log>, String> {
-    let db = state.db.lock().await;
-    
-    let result = sqlx::query(
-        "INSERT INTO logs (user_id, action, description, ip_address, created_at) VALUES (?, ?, ?, ?, ?)"
-    )
-    .bind(request.user_id)
-    .bind(&request.action)
-    .bind(&request.description)
-    .bind(&request.ip_address)
-    .bind(chrono::Utc::now())
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let log = sqlx::query_as::<_, Log>(
-        "SELECT * FROM logs WHERE id = ?"
-    )
-    .bind(result.last_insert_rowid())
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(log))
-} 
+    let db = &state.db;
+
+    let result = transaction!(db.pool, |tx| LogRepository::new(&mut tx).create(&request).await);
+
+    ApiResponse::from_result(result)
+}
 
 #[tauri::command]
 pub async fn get_login_logs(
     state: State<'_, AppState>,
     page: Option<i32>,
     per_page: Option<i32>,
+    token: Option<String>,
 ) -> Result<ApiResponse<PaginatedResponse<LoginAttempt>>, String> {
     let page = page.unwrap_or(1);
     let per_page = per_page.unwrap_or(10);
     let offset = (page - 1) * per_page;
 
-    let db = state.db.lock().await;
+    let db = &state.db;
+    let mut repository = LogRepository::new(&db.pool);
 
     // 获取总数
-    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM login_logs")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let total = repository.count_login_attempts().await?;
 
     // 获取日志数据
-    let logs = sqlx::query_as::<_, LoginAttempt>(
-        "SELECT * FROM login_logs ORDER BY created_at DESC LIMIT ? OFFSET ?"
-    )
-    .bind(per_page)
-    .bind(offset)
-    .fetch_all(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let mut logs = repository.list_login_attempts(per_page, offset).await?;
+    get_pii_policy(db, &state.permission_cache, token.as_deref()).await.apply_all(&mut logs);
 
     let total_pages = (total as f64 / per_page as f64).ceil() as i32;
 
@@ -106,4 +78,65 @@ pub async fn get_login_logs(
         per_page,
         total_pages: total_pages.into(),
     }))
-} 
\ No newline at end of file
+}
+
+#[tauri::command]
+pub async fn get_log_retention_status(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<LogRetentionStatus>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let archive_dir = db.archive_dir();
+    ApiResponse::from_result(LogRetentionService::new(&db.pool, &archive_dir).status().await)
+}
+
+#[tauri::command]
+pub async fn run_log_retention(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<LogRetentionStatus>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let archive_dir = db.archive_dir();
+    ApiResponse::from_result(LogRetentionService::new(&db.pool, &archive_dir).run().await)
+}
+
+#[tauri::command]
+pub async fn get_log_retention_settings(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<LogRetentionSettings>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let archive_dir = db.archive_dir();
+    ApiResponse::from_result(LogRetentionService::new(&db.pool, &archive_dir).settings().await)
+}
+
+#[tauri::command]
+pub async fn save_log_retention_settings(
+    state: State<'_, AppState>,
+    token: String,
+    settings: LogRetentionSettings,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let archive_dir = db.archive_dir();
+    ApiResponse::from_result(LogRetentionService::new(&db.pool, &archive_dir).save_settings(&settings).await)
+}
@@ -0,0 +1,152 @@
+use tauri::State;
+use crate::AppState;
+use crate::api::ApiResponse;
+use crate::api::auth::require_permission;
+use crate::database::health::{self, CheckpointResult, DatabaseStats, IntegrityReport, MaintenanceResult};
+use crate::database::migrations::{self, MigrationStatus};
+use crate::database::seed::{self, SeedOptions, SeedSummary};
+
+#[tauri::command]
+pub async fn get_migration_status(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<MigrationStatus>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let status = migrations::migration_status(&db.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ApiResponse::success(status))
+}
+
+#[tauri::command]
+pub async fn rollback_migration(
+    state: State<'_, AppState>,
+    token: String,
+    target_version: i64,
+) -> Result<ApiResponse<Vec<MigrationStatus>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    if let Err(e) = migrations::rollback_to(&db.pool, target_version).await {
+        return Ok(ApiResponse::error(e.to_string()));
+    }
+
+    let status = migrations::migration_status(&db.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(ApiResponse::success(status))
+}
+
+#[tauri::command]
+pub async fn check_database_integrity(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<IntegrityReport>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match health::integrity_check(db).await {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(format!("完整性检查失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn vacuum_database(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<MaintenanceResult>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match health::vacuum(db).await {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(format!("整理数据库失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn analyze_database(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<MaintenanceResult>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match health::analyze(db).await {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(format!("更新统计信息失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn checkpoint_database(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<CheckpointResult>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match health::wal_checkpoint(db).await {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(format!("WAL 检查点失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_database_stats(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<DatabaseStats>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match health::database_stats(db).await {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(format!("获取数据库统计失败: {}", e))),
+    }
+}
+
+/// 生成演示数据，数量较大时会持续较长时间
+#[tauri::command]
+pub async fn seed_demo_data(
+    state: State<'_, AppState>,
+    token: String,
+    options: Option<SeedOptions>,
+) -> Result<ApiResponse<SeedSummary>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    match seed::seed_demo_data(&db.pool, &options.unwrap_or_default()).await {
+        Ok(summary) => Ok(ApiResponse::success(summary)),
+        Err(e) => Ok(ApiResponse::error(format!("生成演示数据失败: {}", e))),
+    }
+}
@@ -0,0 +1,70 @@
+use tauri::State;
+use crate::{AppState, models::menu::*};
+use crate::api::ApiResponse;
+use crate::api::auth::{get_token_permissions, require_permission};
+use crate::services::MenuService;
+
+#[tauri::command]
+pub async fn get_menu_tree(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<MenuTreeNode>>, String> {
+    let db = &state.db;
+
+    let permissions = match get_token_permissions(db, &state.permission_cache, &token).await {
+        Ok(permissions) => permissions,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    let tree = MenuService::new(&db.pool).tree(Some(permissions.as_ref())).await?;
+
+    Ok(ApiResponse::success(tree))
+}
+
+#[tauri::command]
+pub async fn get_all_menus(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<MenuTreeNode>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "menu:read").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    // 管理视图包含隐藏菜单，不做权限过滤
+    let tree = MenuService::new(&db.pool).tree(None).await?;
+
+    Ok(ApiResponse::success(tree))
+}
+
+#[tauri::command]
+pub async fn update_menu(
+    state: State<'_, AppState>,
+    token: String,
+    id: i64,
+    request: UpdateMenuRequest,
+) -> Result<ApiResponse<Menu>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "menu:write").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(MenuService::new(&db.pool).update(id, &request).await)
+}
+
+#[tauri::command]
+pub async fn sort_menus(
+    state: State<'_, AppState>,
+    token: String,
+    items: Vec<MenuSortItem>,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "menu:write").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(MenuService::new(&db.pool).sort(&items).await)
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::error::AppError;
 
 pub mod auth;
 pub mod users;
@@ -9,12 +10,23 @@ pub mod roles;
 pub mod settings;
 pub mod stats;
 pub mod logs;
+pub mod menus;
+pub mod maintenance;
+pub mod backup;
+pub mod recycle_bin;
+pub mod tenants;
+pub mod sync;
+pub mod stock;
+pub mod assets;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ApiResponse<T> {
     pub success: bool,
     pub message: String,
     pub data: Option<T>,
+    /// 版本冲突时服务端的最新数据，前端据此提示合并
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current: Option<serde_json::Value>,
 }
 
 impl<T> ApiResponse<T> {
@@ -23,17 +35,32 @@ impl<T> ApiResponse<T> {
             success: true,
             message: "操作成功".to_string(),
             data: Some(data),
+            current: None,
         }
     }
 
-    pub fn error(message: String) -> Self
-    where
-        T: Default,
-    {
+    pub fn error(message: String) -> Self {
         Self {
             success: false,
             message,
             data: None,
+            current: None,
+        }
+    }
+
+    /// 把服务层的结果转换为命令返回值：业务错误作为失败响应交给前端提示，其余错误作为命令错误
+    pub fn from_result(result: Result<T, AppError>) -> Result<Self, String> {
+        match result {
+            Ok(data) => Ok(Self::success(data)),
+            Err(e) if e.is_business() => {
+                let message = e.to_string();
+                let current = match e {
+                    AppError::VersionConflict(current) => Some(current),
+                    _ => None,
+                };
+                Ok(Self { current, ..Self::error(message) })
+            }
+            Err(e) => Err(e.to_string()),
         }
     }
 }
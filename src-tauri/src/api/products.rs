@@ -1,58 +1,45 @@
 use tauri::State;
-use sqlx::Row;
 use crate::{AppState, models::product::*};
 use crate::api::{ApiResponse, PaginatedResponse};
+use crate::api::auth::{get_current_tenant, parse_token};
+use crate::repositories::ProductRepository;
+use crate::services::ProductService;
+use crate::utils::search::SearchQuery;
 
 #[tauri::command]
 pub async fn get_products(
     state: State<'_, AppState>,
+    token: String,
     page: Option<i32>,
     per_page: Option<i32>,
-    _search: Option<String>,
+    search: Option<String>,
+    filter: Option<ProductQuery>,
 ) -> Result<ApiResponse<PaginatedResponse<ProductWithCategory>>, String> {
     let page = page.unwrap_or(1);
     let per_page = per_page.unwrap_or(10);
     let offset = (page - 1) * per_page;
-    
-    let db = state.db.lock().await;
 
-    let base_query = r#"
-        SELECT p.id, p.name, p.price, p.description, p.category_id, c.name as category_name,
-               p.stock, p.images, p.status, p.created_at, p.updated_at
-        FROM products p
-        LEFT JOIN categories c ON p.category_id = c.id
-        ORDER BY p.created_at DESC
-        LIMIT ? OFFSET ?
-    "#;
-
-    let products = sqlx::query(base_query)
-        .bind(per_page)
-        .bind(offset)
-        .fetch_all(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let search = search.as_deref().and_then(SearchQuery::parse);
+    let filter = filter.unwrap_or_default();
+    let mut products = ProductRepository::new(&db.pool);
 
-    let product_list: Vec<ProductWithCategory> = products
-        .into_iter()
-        .map(|row| ProductWithCategory {
-            id: row.get("id"),
-            name: row.get("name"),
-            price: row.get("price"),
-            description: row.get("description"),
-            category_id: row.get("category_id"),
-            category_name: row.get("category_name"),
-            stock: row.get("stock"),
-            images: row.get("images"),
-            status: row.get("status"),
-            created_at: row.get("created_at"),
-            updated_at: row.get("updated_at"),
-        })
-        .collect();
+    let mut product_list = products.list(tenant_id, search.as_ref(), &filter, per_page, offset).await?;
+    let total = products.count(tenant_id, search.as_ref(), Some(&filter)).await?;
 
-    let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM products")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    if let Some(search) = &search {
+        for product in &mut product_list {
+            product.highlights = search.highlight_fields(&[
+                ("name", Some(product.name.as_str())),
+                ("description", product.description.as_deref()),
+                ("category_name", product.category_name.as_deref()),
+            ]);
+        }
+    }
 
     let response = PaginatedResponse {
         items: product_list,
@@ -68,90 +55,64 @@ pub async fn get_products(
 #[tauri::command]
 pub async fn create_product(
     state: State<'_, AppState>,
+    token: String,
     request: CreateProductRequest,
 ) -> Result<ApiResponse<Product>, String> {
-    let db = state.db.lock().await;
-    
-    // 将图片数组序列化为JSON字符串
-    let images_json = serde_json::to_string(&request.images).unwrap_or_else(|_| "[]".to_string());
-    
-    let result = sqlx::query(
-        "INSERT INTO products (name, price, description, category_id, stock, images, status, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
-    )
-    .bind(&request.name)
-    .bind(request.price)
-    .bind(&request.description)
-    .bind(request.category_id)
-    .bind(request.stock)
-    .bind(&images_json)
-    .bind(1) // 默认状态为启用
-    .bind(chrono::Utc::now())
-    .bind(chrono::Utc::now())
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let product = sqlx::query_as::<_, Product>(
-        "SELECT * FROM products WHERE id = ?"
-    )
-    .bind(result.last_insert_rowid())
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let actor_id = parse_token(&token);
 
-    Ok(ApiResponse::success(product))
+    ApiResponse::from_result(ProductService::new(&db.pool).create(tenant_id, actor_id, &request).await)
 }
 
 #[tauri::command]
 pub async fn update_product(
     state: State<'_, AppState>,
+    token: String,
     id: i64,
     request: UpdateProductRequest,
 ) -> Result<ApiResponse<Product>, String> {
-    let db = state.db.lock().await;
-    
-    // 将图片数组序列化为JSON字符串
-    let images_json = serde_json::to_string(&request.images).unwrap_or_else(|_| "[]".to_string());
-    
-    sqlx::query(
-        "UPDATE products SET name = ?, price = ?, description = ?, category_id = ?, stock = ?, images = ?, status = ?, updated_at = ? WHERE id = ?"
-    )
-    .bind(&request.name)
-    .bind(request.price)
-    .bind(&request.description)
-    .bind(request.category_id)
-    .bind(request.stock)
-    .bind(&images_json)
-    .bind(request.status)
-    .bind(chrono::Utc::now())
-    .bind(id)
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let actor_id = parse_token(&token);
+
+    ApiResponse::from_result(ProductService::new(&db.pool).update(tenant_id, actor_id, id, &request).await)
+}
 
-    let product = sqlx::query_as::<_, Product>(
-        "SELECT * FROM products WHERE id = ?"
-    )
-    .bind(id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+/// 商品的规格项定义和全部规格，新增和修改规格随 `create_product`、`update_product` 一起提交
+#[tauri::command]
+pub async fn get_product_variants(
+    state: State<'_, AppState>,
+    token: String,
+    product_id: i64,
+) -> Result<ApiResponse<ProductVariants>, String> {
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
 
-    Ok(ApiResponse::success(product))
+    ApiResponse::from_result(ProductService::new(&db.pool).variants(tenant_id, product_id).await)
 }
 
 #[tauri::command]
 pub async fn delete_product(
     state: State<'_, AppState>,
     id: i64,
+    token: String,
 ) -> Result<ApiResponse<()>, String> {
-    let db = state.db.lock().await;
-    
-    sqlx::query("DELETE FROM products WHERE id = ?")
-        .bind(id)
-        .execute(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let deleted_by = parse_token(&token);
 
-    Ok(ApiResponse::success(()))
-} 
\ No newline at end of file
+    ApiResponse::from_result(ProductRepository::new(&db.pool).delete(tenant_id, id, deleted_by).await)
+}
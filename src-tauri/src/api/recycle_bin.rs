@@ -0,0 +1,102 @@
+use tauri::State;
+use crate::{AppState, models::recycle_bin::*};
+use crate::api::ApiResponse;
+use crate::api::auth::{get_current_tenant, get_token_permissions, require_permission};
+use crate::services::RecycleBinService;
+
+#[tauri::command]
+pub async fn get_recycle_bin(
+    state: State<'_, AppState>,
+    token: String,
+    entity: Option<RecycleBinEntity>,
+) -> Result<ApiResponse<Vec<RecycleBinItem>>, String> {
+    let db = &state.db;
+
+    let permissions = match get_token_permissions(db, &state.permission_cache, &token).await {
+        Ok(permissions) => permissions,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    // 只列出当前用户有写权限的数据类型
+    let entities: Vec<RecycleBinEntity> = RecycleBinEntity::ALL
+        .into_iter()
+        .filter(|e| entity.is_none_or(|requested| requested == *e))
+        .filter(|e| permissions.allows(e.write_permission()))
+        .collect();
+    if entities.is_empty() {
+        return Ok(ApiResponse::error("没有权限执行此操作".to_string()));
+    }
+
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    let items = RecycleBinService::new(&db.pool).list(tenant_id, &entities).await?;
+
+    Ok(ApiResponse::success(items))
+}
+
+#[tauri::command]
+pub async fn restore_deleted(
+    state: State<'_, AppState>,
+    token: String,
+    entity: RecycleBinEntity,
+    id: i64,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, entity.write_permission()).await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    ApiResponse::from_result(RecycleBinService::new(&db.pool).restore(tenant_id, entity, id).await)
+}
+
+#[tauri::command]
+pub async fn purge_recycle_bin(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<PurgeResult>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(RecycleBinService::new(&db.pool).purge_expired().await)
+}
+
+#[tauri::command]
+pub async fn get_recycle_bin_settings(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<RecycleBinSettings>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(RecycleBinService::new(&db.pool).settings().await)
+}
+
+#[tauri::command]
+pub async fn save_recycle_bin_settings(
+    state: State<'_, AppState>,
+    token: String,
+    settings: RecycleBinSettings,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(RecycleBinService::new(&db.pool).save_settings(&settings).await)
+}
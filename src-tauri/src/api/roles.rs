@@ -1,19 +1,45 @@
 use tauri::State;
-use crate::{AppState, models::role::Role};
+use crate::{AppState, models::role::*};
 use crate::api::ApiResponse;
+use crate::api::auth::require_permission;
+use crate::repositories::RoleRepository;
+use crate::transaction;
 
 #[tauri::command]
 pub async fn get_roles(
     state: State<'_, AppState>,
 ) -> Result<ApiResponse<Vec<Role>>, String> {
-    let db = state.db.lock().await;
+    let db = &state.db;
     
-    let roles = sqlx::query_as::<_, Role>(
-        "SELECT * FROM roles ORDER BY id"
-    )
-    .fetch_all(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let roles = RoleRepository::new(&db.pool).list().await?;
 
     Ok(ApiResponse::success(roles))
-} 
\ No newline at end of file
+} 
+
+#[tauri::command]
+pub async fn update_role(
+    state: State<'_, AppState>,
+    token: String,
+    id: i64,
+    request: UpdateRoleRequest,
+) -> Result<ApiResponse<Role>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "role:write").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    // 禁止修改超级管理员角色，避免所有人失去管理权限
+    if id == 1 {
+        return Ok(ApiResponse::error("超级管理员角色不可修改".to_string()));
+    }
+
+    let result = transaction!(db.pool, |tx| RoleRepository::new(&mut tx).update(id, &request).await);
+
+    // 角色权限已变化，清除缓存的编译结果
+    if result.is_ok() {
+        state.permission_cache.invalidate(id);
+    }
+
+    ApiResponse::from_result(result)
+}
\ No newline at end of file
@@ -1,6 +1,10 @@
 use tauri::State;
-use crate::{AppState, models::settings::SystemSetting};
+use crate::AppState;
+use crate::models::money::{currency_digits, MoneySettings};
+use crate::repositories::SettingRepository;
+use crate::transaction;
 use crate::api::ApiResponse;
+use crate::api::auth::{get_current_tenant, require_permission};
 use serde::{Deserialize, Serialize};
 
 // 定义响应类型
@@ -9,6 +13,7 @@ pub struct SystemSettingsResponse {
     pub system: SystemSettings,
     pub ui: UISettings,
     pub security: SecuritySettings,
+    pub money: MoneySettings,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,15 +40,16 @@ pub struct SecuritySettings {
 #[tauri::command]
 pub async fn get_all_settings(
     state: State<'_, AppState>,
+    token: String,
 ) -> Result<ApiResponse<SystemSettingsResponse>, String> {
-    let db = state.db.lock().await;
-    
-    let settings = sqlx::query_as::<_, SystemSetting>(
-        "SELECT * FROM system_settings ORDER BY setting_key"
-    )
-    .fetch_all(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    // 店铺自己的设置覆盖全局设置
+    let settings = SettingRepository::new(&db.pool).list_for_tenant(tenant_id).await?;
 
     // 将设置转换为结构化格式
     let mut system = SystemSettings {
@@ -64,6 +70,8 @@ pub async fn get_all_settings(
         reset_attempts_after: 3600,
     };
 
+    let mut money = MoneySettings::default();
+
     // 从数据库设置中填充值
     for setting in settings {
         match setting.setting_key.as_str() {
@@ -76,6 +84,8 @@ pub async fn get_all_settings(
             "max_login_attempts" => security.max_login_attempts = setting.setting_value.parse().unwrap_or(5),
             "lockout_duration" => security.lockout_duration = setting.setting_value.parse().unwrap_or(300),
             "reset_attempts_after" => security.reset_attempts_after = setting.setting_value.parse().unwrap_or(3600),
+            "currency" => money.currency = setting.setting_value,
+            "money_rounding" => money.rounding = setting.setting_value.parse().unwrap_or_default(),
             _ => {}
         }
     }
@@ -84,83 +94,56 @@ pub async fn get_all_settings(
         system,
         ui,
         security,
+        money,
     }))
 }
 
 #[tauri::command]
 pub async fn save_system_settings(
     state: State<'_, AppState>,
+    token: String,
     settings: SystemSettings,
 ) -> Result<ApiResponse<()>, String> {
-    let db = state.db.lock().await;
-
-    // 更新系统设置
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.system_name)
-    .bind("system_name")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.system_description)
-    .bind("system_description")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.system_version)
-    .bind("system_version")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(()))
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    // 更新当前店铺的系统设置，三项设置一起提交
+    let result = transaction!(db.pool, |tx| {
+        let mut repository = SettingRepository::new(&mut tx);
+        repository.set_for_tenant(tenant_id, "system_name", &settings.system_name).await?;
+        repository.set_for_tenant(tenant_id, "system_description", &settings.system_description).await?;
+        repository.set_for_tenant(tenant_id, "system_version", &settings.system_version).await?;
+        Ok(())
+    });
+
+    ApiResponse::from_result(result)
 }
 
 #[tauri::command]
 pub async fn save_ui_settings(
     state: State<'_, AppState>,
+    token: String,
     settings: UISettings,
 ) -> Result<ApiResponse<()>, String> {
-    let db = state.db.lock().await;
-
-    // 更新UI设置
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.theme_color)
-    .bind("theme_color")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.language)
-    .bind("language")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.page_size.to_string())
-    .bind("page_size")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(()))
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    // 更新当前店铺的UI设置
+    let result = transaction!(db.pool, |tx| {
+        let mut repository = SettingRepository::new(&mut tx);
+        repository.set_for_tenant(tenant_id, "theme_color", &settings.theme_color).await?;
+        repository.set_for_tenant(tenant_id, "language", &settings.language).await?;
+        repository.set_for_tenant(tenant_id, "page_size", &settings.page_size.to_string()).await?;
+        Ok(())
+    });
+
+    ApiResponse::from_result(result)
 }
 
 #[tauri::command]
@@ -168,35 +151,47 @@ pub async fn save_security_settings(
     state: State<'_, AppState>,
     settings: SecuritySettings,
 ) -> Result<ApiResponse<()>, String> {
-    let db = state.db.lock().await;
-
-    // 更新安全设置
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.max_login_attempts.to_string())
-    .bind("max_login_attempts")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.lockout_duration.to_string())
-    .bind("lockout_duration")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    sqlx::query(
-        "UPDATE system_settings SET setting_value = ? WHERE setting_key = ?"
-    )
-    .bind(&settings.reset_attempts_after.to_string())
-    .bind("reset_attempts_after")
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(()))
-} 
\ No newline at end of file
+    let db = &state.db;
+
+    // 更新安全设置，登录策略对所有店铺生效，保存为全局设置
+    let result = transaction!(db.pool, |tx| {
+        let mut repository = SettingRepository::new(&mut tx);
+        repository.set("max_login_attempts", &settings.max_login_attempts.to_string()).await?;
+        repository.set("lockout_duration", &settings.lockout_duration.to_string()).await?;
+        repository.set("reset_attempts_after", &settings.reset_attempts_after.to_string()).await?;
+        Ok(())
+    });
+
+    ApiResponse::from_result(result)
+}
+
+#[tauri::command]
+pub async fn save_money_settings(
+    state: State<'_, AppState>,
+    token: String,
+    settings: MoneySettings,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    // 币种和舍入方式影响之后所有金额的计算，需要修改设置的权限
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "settings:write").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    if currency_digits(&settings.currency).is_none() {
+        return Ok(ApiResponse::error(format!("不支持的币种: {}", settings.currency)));
+    }
+
+    // 币种只影响之后新建的商品和订单，已有数据保留原来的币种
+    let result = transaction!(db.pool, |tx| {
+        let mut repository = SettingRepository::new(&mut tx);
+        repository.set_for_tenant(tenant_id, "currency", &settings.currency).await?;
+        repository.set_for_tenant(tenant_id, "money_rounding", settings.rounding.as_str()).await?;
+        Ok(())
+    });
+
+    ApiResponse::from_result(result)
+}
@@ -1,38 +1,37 @@
 use tauri::State;
 use crate::{AppState, models::stats::*};
+use crate::repositories::{CategoryRepository, OrderRepository, ProductRepository, SettingRepository, UserRepository};
 use crate::api::ApiResponse;
+use crate::api::auth::get_current_tenant;
 
 #[tauri::command]
 pub async fn get_dashboard_stats(
     state: State<'_, AppState>,
+    token: String,
 ) -> Result<ApiResponse<DashboardStats>, String> {
-    let db = state.db.lock().await;
-    
-    let total_users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
 
-    let total_products = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM products")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    // 统计的是当前店铺的数据
+    let total_users = UserRepository::new(&db.pool).count(tenant_id, None).await?;
 
-    let total_orders = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM orders")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let total_products = ProductRepository::new(&db.pool).count(tenant_id, None, None).await?;
 
-    let total_categories = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM categories")
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+    let total_orders = OrderRepository::new(&db.pool).count(tenant_id, None).await?;
+
+    let total_categories = CategoryRepository::new(&db.pool).count(tenant_id).await?;
+
+    let total_revenue = OrderRepository::new(&db.pool).revenue(tenant_id).await?;
 
     let stats = DashboardStats {
         total_users,
         total_products,
         total_orders,
         total_categories,
+        total_revenue,
     };
 
     Ok(ApiResponse::success(stats))
@@ -41,29 +40,35 @@ pub async fn get_dashboard_stats(
 #[tauri::command]
 pub async fn get_system_info(
     state: State<'_, AppState>,
+    token: String,
 ) -> Result<ApiResponse<SystemInfo>, String> {
-    let db = state.db.lock().await;
-    
-    let system_name = sqlx::query_scalar::<_, String>(
-        "SELECT setting_value FROM system_settings WHERE setting_key = 'system_name'"
-    )
-        .fetch_one(&db.pool)
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let mut settings = SettingRepository::new(&db.pool);
+
+    let system_name = settings
+        .get_for_tenant(tenant_id, "system_name")
         .await
-        .unwrap_or_else(|_| "Rust Admin".to_string());
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "Rust Admin".to_string());
 
-    let system_version = sqlx::query_scalar::<_, String>(
-        "SELECT setting_value FROM system_settings WHERE setting_key = 'system_version'"
-    )
-        .fetch_one(&db.pool)
+    let system_version = settings
+        .get_for_tenant(tenant_id, "system_version")
         .await
-        .unwrap_or_else(|_| "1.0.0".to_string());
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "1.0.0".to_string());
 
-    let system_description = sqlx::query_scalar::<_, String>(
-        "SELECT setting_value FROM system_settings WHERE setting_key = 'system_description'"
-    )
-        .fetch_one(&db.pool)
+    let system_description = settings
+        .get_for_tenant(tenant_id, "system_description")
         .await
-        .unwrap_or_else(|_| "基于 Tauri + Vue 3 的后台管理系统".to_string());
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| "基于 Tauri + Vue 3 的后台管理系统".to_string());
 
     let info = SystemInfo {
         system_name,
@@ -74,4 +79,4 @@ pub async fn get_system_info(
     };
 
     Ok(ApiResponse::success(info))
-} 
\ No newline at end of file
+}
@@ -0,0 +1,101 @@
+use tauri::State;
+use crate::{AppState, models::stock::*};
+use crate::api::{ApiResponse, PaginatedResponse};
+use crate::api::auth::{get_current_tenant, parse_token, require_permission};
+use crate::services::StockService;
+
+/// 手工登记库存变化，必须填写原因
+#[tauri::command]
+pub async fn adjust_stock(
+    state: State<'_, AppState>,
+    token: String,
+    request: AdjustStockRequest,
+) -> Result<ApiResponse<StockMovement>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "product:write").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let actor_id = parse_token(&token);
+
+    ApiResponse::from_result(StockService::new(&db.pool).adjust(tenant_id, actor_id, &request).await)
+}
+
+/// 商品的库存流水，最新的在前；不指定规格时包括所有规格
+#[tauri::command]
+pub async fn get_stock_movements(
+    state: State<'_, AppState>,
+    token: String,
+    product_id: i64,
+    variant_id: Option<i64>,
+    page: Option<i32>,
+    per_page: Option<i32>,
+) -> Result<ApiResponse<PaginatedResponse<StockMovement>>, String> {
+    let page = page.unwrap_or(1);
+    let per_page = per_page.unwrap_or(10);
+    let offset = (page - 1) * per_page;
+
+    let db = &state.db;
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "product:read").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    let result = StockService::new(&db.pool)
+        .movements(tenant_id, product_id, variant_id, per_page, offset)
+        .await
+        .map(|(items, total)| PaginatedResponse {
+            items,
+            total,
+            page,
+            per_page,
+            total_pages: (total + per_page as i64 - 1) / per_page as i64,
+        });
+    ApiResponse::from_result(result)
+}
+
+/// 库存对账：列出库存与流水合计不一致的商品和规格
+#[tauri::command]
+pub async fn check_stock_reconciliation(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<StockDiscrepancy>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "product:read").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    ApiResponse::from_result(StockService::new(&db.pool).reconcile(tenant_id).await)
+}
+
+/// 需要补货的商品，供仪表盘展示
+#[tauri::command]
+pub async fn get_low_stock_products(
+    state: State<'_, AppState>,
+    token: String,
+    limit: Option<i32>,
+) -> Result<ApiResponse<Vec<LowStockProduct>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "product:read").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+
+    ApiResponse::from_result(StockService::new(&db.pool).low_stock(tenant_id, limit.unwrap_or(10)).await)
+}
@@ -0,0 +1,84 @@
+use tauri::State;
+use crate::{AppState, models::sync::*};
+use crate::api::ApiResponse;
+use crate::api::auth::require_permission;
+use crate::services::SyncService;
+
+/// 最多返回的冲突记录条数
+const CONFLICT_LIMIT: i64 = 200;
+
+#[tauri::command]
+pub async fn get_sync_status(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<SyncStatus>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(SyncService::new(&db.pool).status().await)
+}
+
+#[tauri::command]
+pub async fn get_sync_conflicts(
+    state: State<'_, AppState>,
+    token: String,
+    entity: Option<SyncEntity>,
+) -> Result<ApiResponse<Vec<SyncConflict>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(SyncService::new(&db.pool).conflicts(entity, CONFLICT_LIMIT).await)
+}
+
+#[tauri::command]
+pub async fn run_sync(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<SyncRunResult>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    // 连不上中心节点是常见情况，作为失败响应提示，而不是命令错误
+    match SyncService::new(&db.pool).run().await {
+        Ok(result) => Ok(ApiResponse::success(result)),
+        Err(e) => Ok(ApiResponse::error(format!("同步失败: {}", e))),
+    }
+}
+
+#[tauri::command]
+pub async fn get_sync_settings(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<SyncSettings>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(SyncService::new(&db.pool).settings().await)
+}
+
+#[tauri::command]
+pub async fn save_sync_settings(
+    state: State<'_, AppState>,
+    token: String,
+    settings: SyncSettings,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "database:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(SyncService::new(&db.pool).save_settings(&settings).await)
+}
@@ -0,0 +1,154 @@
+use tauri::State;
+use crate::{AppState, models::tenant::*};
+use crate::api::ApiResponse;
+use crate::api::auth::{get_current_tenant, parse_token, require_permission};
+use crate::services::TenantService;
+
+#[tauri::command]
+pub async fn get_my_tenants(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<UserTenant>>, String> {
+    let db = &state.db;
+    let user_id = match parse_token(&token) {
+        Some(user_id) => user_id,
+        None => return Ok(ApiResponse::error("无效的登录凭证".to_string())),
+    };
+
+    ApiResponse::from_result(TenantService::new(&db.pool).list_for_user(user_id).await)
+}
+
+#[tauri::command]
+pub async fn switch_tenant(
+    state: State<'_, AppState>,
+    token: String,
+    tenant_id: i64,
+) -> Result<ApiResponse<Tenant>, String> {
+    let db = &state.db;
+    let user_id = match parse_token(&token) {
+        Some(user_id) => user_id,
+        None => return Ok(ApiResponse::error("无效的登录凭证".to_string())),
+    };
+
+    ApiResponse::from_result(TenantService::new(&db.pool).switch(user_id, tenant_id).await)
+}
+
+#[tauri::command]
+pub async fn get_tenants(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<ApiResponse<Vec<Tenant>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(TenantService::new(&db.pool).list().await)
+}
+
+#[tauri::command]
+pub async fn create_tenant(
+    state: State<'_, AppState>,
+    token: String,
+    request: CreateTenantRequest,
+) -> Result<ApiResponse<Tenant>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let creator_id = match parse_token(&token) {
+        Some(user_id) => user_id,
+        None => return Ok(ApiResponse::error("无效的登录凭证".to_string())),
+    };
+
+    ApiResponse::from_result(TenantService::new(&db.pool).create(&request, creator_id).await)
+}
+
+#[tauri::command]
+pub async fn update_tenant(
+    state: State<'_, AppState>,
+    token: String,
+    id: i64,
+    request: UpdateTenantRequest,
+) -> Result<ApiResponse<Tenant>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(TenantService::new(&db.pool).update(id, &request).await)
+}
+
+#[tauri::command]
+pub async fn get_tenant_members(
+    state: State<'_, AppState>,
+    token: String,
+    tenant_id: i64,
+) -> Result<ApiResponse<Vec<TenantMember>>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(TenantService::new(&db.pool).members(tenant_id).await)
+}
+
+#[tauri::command]
+pub async fn add_tenant_member(
+    state: State<'_, AppState>,
+    token: String,
+    tenant_id: i64,
+    user_id: i64,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(TenantService::new(&db.pool).add_member(tenant_id, user_id).await)
+}
+
+#[tauri::command]
+pub async fn remove_tenant_member(
+    state: State<'_, AppState>,
+    token: String,
+    tenant_id: i64,
+    user_id: i64,
+) -> Result<ApiResponse<()>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+
+    ApiResponse::from_result(TenantService::new(&db.pool).remove_member(tenant_id, user_id).await)
+}
+
+/// 导出单个店铺的数据，不指定店铺时导出当前店铺
+#[tauri::command]
+pub async fn export_tenant(
+    state: State<'_, AppState>,
+    token: String,
+    tenant_id: Option<i64>,
+) -> Result<ApiResponse<TenantExportInfo>, String> {
+    let db = &state.db;
+
+    if let Err(e) = require_permission(db, &state.permission_cache, &token, "tenant:manage").await {
+        return Ok(ApiResponse::error(e));
+    }
+    let tenant_id = match tenant_id {
+        Some(tenant_id) => tenant_id,
+        None => match get_current_tenant(db, &token).await {
+            Ok(tenant_id) => tenant_id,
+            Err(e) => return Ok(ApiResponse::error(e)),
+        },
+    };
+
+    let export_dir = db.export_dir();
+    ApiResponse::from_result(TenantService::new(&db.pool).export(tenant_id, &export_dir).await)
+}
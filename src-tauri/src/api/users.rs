@@ -1,7 +1,10 @@
 use tauri::State;
-use sqlx::Row;
 use crate::{AppState, models::user::*};
 use crate::api::{ApiResponse, PaginatedResponse};
+use crate::api::auth::{get_current_tenant, get_pii_policy, parse_token};
+use crate::services::UserService;
+use crate::utils::masking::{mask_address, mask_email, mask_phone};
+use crate::utils::search::SearchQuery;
 
 #[tauri::command]
 pub async fn get_users(
@@ -9,175 +12,69 @@ pub async fn get_users(
     page: Option<i32>,
     per_page: Option<i32>,
     search: Option<String>,
+    token: String,
 ) -> Result<ApiResponse<PaginatedResponse<UserWithRole>>, String> {
     let page = page.unwrap_or(1);
     let per_page = per_page.unwrap_or(10);
     let offset = (page - 1) * per_page;
-    
-    let db = state.db.lock().await;
 
-    if let Some(search_term) = &search {
-        let search_pattern = format!("%{}%", search_term);
-        
-        let rows = sqlx::query(
-            r#"
-            SELECT u.id, u.username, u.email, u.phone, u.address, u.avatar, 
-                   u.role_id, r.name as role_name, r.permissions, u.status, u.created_at, u.updated_at
-            FROM users u
-            LEFT JOIN roles r ON u.role_id = r.id
-            WHERE u.username LIKE ? OR u.email LIKE ?
-            ORDER BY u.created_at DESC LIMIT ? OFFSET ?
-            "#
-        )
-        .bind(&search_pattern)
-        .bind(&search_pattern)
-        .bind(per_page)
-        .bind(offset)
-        .fetch_all(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        let total = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM users u LEFT JOIN roles r ON u.role_id = r.id WHERE u.username LIKE ? OR u.email LIKE ?"
-        )
-        .bind(&search_pattern)
-        .bind(&search_pattern)
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        let users: Vec<UserWithRole> = rows.into_iter().map(|row| {
-            UserWithRole {
-                id: row.get("id"),
-                username: row.get("username"),
-                email: row.get("email"),
-                phone: row.get("phone"),
-                address: row.get("address"),
-                avatar: row.get("avatar"),
-                role_id: row.get("role_id"),
-                role_name: row.get::<Option<String>, _>("role_name").unwrap_or_else(|| "未知角色".to_string()),
-                permissions: row.get::<Option<String>, _>("permissions").unwrap_or_else(|| "[]".to_string()),
-                status: row.get("status"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            }
-        }).collect();
-
-        let total_pages = (total as f64 / per_page as f64).ceil() as i64;
-
-        let response = PaginatedResponse {
-            items: users,
-            total,
-            page,
-            per_page,
-            total_pages,
-        };
-
-        Ok(ApiResponse::success(response))
-    } else {
-        let rows = sqlx::query(
-            r#"
-            SELECT u.id, u.username, u.email, u.phone, u.address, u.avatar, 
-                   u.role_id, r.name as role_name, r.permissions, u.status, u.created_at, u.updated_at
-            FROM users u
-            LEFT JOIN roles r ON u.role_id = r.id
-            ORDER BY u.created_at DESC LIMIT ? OFFSET ?
-            "#
-        )
-        .bind(per_page)
-        .bind(offset)
-        .fetch_all(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        let total = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
-            .fetch_one(&db.pool)
-            .await
-            .map_err(|e| e.to_string())?;
-
-        let users: Vec<UserWithRole> = rows.into_iter().map(|row| {
-            UserWithRole {
-                id: row.get("id"),
-                username: row.get("username"),
-                email: row.get("email"),
-                phone: row.get("phone"),
-                address: row.get("address"),
-                avatar: row.get("avatar"),
-                role_id: row.get("role_id"),
-                role_name: row.get::<Option<String>, _>("role_name").unwrap_or_else(|| "未知角色".to_string()),
-                permissions: row.get::<Option<String>, _>("permissions").unwrap_or_else(|| "[]".to_string()),
-                status: row.get("status"),
-                created_at: row.get("created_at"),
-                updated_at: row.get("updated_at"),
-            }
-        }).collect();
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let pii_policy = get_pii_policy(db, &state.permission_cache, Some(&token)).await;
+
+    let search = search.as_deref().and_then(SearchQuery::parse);
+
+    let (mut users, total) = UserService::new(&db.pool)
+        .list(tenant_id, search.as_ref(), per_page, offset)
+        .await?;
+
+    pii_policy.apply_all(&mut users);
+
+    // 在脱敏之后生成高亮，避免片段中带出完整的邮箱
+    if let Some(search) = &search {
+        for user in &mut users {
+            user.highlights = search.highlight_fields(&[
+                ("username", Some(user.username.as_str())),
+                ("email", Some(user.email.as_str())),
+            ]);
+        }
+    }
 
-        let total_pages = (total as f64 / per_page as f64).ceil() as i64;
+    let total_pages = (total as f64 / per_page as f64).ceil() as i64;
 
-        let response = PaginatedResponse {
-            items: users,
-            total,
-            page,
-            per_page,
-            total_pages,
-        };
+    let response = PaginatedResponse {
+        items: users,
+        total,
+        page,
+        per_page,
+        total_pages,
+    };
 
-        Ok(ApiResponse::success(response))
-    }
+    Ok(ApiResponse::success(response))
 }
 
 #[tauri::command]
 pub async fn create_user(
     state: State<'_, AppState>,
     request: CreateUserRequest,
+    token: String,
 ) -> Result<ApiResponse<User>, String> {
-    let db = state.db.lock().await;
-    
-    // 检查用户名和邮箱是否已存在
-    let existing = sqlx::query_scalar::<_, i64>(
-        "SELECT COUNT(*) FROM users WHERE username = ? OR email = ?"
-    )
-    .bind(&request.username)
-    .bind(&request.email)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    if existing > 0 {
-        return Ok(ApiResponse::error("用户名或邮箱已存在".to_string()));
-    }
-
-    // 加密密码
-    let password_hash = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)
-        .map_err(|e| e.to_string())?;
-
-    let result = sqlx::query(
-        r#"INSERT INTO users (username, email, password_hash, phone, address, avatar, role_id, status, created_at, updated_at)
-           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
-    )
-    .bind(&request.username)
-    .bind(&request.email)
-    .bind(&password_hash)
-    .bind(&request.phone)
-    .bind(&request.address)
-    .bind(&request.avatar)
-    .bind(request.role_id)
-    .bind(1) // 默认状态为启用
-    .bind(chrono::Utc::now())
-    .bind(chrono::Utc::now())
-    .execute(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    let user = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE id = ?"
-    )
-    .bind(result.last_insert_rowid())
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(user))
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let pii_policy = get_pii_policy(db, &state.permission_cache, Some(&token)).await;
+
+    let result = UserService::new(&db.pool).create(tenant_id, &request).await.map(|mut user| {
+        pii_policy.apply(&mut user);
+        user
+    });
+
+    ApiResponse::from_result(result)
 }
 
 #[tauri::command]
@@ -185,86 +82,52 @@ pub async fn update_user(
     state: State<'_, AppState>,
     user_id: i64,
     request: UpdateUserRequest,
+    token: String,
 ) -> Result<ApiResponse<User>, String> {
-    let db = state.db.lock().await;
-    
-    // 检查是否为admin用户，如果是则限制某些字段的修改
-    let current_user = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE id = ?"
-    )
-    .bind(user_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let pii_policy = get_pii_policy(db, &state.permission_cache, Some(&token)).await;
+    let service = UserService::new(&db.pool);
+
+    let result = async {
+        let current_user = service.find(tenant_id, user_id).await?;
+
+        // 没有查看权限的调用者拿到的是脱敏值，未修改的字段需要还原为原值
+        let request = UpdateUserRequest {
+            email: pii_policy.restore(&request.email, &current_user.email, mask_email),
+            phone: pii_policy.restore_opt(&request.phone, &current_user.phone, mask_phone),
+            address: pii_policy.restore_opt(&request.address, &current_user.address, mask_address),
+            ..request
+        };
 
-    if current_user.username == "admin" {
-        // 对于admin用户，只更新基本信息，不更新敏感信息
-        sqlx::query(
-            "UPDATE users SET phone = ?, address = ?, avatar = ?, updated_at = ? WHERE id = ?"
-        )
-        .bind(&request.phone)
-        .bind(&request.address)
-        .bind(&request.avatar)
-        .bind(chrono::Utc::now())
-        .bind(user_id)
-        .execute(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    } else {
-        // 对于普通用户，可以更新所有字段
-        sqlx::query(
-            "UPDATE users SET username = ?, email = ?, phone = ?, address = ?, avatar = ?, role_id = ?, updated_at = ? WHERE id = ?"
-        )
-        .bind(&request.username)
-        .bind(&request.email)
-        .bind(&request.phone)
-        .bind(&request.address)
-        .bind(&request.avatar)
-        .bind(request.role_id)
-        .bind(chrono::Utc::now())
-        .bind(user_id)
-        .execute(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
+        // 冲突时返回的最新数据同样需要脱敏
+        let mut user = service
+            .update(&current_user, &request)
+            .await
+            .map_err(|e| e.map_current(|latest: &mut User| pii_policy.apply(latest)))?;
+        pii_policy.apply(&mut user);
+        Ok(user)
     }
+    .await;
 
-    let user = sqlx::query_as::<_, User>(
-        "SELECT * FROM users WHERE id = ?"
-    )
-    .bind(user_id)
-    .fetch_one(&db.pool)
-    .await
-    .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(user))
+    ApiResponse::from_result(result)
 }
 
 #[tauri::command]
 pub async fn delete_user(
     state: State<'_, AppState>,
     user_id: i64,
+    token: String,
 ) -> Result<ApiResponse<()>, String> {
-    // 检查是否为admin用户（假设admin用户的ID为1，或者用户名为admin）
-    let db = state.db.lock().await;
-    
-    let user = sqlx::query("SELECT id, username FROM users WHERE id = ?")
-        .bind(user_id)
-        .fetch_one(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    let username: String = user.get("username");
-    
-    // 禁止删除admin用户
-    if username == "admin" || user_id == 1 {
-        return Ok(ApiResponse::error("管理员账户不可删除".to_string()));
-    }
-    
-    sqlx::query("DELETE FROM users WHERE id = ?")
-        .bind(user_id)
-        .execute(&db.pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(ApiResponse::success(()))
-} 
\ No newline at end of file
+    let db = &state.db;
+    let tenant_id = match get_current_tenant(db, &token).await {
+        Ok(tenant_id) => tenant_id,
+        Err(e) => return Ok(ApiResponse::error(e)),
+    };
+    let deleted_by = parse_token(&token);
+
+    ApiResponse::from_result(UserService::new(&db.pool).delete(tenant_id, user_id, deleted_by).await)
+}
@@ -0,0 +1,642 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::Argon2;
+use chrono::{DateTime, Local, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use libsqlite3_sys as ffi;
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::ConnectOptions;
+use sqlx::SqlitePool;
+
+use super::{append_suffix, migrations, Database, DatabaseConfig, DatabaseKey};
+use crate::with_pool;
+
+/// 加密备份文件的文件头
+const ENCRYPTED_MAGIC: &[u8; 8] = b"RADBENC1";
+const GZIP_MAGIC: &[u8; 2] = &[0x1f, 0x8b];
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// 定时备份检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(10 * 60);
+/// 定时备份使用的口令（可选），口令不保存在数据库里
+pub const BACKUP_PASSPHRASE_ENV: &str = "RUST_ADMIN_BACKUP_PASSPHRASE";
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct BackupOptions {
+    pub compress: bool,
+    pub passphrase: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub size: u64,
+    pub created_at: Option<DateTime<Utc>>,
+    pub compressed: bool,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: i64,
+    pub retention_count: i64,
+    pub compress: bool,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            retention_count: 7,
+            compress: true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RestoreResult {
+    pub restored_from: String,
+    pub safety_copy: String,
+    pub schema_version: i64,
+}
+
+impl Database {
+    /// 备份文件所在目录：数据库文件旁边的 backups 目录
+    pub fn backup_dir(&self) -> PathBuf {
+        backup_dir_for(&self.path)
+    }
+}
+
+fn backup_dir_for(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .map(|dir| dir.join("backups"))
+        .unwrap_or_else(|| PathBuf::from("backups"))
+}
+
+/// 在线备份：VACUUM INTO 生成一致性快照，不阻塞其他读写
+pub async fn create_backup(db: &Database, options: &BackupOptions) -> Result<BackupInfo> {
+    let dir = db.backup_dir();
+    std::fs::create_dir_all(&dir)?;
+
+    let timestamp = Local::now().format("%Y%m%d-%H%M%S");
+    let snapshot = dir.join(format!("rust-admin-{}.db", timestamp));
+    vacuum_into(db.sqlite()?, &snapshot).await?;
+
+    let mut final_path = snapshot.clone();
+
+    if options.compress {
+        let gz_path = append_extension(&final_path, "gz");
+        let mut encoder = GzEncoder::new(BufWriter::new(File::create(&gz_path)?), Compression::default());
+        std::io::copy(&mut BufReader::new(File::open(&final_path)?), &mut encoder)?;
+        encoder.finish()?.flush()?;
+        std::fs::remove_file(&final_path)?;
+        final_path = gz_path;
+    }
+
+    if let Some(passphrase) = options.passphrase.as_deref().filter(|p| !p.is_empty()) {
+        let enc_path = append_extension(&final_path, "enc");
+        let plain = std::fs::read(&final_path)?;
+        std::fs::write(&enc_path, encrypt(&plain, passphrase)?)?;
+        std::fs::remove_file(&final_path)?;
+        final_path = enc_path;
+    }
+
+    println!("Backup created: {}", final_path.display());
+
+    backup_info(&final_path)
+}
+
+/// 列出所有备份，最新的在前
+pub fn list_backups(db: &Database) -> Result<Vec<BackupInfo>> {
+    list_backups_in(&db.backup_dir())
+}
+
+fn list_backups_in(dir: &Path) -> Result<Vec<BackupInfo>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_backup = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("rust-admin-"));
+        if path.is_file() && is_backup {
+            backups.push(backup_info(&path)?);
+        }
+    }
+    backups.sort_by(|a, b| b.file_name.cmp(&a.file_name));
+
+    Ok(backups)
+}
+
+/// 保留策略：只保留最新的 keep 个备份
+pub fn apply_retention(db: &Database, keep: usize) -> Result<usize> {
+    apply_retention_in(&db.backup_dir(), keep)
+}
+
+fn apply_retention_in(dir: &Path, keep: usize) -> Result<usize> {
+    let mut removed = 0;
+    for backup in list_backups_in(dir)?.into_iter().skip(keep) {
+        std::fs::remove_file(dir.join(&backup.file_name))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+/// 恢复备份：先校验完整性和结构版本，再保留当前数据库的安全副本，最后替换数据库文件。
+/// 恢复会整体替换数据库内容，调用方应重新加载应用状态（重启应用）。
+pub async fn restore_backup(db: &Database, file_name: &str, passphrase: Option<&str>) -> Result<RestoreResult> {
+    let dir = db.backup_dir();
+    let source = dir.join(file_name);
+    // 只允许恢复备份目录中的文件
+    if Path::new(file_name).file_name().and_then(|n| n.to_str()) != Some(file_name) || !source.is_file() {
+        bail!("备份文件不存在: {}", file_name);
+    }
+
+    // 解密、解压到临时文件
+    let staging = dir.join(format!(".restore-{}.db", Utc::now().timestamp_millis()));
+    let result = restore_from_staging(db, &source, &staging, passphrase).await;
+    let _ = std::fs::remove_file(&staging);
+    result.map(|(safety_copy, schema_version)| RestoreResult {
+        restored_from: file_name.to_string(),
+        safety_copy,
+        schema_version,
+    })
+}
+
+async fn restore_from_staging(
+    db: &Database,
+    source: &Path,
+    staging: &Path,
+    passphrase: Option<&str>,
+) -> Result<(String, i64)> {
+    let pool = db.sqlite()?;
+    decode_backup(source, staging, passphrase, db.key.is_some())?;
+
+    let schema_version = verify_backup(staging, db.key.as_ref()).await?;
+
+    // 保留当前数据库的安全副本
+    let safety_copy = db.backup_dir().join(format!(
+        "pre-restore-{}.db",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    vacuum_into(pool, &safety_copy).await?;
+
+    // 通过 SQLite 在线备份 API 把暂存文件写回当前数据库，不需要关闭连接池，
+    // 也不会与尚未写回的 WAL 内容冲突
+    copy_into_live(pool, staging, db.key.as_ref()).await.context("写回数据库失败")?;
+
+    println!("Database restored from {}, safety copy at {}", source.display(), safety_copy.display());
+
+    Ok((safety_copy.display().to_string(), schema_version))
+}
+
+/// 启动时数据库已损坏、无法正常打开时使用：从最新的备份恢复。
+/// 此时没有可用的连接池，损坏的文件（连同 -wal、-shm）改名移入备份目录保留，
+/// 校验通过的备份副本直接放回数据库文件的位置。加密备份使用环境变量中的口令解密。
+pub async fn recover_from_latest_backup(config: &DatabaseConfig) -> Result<RestoreResult> {
+    let dir = backup_dir_for(&config.path);
+    let latest = list_backups_in(&dir)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("没有可用的备份: {}", dir.display()))?;
+    let source = dir.join(&latest.file_name);
+
+    let staging = dir.join(format!(".recover-{}.db", Utc::now().timestamp_millis()));
+    let passphrase = std::env::var(BACKUP_PASSPHRASE_ENV).ok();
+    let result = async {
+        decode_backup(&source, &staging, passphrase.as_deref(), config.key.is_some())?;
+        verify_backup(&staging, config.key.as_ref()).await
+    }
+    .await;
+    let schema_version = match result {
+        Ok(version) => version,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staging);
+            return Err(e.context(format!("备份 {} 不可用", latest.file_name)));
+        }
+    };
+
+    let corrupt_copy = dir.join(format!("corrupt-{}.db", Local::now().format("%Y%m%d-%H%M%S")));
+    for suffix in ["", "-wal", "-shm"] {
+        let file = append_suffix(&config.path, suffix);
+        if file.exists() {
+            std::fs::rename(&file, append_suffix(&corrupt_copy, suffix))
+                .with_context(|| format!("无法移走损坏的数据库文件: {}", file.display()))?;
+        }
+    }
+    std::fs::rename(&staging, &config.path).context("无法放回恢复的数据库文件")?;
+
+    println!("Database recovered from {}, corrupt copy at {}", source.display(), corrupt_copy.display());
+
+    Ok(RestoreResult {
+        restored_from: latest.file_name,
+        safety_copy: corrupt_copy.display().to_string(),
+        schema_version,
+    })
+}
+
+// 使用 sqlite3_backup_* 把 source 文件整体复制到连接池对应的数据库
+async fn copy_into_live(pool: &SqlitePool, source: &Path, key: Option<&DatabaseKey>) -> Result<()> {
+    let mut source_conn = source_options(source, key).connect().await?;
+    let mut target_conn = pool.acquire().await?;
+
+    let mut source_handle = source_conn.lock_handle().await?;
+    let mut target_handle = target_conn.lock_handle().await?;
+    let source_db = source_handle.as_raw_handle().as_ptr();
+    let target_db = target_handle.as_raw_handle().as_ptr();
+
+    // SAFETY: 两个句柄在 lock_handle 期间由当前线程独占，备份对象在返回前被释放
+    unsafe {
+        let backup = ffi::sqlite3_backup_init(target_db, c"main".as_ptr(), source_db, c"main".as_ptr());
+        if backup.is_null() {
+            bail!(sqlite_error(target_db));
+        }
+        let step = ffi::sqlite3_backup_step(backup, -1);
+        let finish = ffi::sqlite3_backup_finish(backup);
+        if step != ffi::SQLITE_DONE || finish != ffi::SQLITE_OK {
+            bail!(sqlite_error(target_db));
+        }
+    }
+
+    Ok(())
+}
+
+unsafe fn sqlite_error(db: *mut ffi::sqlite3) -> String {
+    std::ffi::CStr::from_ptr(ffi::sqlite3_errmsg(db)).to_string_lossy().into_owned()
+}
+
+/// 定时备份任务：按设置的间隔备份，并执行保留策略
+pub fn spawn_scheduler(db: Database) {
+    // PostgreSQL 由数据库服务端负责备份
+    if db.sqlite().is_err() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = run_scheduled_backup(&db).await {
+                println!("Scheduled backup failed: {}", e);
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    });
+}
+
+async fn run_scheduled_backup(db: &Database) -> Result<()> {
+    if db.pool.is_closed() {
+        return Ok(());
+    }
+
+    let settings = load_settings(db).await?;
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let latest = list_backups(db)?
+        .into_iter()
+        .filter_map(|b| b.created_at)
+        .max();
+    let due = latest.is_none_or(|t| Utc::now() - t >= chrono::Duration::hours(settings.interval_hours.max(1)));
+    if !due {
+        return Ok(());
+    }
+
+    let options = BackupOptions {
+        compress: settings.compress,
+        passphrase: std::env::var(BACKUP_PASSPHRASE_ENV).ok(),
+    };
+    create_backup(db, &options).await?;
+    apply_retention(db, settings.retention_count.max(1) as usize)?;
+
+    Ok(())
+}
+
+pub async fn load_settings(db: &Database) -> Result<BackupSettings> {
+    let rows = with_pool!(db.pool, |pool| {
+        sqlx::query_as::<_, (String, String)>(
+            "SELECT setting_key, setting_value FROM system_settings WHERE tenant_id = 0 AND setting_key LIKE 'backup_%'"
+        )
+        .fetch_all(pool)
+        .await
+    })?;
+
+    let mut settings = BackupSettings::default();
+    for (key, value) in rows {
+        match key.as_str() {
+            "backup_enabled" => settings.enabled = value == "true",
+            "backup_interval_hours" => settings.interval_hours = value.parse().unwrap_or(24),
+            "backup_retention_count" => settings.retention_count = value.parse().unwrap_or(7),
+            "backup_compress" => settings.compress = value == "true",
+            _ => {}
+        }
+    }
+
+    Ok(settings)
+}
+
+pub async fn save_settings(db: &Database, settings: &BackupSettings) -> Result<()> {
+    let values = [
+        ("backup_enabled", settings.enabled.to_string()),
+        ("backup_interval_hours", settings.interval_hours.to_string()),
+        ("backup_retention_count", settings.retention_count.to_string()),
+        ("backup_compress", settings.compress.to_string()),
+    ];
+
+    with_pool!(db.pool, |pool| {
+        let mut tx = pool.begin().await?;
+        for (key, value) in &values {
+            sqlx::query("UPDATE system_settings SET setting_value = $1, updated_at = $2 WHERE tenant_id = 0 AND setting_key = $3")
+                .bind(value)
+                .bind(Utc::now())
+                .bind(key)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    })?;
+
+    Ok(())
+}
+
+async fn vacuum_into(pool: &SqlitePool, target: &Path) -> Result<()> {
+    if target.exists() {
+        bail!("目标文件已存在: {}", target.display());
+    }
+    sqlx::query("VACUUM INTO ?")
+        .bind(target.display().to_string())
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+// 打开备份副本，执行完整性检查并确认结构版本与当前程序兼容
+async fn verify_backup(path: &Path, key: Option<&DatabaseKey>) -> Result<i64> {
+    let pool = SqlitePool::connect_with(source_options(path, key))
+        .await
+        .context("无法打开备份文件")?;
+
+    let result = async {
+        let integrity = sqlx::query_scalar::<_, String>("PRAGMA integrity_check")
+            .fetch_all(&pool)
+            .await?;
+        if integrity != ["ok"] {
+            bail!("备份文件完整性检查失败: {}", integrity.join("; "));
+        }
+
+        migrations::check_compatible(&pool).await
+    }
+    .await;
+
+    pool.close().await;
+    result
+}
+
+// 以只读方式打开备份副本；加密数据库的快照使用同一个 SQLCipher 密钥
+fn source_options(path: &Path, key: Option<&DatabaseKey>) -> SqliteConnectOptions {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    match key {
+        Some(key) => key.apply(options),
+        None => options,
+    }
+}
+
+// 依次解密、解压，得到可直接打开的 SQLite 文件
+fn decode_backup(source: &Path, target: &Path, passphrase: Option<&str>, sqlcipher: bool) -> Result<()> {
+    let mut data = std::fs::read(source)?;
+
+    if data.starts_with(ENCRYPTED_MAGIC) {
+        let passphrase = passphrase
+            .filter(|p| !p.is_empty())
+            .ok_or_else(|| anyhow!("该备份已加密，请提供口令"))?;
+        data = decrypt(&data, passphrase)?;
+    }
+
+    if data.starts_with(GZIP_MAGIC) {
+        let mut plain = Vec::new();
+        GzDecoder::new(data.as_slice()).read_to_end(&mut plain)?;
+        data = plain;
+    }
+
+    // SQLCipher 数据库没有明文文件头，交给 verify_backup 用密钥校验
+    if !sqlcipher && !data.starts_with(SQLITE_MAGIC) {
+        bail!("不是有效的数据库备份文件");
+    }
+
+    std::fs::write(target, data)?;
+    Ok(())
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("口令派生密钥失败: {}", e))?;
+    Ok(key)
+}
+
+// 文件格式：文件头 | salt | nonce | AES-256-GCM 密文
+fn encrypt(plain: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let mut salt = [0u8; SALT_LEN];
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow!(e.to_string()))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plain)
+        .map_err(|_| anyhow!("备份加密失败"))?;
+
+    let mut out = Vec::with_capacity(ENCRYPTED_MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(ENCRYPTED_MAGIC);
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>> {
+    let header = ENCRYPTED_MAGIC.len();
+    if data.len() < header + SALT_LEN + NONCE_LEN {
+        bail!("加密备份文件已损坏");
+    }
+    let salt = &data[header..header + SALT_LEN];
+    let nonce = &data[header + SALT_LEN..header + SALT_LEN + NONCE_LEN];
+    let ciphertext = &data[header + SALT_LEN + NONCE_LEN..];
+
+    let key = derive_key(passphrase, salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| anyhow!(e.to_string()))?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!("口令错误或备份文件已损坏"))
+}
+
+fn backup_info(path: &Path) -> Result<BackupInfo> {
+    let metadata = std::fs::metadata(path)?;
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+
+    Ok(BackupInfo {
+        created_at: metadata.modified().ok().map(DateTime::<Utc>::from),
+        compressed: file_name.contains(".gz"),
+        encrypted: file_name.ends_with(".enc"),
+        size: metadata.len(),
+        file_name,
+    })
+}
+
+fn append_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".");
+    name.push(extension);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rust-admin-backup-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    // 以文件头开头的假数据库内容，decode_backup 只检查文件头
+    fn sqlite_bytes() -> Vec<u8> {
+        [SQLITE_MAGIC.as_slice(), b"page data"].concat()
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decrypts_what_it_encrypts() {
+        let plain = sqlite_bytes();
+        let encrypted = encrypt(&plain, "口令 passphrase").unwrap();
+
+        assert!(encrypted.starts_with(ENCRYPTED_MAGIC));
+        assert_ne!(&encrypted[ENCRYPTED_MAGIC.len()..], plain.as_slice());
+        assert_eq!(decrypt(&encrypted, "口令 passphrase").unwrap(), plain);
+        // 每次使用新的 salt 和 nonce
+        assert_ne!(encrypt(&plain, "口令 passphrase").unwrap(), encrypted);
+    }
+
+    #[test]
+    fn rejects_wrong_passphrase_and_truncated_files() {
+        let encrypted = encrypt(&sqlite_bytes(), "right").unwrap();
+
+        assert!(decrypt(&encrypted, "wrong").is_err());
+        assert!(decrypt(&encrypted[..ENCRYPTED_MAGIC.len() + SALT_LEN], "right").is_err());
+
+        let mut tampered = encrypted.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(&tampered, "right").is_err());
+    }
+
+    #[test]
+    fn derives_the_same_key_only_from_the_same_input() {
+        let salt = [7u8; SALT_LEN];
+        let key = derive_key("passphrase", &salt).unwrap();
+
+        assert_eq!(derive_key("passphrase", &salt).unwrap(), key);
+        assert_ne!(derive_key("passphrase", &[8u8; SALT_LEN]).unwrap(), key);
+        assert_ne!(derive_key("Passphrase", &salt).unwrap(), key);
+    }
+
+    #[test]
+    fn decodes_compressed_and_encrypted_backups() {
+        let dir = temp_dir();
+        let plain = sqlite_bytes();
+        let gz = dir.join("rust-admin-1.db.gz");
+        let enc = dir.join("rust-admin-2.db.gz.enc");
+        let target = dir.join("decoded.db");
+        std::fs::write(&gz, gzip(&plain)).unwrap();
+        std::fs::write(&enc, encrypt(&gzip(&plain), "secret").unwrap()).unwrap();
+
+        decode_backup(&gz, &target, None, false).unwrap();
+        let from_gz = std::fs::read(&target).unwrap();
+        decode_backup(&enc, &target, Some("secret"), false).unwrap();
+        let from_enc = std::fs::read(&target).unwrap();
+        let without_passphrase = decode_backup(&enc, &target, None, false);
+        let wrong_passphrase = decode_backup(&enc, &target, Some("other"), false);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(from_gz, plain);
+        assert_eq!(from_enc, plain);
+        assert!(without_passphrase.is_err());
+        assert!(wrong_passphrase.is_err());
+    }
+
+    #[test]
+    fn refuses_files_that_are_not_databases() {
+        let dir = temp_dir();
+        let source = dir.join("rust-admin-1.db.gz");
+        let target = dir.join("decoded.db");
+        std::fs::write(&source, gzip(b"not a database")).unwrap();
+
+        let plain = decode_backup(&source, &target, None, false);
+        // SQLCipher 的快照没有明文文件头，留给打开时用密钥校验
+        let sqlcipher = decode_backup(&source, &target, None, true);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(plain.is_err());
+        assert!(sqlcipher.is_ok());
+    }
+
+    #[test]
+    fn retention_keeps_the_newest_backups_only() {
+        let dir = temp_dir();
+        let names = [
+            "rust-admin-20240101-080000.db.gz",
+            "rust-admin-20240102-080000.db",
+            "rust-admin-20240103-080000.db.gz.enc",
+            "rust-admin-20240104-080000.db.gz",
+            "pre-restore-20240101-090000.db",
+            "corrupt-20240101-100000.db",
+        ];
+        for name in names {
+            std::fs::write(dir.join(name), b"backup").unwrap();
+        }
+
+        let removed = apply_retention_in(&dir, 2).unwrap();
+        let mut left: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        left.sort();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(removed, 2);
+        assert_eq!(left, [
+            "corrupt-20240101-100000.db",
+            "pre-restore-20240101-090000.db",
+            "rust-admin-20240103-080000.db.gz.enc",
+            "rust-admin-20240104-080000.db.gz",
+        ]);
+    }
+}
@@ -0,0 +1,263 @@
+use std::path::{Path, PathBuf};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use super::{append_suffix, Backend};
+use super::encryption::{
+    DatabaseKey, KeyAction, DATABASE_KEY_ENV, DATABASE_KEY_FILE_ENV, DATABASE_KEY_FILE_FLAG,
+    ENCRYPT_FLAG, NEW_KEY_ENV, NEW_KEY_FILE_FLAG, ROTATE_KEY_FLAG,
+};
+
+/// 数据库文件名
+pub const DATABASE_FILE: &str = "rust-admin.db";
+/// 指定数据库路径的环境变量
+pub const DATABASE_PATH_ENV: &str = "RUST_ADMIN_DB_PATH";
+/// 指定数据库路径的命令行参数，支持 `--db-path <path>` 和 `--db-path=<path>`
+pub const DATABASE_PATH_FLAG: &str = "--db-path";
+/// 应用配置目录下的配置文件
+pub const CONFIG_FILE: &str = "config.json";
+/// 指定数据库连接串的环境变量，`postgres://` 开头时使用 PostgreSQL 后端
+pub const DATABASE_URL_ENV: &str = "RUST_ADMIN_DATABASE_URL";
+/// 指定数据库连接串的命令行参数
+pub const DATABASE_URL_FLAG: &str = "--database-url";
+
+/// 数据库位置（SQLite 路径或 PostgreSQL 连接串）的来源，按优先级从高到低排列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatabasePathSource {
+    CliFlag,
+    EnvVar,
+    ConfigFile,
+    AppDataDir,
+    WorkingDir,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub backend: Backend,
+    pub path: PathBuf,
+    /// PostgreSQL 连接串，SQLite 后端为 None
+    pub url: Option<String>,
+    pub source: DatabasePathSource,
+    /// SQLCipher 密钥，未配置时使用明文数据库
+    pub key: Option<DatabaseKey>,
+    /// 本次启动需要执行的加密或密钥轮换
+    pub key_action: Option<KeyAction>,
+}
+
+// config.json 中与数据库相关的配置
+#[derive(Debug, Default, Deserialize)]
+struct ConfigFile {
+    database_path: Option<PathBuf>,
+    database_url: Option<String>,
+    database_key_file: Option<PathBuf>,
+}
+
+impl DatabaseConfig {
+    /// 按 命令行参数 > 环境变量 > 配置文件 > 应用数据目录 的顺序确定数据库位置和密钥
+    pub fn resolve(app_data_dir: Option<PathBuf>, app_config_dir: Option<PathBuf>) -> Result<Self> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let config_file = match &app_config_dir {
+            Some(config_dir) => load_config_file(&config_dir.join(CONFIG_FILE))?,
+            None => ConfigFile::default(),
+        };
+
+        let (path, mut source) = resolve_path(&args, &config_file, app_data_dir)?;
+
+        // 配置了连接串时按连接串选择后端，否则使用 SQLite 文件
+        let (backend, url) = match resolve_url(&args, &config_file) {
+            Some((url, url_source)) => {
+                source = url_source;
+                (backend_from_url(&url)?, Some(url))
+            }
+            None => (Backend::Sqlite, None),
+        };
+
+        Ok(Self {
+            backend,
+            path,
+            url,
+            source,
+            key: resolve_key(&args, &config_file)?,
+            key_action: resolve_key_action(&args)?,
+        })
+    }
+
+    /// 首次使用应用数据目录时，把旧版本写在 `data/` 目录下的数据库迁移过来
+    pub fn migrate_legacy_database(&self) -> Result<()> {
+        // 显式指定的位置不做迁移，避免把旧数据复制到用户不期望的地方
+        if self.backend != Backend::Sqlite || self.source != DatabasePathSource::AppDataDir || self.path.exists() {
+            return Ok(());
+        }
+
+        let Some(legacy_path) = legacy_database_path() else {
+            return Ok(());
+        };
+        if !legacy_path.exists() || legacy_path == self.path {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // 连同 WAL 文件一起复制，保证数据完整；旧文件保留作为备份
+        for suffix in ["", "-wal", "-shm"] {
+            let from = append_suffix(&legacy_path, suffix);
+            if from.exists() {
+                std::fs::copy(&from, append_suffix(&self.path, suffix))
+                    .with_context(|| format!("迁移旧数据库 {} 失败", from.display()))?;
+            }
+        }
+
+        println!("Migrated legacy database from {:?} to {:?}", legacy_path, self.path);
+
+        Ok(())
+    }
+}
+
+fn resolve_path(
+    args: &[String],
+    config_file: &ConfigFile,
+    app_data_dir: Option<PathBuf>,
+) -> Result<(PathBuf, DatabasePathSource)> {
+    if let Some(path) = flag_value(args, DATABASE_PATH_FLAG) {
+        return Ok((PathBuf::from(path), DatabasePathSource::CliFlag));
+    }
+
+    if let Some(path) = std::env::var_os(DATABASE_PATH_ENV).filter(|v| !v.is_empty()) {
+        return Ok((PathBuf::from(path), DatabasePathSource::EnvVar));
+    }
+
+    if let Some(path) = &config_file.database_path {
+        return Ok((path.clone(), DatabasePathSource::ConfigFile));
+    }
+
+    match app_data_dir {
+        Some(dir) => Ok((dir.join(DATABASE_FILE), DatabasePathSource::AppDataDir)),
+        None => Ok((
+            std::env::current_dir()?.join("data").join(DATABASE_FILE),
+            DatabasePathSource::WorkingDir,
+        )),
+    }
+}
+
+fn resolve_url(args: &[String], config_file: &ConfigFile) -> Option<(String, DatabasePathSource)> {
+    if let Some(url) = flag_value(args, DATABASE_URL_FLAG) {
+        return Some((url, DatabasePathSource::CliFlag));
+    }
+
+    if let Some(url) = std::env::var(DATABASE_URL_ENV).ok().filter(|v| !v.is_empty()) {
+        return Some((url, DatabasePathSource::EnvVar));
+    }
+
+    config_file.database_url.clone().map(|url| (url, DatabasePathSource::ConfigFile))
+}
+
+fn backend_from_url(url: &str) -> Result<Backend> {
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        Ok(Backend::Postgres)
+    } else {
+        bail!("不支持的数据库连接串，SQLite 请使用 database_path 指定文件路径")
+    }
+}
+
+// 密钥来源：命令行密钥文件 > 口令环境变量 > 密钥文件环境变量 > 配置文件中的密钥文件
+fn resolve_key(args: &[String], config_file: &ConfigFile) -> Result<Option<DatabaseKey>> {
+    if let Some(path) = flag_value(args, DATABASE_KEY_FILE_FLAG) {
+        return DatabaseKey::from_file(Path::new(&path)).map(Some);
+    }
+
+    if let Ok(passphrase) = std::env::var(DATABASE_KEY_ENV) {
+        if !passphrase.is_empty() {
+            return DatabaseKey::passphrase(passphrase).map(Some);
+        }
+    }
+
+    if let Some(path) = std::env::var_os(DATABASE_KEY_FILE_ENV).filter(|v| !v.is_empty()) {
+        return DatabaseKey::from_file(Path::new(&path)).map(Some);
+    }
+
+    match &config_file.database_key_file {
+        Some(path) => DatabaseKey::from_file(path).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn resolve_key_action(args: &[String]) -> Result<Option<KeyAction>> {
+    let encrypt = args.iter().any(|a| a == ENCRYPT_FLAG);
+    let rotate = args.iter().any(|a| a == ROTATE_KEY_FLAG);
+
+    match (encrypt, rotate) {
+        (true, true) => bail!("{} 和 {} 不能同时使用", ENCRYPT_FLAG, ROTATE_KEY_FLAG),
+        (true, false) => Ok(Some(KeyAction::Encrypt)),
+        (false, true) => {
+            let new_key = if let Some(path) = flag_value(args, NEW_KEY_FILE_FLAG) {
+                DatabaseKey::from_file(Path::new(&path))?
+            } else {
+                let passphrase = std::env::var(NEW_KEY_ENV).unwrap_or_default();
+                DatabaseKey::passphrase(passphrase).with_context(|| {
+                    format!("轮换密钥需要通过 {} 或环境变量 {} 提供新密钥", NEW_KEY_FILE_FLAG, NEW_KEY_ENV)
+                })?
+            };
+            Ok(Some(KeyAction::Rotate(new_key)))
+        }
+        (false, false) => Ok(None),
+    }
+}
+
+// 读取 `--flag <value>` 或 `--flag=<value>` 形式的参数
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().cloned();
+        }
+        if let Some(value) = arg.strip_prefix(flag).and_then(|v| v.strip_prefix('=')) {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+fn load_config_file(config_path: &Path) -> Result<ConfigFile> {
+    if !config_path.exists() {
+        return Ok(ConfigFile::default());
+    }
+
+    let content = std::fs::read_to_string(config_path)?;
+    let config: ConfigFile = serde_json::from_str(&content)
+        .with_context(|| format!("配置文件 {} 格式错误", config_path.display()))?;
+
+    // 相对路径相对于配置文件所在目录
+    let resolve = |path: PathBuf| match config_path.parent() {
+        Some(dir) if path.is_relative() => dir.join(path),
+        _ => path,
+    };
+
+    Ok(ConfigFile {
+        database_path: config.database_path.map(resolve),
+        database_url: config.database_url,
+        database_key_file: config.database_key_file.map(resolve),
+    })
+}
+
+// 旧版本根据当前工作目录推测项目根目录，并把数据库放在 `data/` 下
+fn legacy_database_path() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().ok()?;
+
+    let project_root = if current_dir.ends_with("debug") {
+        // 如果在 target/debug 目录，向上三级到项目根目录
+        current_dir.parent()
+            .and_then(|p| p.parent()) // target
+            .and_then(|p| p.parent()) // src-tauri
+            .unwrap_or(&current_dir)
+    } else if current_dir.ends_with("src-tauri") {
+        // 如果在 src-tauri 目录，向上一级到项目根目录
+        current_dir.parent().unwrap_or(&current_dir)
+    } else {
+        // 否则假设就在项目根目录
+        &current_dir
+    };
+
+    Some(project_root.join("data").join(DATABASE_FILE))
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use anyhow::{bail, Context, Result};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteConnection};
+use sqlx::{ConnectOptions, Connection};
+
+use super::append_suffix;
+
+/// 数据库口令的环境变量（SQLCipher 使用 PBKDF2 从口令派生密钥）
+pub const DATABASE_KEY_ENV: &str = "RUST_ADMIN_DB_KEY";
+/// 密钥文件的环境变量
+pub const DATABASE_KEY_FILE_ENV: &str = "RUST_ADMIN_DB_KEY_FILE";
+/// 指定密钥文件的命令行参数
+pub const DATABASE_KEY_FILE_FLAG: &str = "--db-key-file";
+/// 启动时把现有的明文数据库加密
+pub const ENCRYPT_FLAG: &str = "--encrypt-db";
+/// 启动时轮换数据库密钥，新密钥来自 `--new-db-key-file` 或 `RUST_ADMIN_DB_NEW_KEY`
+pub const ROTATE_KEY_FLAG: &str = "--rotate-db-key";
+pub const NEW_KEY_FILE_FLAG: &str = "--new-db-key-file";
+pub const NEW_KEY_ENV: &str = "RUST_ADMIN_DB_NEW_KEY";
+
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// 数据库密钥
+#[derive(Clone, PartialEq, Eq)]
+pub enum DatabaseKey {
+    /// 口令，由 SQLCipher 派生出实际密钥
+    Passphrase(String),
+    /// 64 位十六进制的原始密钥（来自密钥文件），跳过口令派生
+    Raw(String),
+}
+
+// 避免密钥出现在日志里
+impl fmt::Debug for DatabaseKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseKey::Passphrase(_) => f.write_str("Passphrase(***)"),
+            DatabaseKey::Raw(_) => f.write_str("Raw(***)"),
+        }
+    }
+}
+
+/// 启动时对数据库执行的密钥操作
+#[derive(Debug, Clone)]
+pub enum KeyAction {
+    Encrypt,
+    Rotate(DatabaseKey),
+}
+
+impl DatabaseKey {
+    pub fn passphrase(value: String) -> Result<Self> {
+        if value.is_empty() {
+            bail!("数据库口令不能为空");
+        }
+        Ok(DatabaseKey::Passphrase(value))
+    }
+
+    /// 从密钥文件读取：64 位十六进制视为原始密钥，否则整个文件内容作为口令
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("读取密钥文件 {} 失败", path.display()))?;
+        let content = content.trim();
+
+        if content.len() == 64 && content.chars().all(|c| c.is_ascii_hexdigit()) {
+            Ok(DatabaseKey::Raw(content.to_ascii_lowercase()))
+        } else {
+            Self::passphrase(content.to_string())
+                .with_context(|| format!("密钥文件 {} 为空", path.display()))
+        }
+    }
+
+    // SQLCipher 识别的密钥文本，原始密钥使用 x'...' 形式
+    fn secret(&self) -> String {
+        match self {
+            DatabaseKey::Passphrase(passphrase) => passphrase.clone(),
+            DatabaseKey::Raw(hex) => format!("x'{}'", hex),
+        }
+    }
+
+    // PRAGMA key / rekey 的取值，作为字符串字面量转义单引号
+    fn pragma_value(&self) -> String {
+        format!("'{}'", self.secret().replace('\'', "''"))
+    }
+
+    /// 在连接选项上设置密钥，sqlx 会保证 `PRAGMA key` 在其他语句之前执行
+    pub fn apply(&self, options: SqliteConnectOptions) -> SqliteConnectOptions {
+        options.pragma("key", self.pragma_value())
+    }
+}
+
+/// 根据文件头判断数据库是否已加密：SQLCipher 加密后整个文件（包括文件头）都是密文
+pub fn is_encrypted(path: &Path) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+
+    let mut header = Vec::with_capacity(SQLITE_MAGIC.len());
+    std::fs::File::open(path)?
+        .take(SQLITE_MAGIC.len() as u64)
+        .read_to_end(&mut header)?;
+
+    // 空文件视为尚未初始化的数据库
+    Ok(!header.is_empty() && header != SQLITE_MAGIC)
+}
+
+/// 打开前检查密钥配置与数据库文件是否匹配，给出明确的提示
+pub fn check_key(path: &Path, key: Option<&DatabaseKey>) -> Result<()> {
+    match (is_encrypted(path)?, key) {
+        (true, None) => bail!(
+            "数据库已加密，请通过环境变量 {} 或参数 {} 提供口令",
+            DATABASE_KEY_ENV,
+            DATABASE_KEY_FILE_FLAG
+        ),
+        (false, Some(_)) if path.exists() => bail!(
+            "数据库未加密，如需加密请使用参数 {} 启动一次",
+            ENCRYPT_FLAG
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// 执行启动时的密钥操作，返回之后打开数据库应使用的密钥
+pub async fn run_key_action(
+    path: &Path,
+    key: Option<&DatabaseKey>,
+    action: &KeyAction,
+) -> Result<Option<DatabaseKey>> {
+    match action {
+        KeyAction::Encrypt => {
+            let key = key.with_context(|| {
+                format!("加密数据库需要先通过 {} 或 {} 提供口令", DATABASE_KEY_ENV, DATABASE_KEY_FILE_FLAG)
+            })?;
+            encrypt_in_place(path, key).await?;
+            Ok(Some(key.clone()))
+        }
+        KeyAction::Rotate(new_key) => {
+            let old_key = key.context("轮换密钥需要提供当前口令")?;
+            rotate_key(path, old_key, new_key).await?;
+            Ok(Some(new_key.clone()))
+        }
+    }
+}
+
+/// 原地加密明文数据库：sqlcipher_export 导出到加密副本后替换原文件
+pub async fn encrypt_in_place(path: &Path, key: &DatabaseKey) -> Result<()> {
+    if !path.exists() {
+        // 新数据库会直接以加密方式创建
+        return Ok(());
+    }
+    if is_encrypted(path)? {
+        bail!("数据库已经是加密的");
+    }
+
+    let encrypted = append_suffix(path, ".encrypting");
+    let _ = std::fs::remove_file(&encrypted);
+
+    // ATTACH 沿用主库的打开标志，需要允许创建才能生成加密副本
+    let options = SqliteConnectOptions::new().filename(path).create_if_missing(true);
+    let mut conn = open_checked(options).await?;
+    let result = async {
+        // 先把 WAL 中的内容写回主文件，导出的才是完整数据
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&mut conn).await?;
+        sqlx::query("ATTACH DATABASE ? AS encrypted KEY ?")
+            .bind(encrypted.display().to_string())
+            .bind(key.secret())
+            .execute(&mut conn)
+            .await?;
+        sqlx::query("SELECT sqlcipher_export('encrypted')").execute(&mut conn).await?;
+        sqlx::query("DETACH DATABASE encrypted").execute(&mut conn).await?;
+        anyhow::Ok(())
+    }
+    .await;
+    conn.close().await?;
+
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&encrypted);
+        return Err(e.context("加密数据库失败"));
+    }
+
+    remove_wal_files(path);
+    std::fs::rename(&encrypted, path).context("替换数据库文件失败")?;
+
+    println!("Database encrypted in place: {}", path.display());
+    println!("Backups created before encryption are still plaintext, remove them if they are no longer needed");
+
+    Ok(())
+}
+
+/// 轮换密钥：用当前密钥打开后执行 PRAGMA rekey 重新加密所有页面
+pub async fn rotate_key(path: &Path, old_key: &DatabaseKey, new_key: &DatabaseKey) -> Result<()> {
+    if !is_encrypted(path)? {
+        bail!("数据库未加密，无法轮换密钥");
+    }
+    if old_key == new_key {
+        bail!("新密钥与当前密钥相同");
+    }
+
+    let options = old_key.apply(SqliteConnectOptions::new().filename(path));
+    let mut conn = open_checked(options).await.context("无法打开数据库，当前口令可能不正确")?;
+    let result = async {
+        // rekey 不支持 WAL 模式，先切换为回滚日志，打开连接池时会重新启用 WAL
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(&mut conn).await?;
+        sqlx::query("PRAGMA journal_mode = DELETE").execute(&mut conn).await?;
+        sqlx::query(&format!("PRAGMA rekey = {}", new_key.pragma_value()))
+            .execute(&mut conn)
+            .await?;
+        anyhow::Ok(())
+    }
+    .await;
+    conn.close().await?;
+    result.context("轮换数据库密钥失败")?;
+
+    println!("Database key rotated: {}", path.display());
+    println!("Start the application with the new key from now on");
+
+    Ok(())
+}
+
+// 打开单个连接，并读取一次 sqlite_master 确认密钥正确
+async fn open_checked(options: SqliteConnectOptions) -> Result<SqliteConnection> {
+    let mut conn = options.connect().await?;
+    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sqlite_master")
+        .fetch_one(&mut conn)
+        .await?;
+    Ok(conn)
+}
+
+fn remove_wal_files(path: &Path) {
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(append_suffix(path, suffix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    // 写入临时文件，测试结束时由调用方删除
+    fn temp_file(content: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rust-admin-key-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_hex_key_files_as_raw_keys() {
+        let hex = "0123456789ABCDEF".repeat(4);
+        let path = temp_file(format!("{}\n", hex).as_bytes());
+        let key = DatabaseKey::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(key.unwrap(), DatabaseKey::Raw(hex.to_ascii_lowercase()));
+    }
+
+    #[test]
+    fn reads_other_key_files_as_passphrases() {
+        // 长度不是 64 或含有非十六进制字符时，整个内容都是口令
+        for content in ["correct horse battery staple", &"0".repeat(63), &format!("{}g", "0".repeat(63))] {
+            let path = temp_file(format!("  {}\n", content).as_bytes());
+            let key = DatabaseKey::from_file(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(key.unwrap(), DatabaseKey::Passphrase(content.to_string()));
+        }
+
+        let path = temp_file(b" \n");
+        let key = DatabaseKey::from_file(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(key.is_err());
+    }
+
+    #[test]
+    fn detects_encryption_from_the_header() {
+        let missing = std::env::temp_dir().join(format!("rust-admin-key-{}", uuid::Uuid::new_v4()));
+        assert!(!is_encrypted(&missing).unwrap());
+
+        let empty = temp_file(b"");
+        let plain = temp_file(&[SQLITE_MAGIC.as_slice(), &[0u8; 84]].concat());
+        let encrypted = temp_file(&[0x5a; 100]);
+        let results = [is_encrypted(&empty), is_encrypted(&plain), is_encrypted(&encrypted)];
+        for path in [&empty, &plain, &encrypted] {
+            std::fs::remove_file(path).unwrap();
+        }
+
+        let [empty, plain, encrypted] = results.map(Result::unwrap);
+        assert!(!empty);
+        assert!(!plain);
+        assert!(encrypted);
+    }
+
+    #[test]
+    fn checks_the_key_against_the_file() {
+        let key = DatabaseKey::Passphrase("secret".to_string());
+        let missing = std::env::temp_dir().join(format!("rust-admin-key-{}", uuid::Uuid::new_v4()));
+        let plain = temp_file(SQLITE_MAGIC);
+        let encrypted = temp_file(&[0x5a; 16]);
+
+        // 新建数据库：有没有密钥都可以
+        assert!(check_key(&missing, None).is_ok());
+        assert!(check_key(&missing, Some(&key)).is_ok());
+        // 明文数据库不能直接用密钥打开，需要先加密
+        assert!(check_key(&plain, None).is_ok());
+        assert!(check_key(&plain, Some(&key)).is_err());
+        // 加密数据库必须提供密钥
+        assert!(check_key(&encrypted, None).is_err());
+        assert!(check_key(&encrypted, Some(&key)).is_ok());
+
+        std::fs::remove_file(&plain).unwrap();
+        std::fs::remove_file(&encrypted).unwrap();
+    }
+}
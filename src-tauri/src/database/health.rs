@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::time::Instant;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+
+use super::{append_suffix, Backend, Database, DbPool};
+use crate::with_pool;
+
+/// 数据库文件损坏（完整性检查未通过），启动时据此提示从备份恢复
+#[derive(Debug)]
+pub struct DatabaseCorrupted {
+    pub details: String,
+}
+
+impl fmt::Display for DatabaseCorrupted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "数据库文件已损坏: {}", self.details)
+    }
+}
+
+impl std::error::Error for DatabaseCorrupted {}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub ok: bool,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MaintenanceResult {
+    pub operation: String,
+    pub elapsed_ms: u64,
+    pub size_before: u64,
+    pub size_after: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CheckpointResult {
+    /// 有读者占用导致未能完成全部检查点
+    pub busy: bool,
+    pub wal_frames: i64,
+    pub checkpointed_frames: i64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DatabaseStats {
+    pub backend: Backend,
+    pub file_size: u64,
+    pub wal_size: u64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    pub tables: Vec<TableStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TableStats {
+    pub name: String,
+    pub row_count: i64,
+    /// 表及其索引占用的字节数，无法统计时为 None
+    pub size: Option<i64>,
+}
+
+/// 启动时的快速完整性检查，比 integrity_check 快，但不校验索引内容
+pub async fn quick_check(pool: &SqlitePool) -> Result<()> {
+    match sqlx::query_scalar::<_, String>("PRAGMA quick_check").fetch_all(pool).await {
+        Ok(rows) if rows == ["ok"] => Ok(()),
+        Ok(rows) => Err(DatabaseCorrupted { details: rows.join("; ") }.into()),
+        Err(e) if is_corruption(&e) => Err(DatabaseCorrupted { details: e.to_string() }.into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// 完整检查数据库文件，包括索引与表数据是否一致
+pub async fn integrity_check(db: &Database) -> Result<IntegrityReport> {
+    let rows = sqlx::query_scalar::<_, String>("PRAGMA integrity_check")
+        .fetch_all(db.sqlite()?)
+        .await?;
+
+    let ok = rows == ["ok"];
+    Ok(IntegrityReport {
+        ok,
+        errors: if ok { Vec::new() } else { rows },
+    })
+}
+
+/// 重建数据库文件、回收空闲页。SQLite 下随后截断 WAL 文件，使文件大小立即反映结果
+pub async fn vacuum(db: &Database) -> Result<MaintenanceResult> {
+    let size_before = database_size(db).await?;
+    let started = Instant::now();
+
+    match &db.pool {
+        DbPool::Sqlite(pool) => {
+            sqlx::query("VACUUM").execute(pool).await?;
+            checkpoint(pool).await?;
+        }
+        DbPool::Postgres(pool) => {
+            sqlx::query("VACUUM").execute(pool).await?;
+        }
+    }
+
+    Ok(MaintenanceResult {
+        operation: "VACUUM".to_string(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        size_before,
+        size_after: database_size(db).await?,
+    })
+}
+
+/// 更新查询优化器使用的统计信息
+pub async fn analyze(db: &Database) -> Result<MaintenanceResult> {
+    let size_before = database_size(db).await?;
+    let started = Instant::now();
+
+    with_pool!(db.pool, |pool| sqlx::query("ANALYZE").execute(pool).await.map(|_| ()))?;
+
+    Ok(MaintenanceResult {
+        operation: "ANALYZE".to_string(),
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        size_before,
+        size_after: database_size(db).await?,
+    })
+}
+
+/// 把 WAL 中的内容写回数据库文件并截断 WAL
+pub async fn wal_checkpoint(db: &Database) -> Result<CheckpointResult> {
+    checkpoint(db.sqlite()?).await
+}
+
+/// 文件大小、页数以及每个表的行数和占用空间
+pub async fn database_stats(db: &Database) -> Result<DatabaseStats> {
+    match &db.pool {
+        DbPool::Sqlite(pool) => sqlite_stats(db, pool).await,
+        DbPool::Postgres(pool) => {
+            let file_size = sqlx::query_scalar::<_, i64>("SELECT pg_database_size(current_database())")
+                .fetch_one(pool)
+                .await?;
+            let page_size = sqlx::query_scalar::<_, String>("SELECT current_setting('block_size')")
+                .fetch_one(pool)
+                .await?
+                .parse()
+                .unwrap_or(8192);
+
+            let sizes = sqlx::query_as::<_, (String, i64)>(
+                r#"
+                SELECT c.relname::text, pg_total_relation_size(c.oid)
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = current_schema() AND c.relkind = 'r'
+                ORDER BY c.relname
+                "#
+            )
+            .fetch_all(pool)
+            .await?;
+
+            let mut tables = Vec::new();
+            for (name, size) in sizes {
+                tables.push(TableStats {
+                    row_count: row_count(&db.pool, &name).await?,
+                    size: Some(size),
+                    name,
+                });
+            }
+
+            Ok(DatabaseStats {
+                backend: Backend::Postgres,
+                file_size: file_size as u64,
+                wal_size: 0,
+                page_size,
+                page_count: file_size / page_size.max(1),
+                freelist_count: 0,
+                tables,
+            })
+        }
+    }
+}
+
+async fn sqlite_stats(db: &Database, pool: &SqlitePool) -> Result<DatabaseStats> {
+    let page_size = pragma(pool, "page_size").await?;
+    let page_count = pragma(pool, "page_count").await?;
+    let freelist_count = pragma(pool, "freelist_count").await?;
+
+    let names = sqlx::query_scalar::<_, String>(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // dbstat 虚拟表需要编译选项支持，不可用时只统计行数
+    let sizes: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT m.tbl_name, SUM(d.pgsize)
+        FROM sqlite_master m
+        JOIN dbstat d ON d.name = m.name
+        WHERE m.type IN ('table', 'index')
+        GROUP BY m.tbl_name
+        "#
+    )
+    .fetch_all(pool)
+    .await
+    .map(|rows| rows.into_iter().collect())
+    .unwrap_or_default();
+
+    let mut tables = Vec::new();
+    for name in names {
+        tables.push(TableStats {
+            row_count: row_count(&db.pool, &name).await?,
+            size: sizes.get(&name).copied(),
+            name,
+        });
+    }
+
+    Ok(DatabaseStats {
+        backend: Backend::Sqlite,
+        file_size: file_size(&db.path),
+        wal_size: file_size(&append_suffix(&db.path, "-wal")),
+        page_size,
+        page_count,
+        freelist_count,
+        tables,
+    })
+}
+
+async fn checkpoint(pool: &SqlitePool) -> Result<CheckpointResult> {
+    let (busy, wal_frames, checkpointed_frames) =
+        sqlx::query_as::<_, (i64, i64, i64)>("PRAGMA wal_checkpoint(TRUNCATE)")
+            .fetch_one(pool)
+            .await?;
+
+    Ok(CheckpointResult {
+        busy: busy != 0,
+        wal_frames,
+        checkpointed_frames,
+    })
+}
+
+async fn pragma(pool: &SqlitePool, name: &str) -> Result<i64> {
+    Ok(sqlx::query_scalar::<_, i64>(&format!("PRAGMA {}", name))
+        .fetch_one(pool)
+        .await?)
+}
+
+// 表名来自系统目录，按标识符规则加引号后拼接
+async fn row_count(pool: &DbPool, table: &str) -> Result<i64> {
+    let sql = format!("SELECT COUNT(*) FROM \"{}\"", table.replace('"', "\"\""));
+    Ok(with_pool!(pool, |pool| sqlx::query_scalar::<_, i64>(&sql).fetch_one(pool).await)?)
+}
+
+async fn database_size(db: &Database) -> Result<u64> {
+    match &db.pool {
+        DbPool::Sqlite(_) => Ok(file_size(&db.path) + file_size(&append_suffix(&db.path, "-wal"))),
+        DbPool::Postgres(pool) => {
+            let size = sqlx::query_scalar::<_, i64>("SELECT pg_database_size(current_database())")
+                .fetch_one(pool)
+                .await?;
+            Ok(size as u64)
+        }
+    }
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+// SQLITE_CORRUPT 及其扩展错误码（低 8 位为 11）
+fn is_corruption(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Database(db_error) => db_error
+            .code()
+            .and_then(|code| code.parse::<i32>().ok())
+            .is_some_and(|code| code & 0xff == 11),
+        _ => false,
+    }
+}
@@ -1,237 +1,521 @@
-use sqlx::SqlitePool;
-use anyhow::Result;
-
-pub async fn run_migrations(pool: &SqlitePool) -> Result<()> {
-    // 角色表
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS roles (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE,
-            permissions TEXT NOT NULL,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+use sqlx::{Executor, Row, SqlitePool};
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
-    // 用户表 - 包含所有必要字段
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS users (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            username TEXT NOT NULL UNIQUE,
-            email TEXT NOT NULL UNIQUE,
-            password_hash TEXT NOT NULL,
-            phone TEXT,
-            address TEXT,
-            avatar TEXT,
-            role_id INTEGER NOT NULL DEFAULT 3,
-            status INTEGER NOT NULL DEFAULT 1,
-            failed_login_attempts INTEGER NOT NULL DEFAULT 0,
-            last_failed_login DATETIME,
-            locked_until DATETIME,
-            last_login DATETIME,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (role_id) REFERENCES roles (id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+use super::{Backend, DbPool};
+use crate::with_pool;
 
-    // 分类表
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS categories (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            parent_id INTEGER,
-            sort_order INTEGER DEFAULT 0,
-            status INTEGER NOT NULL DEFAULT 1,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (parent_id) REFERENCES categories(id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+/// 一个版本化的数据库迁移，每种方言各有一份 SQL，位于 src-tauri/migrations/<方言> 目录
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub sqlite: MigrationScript,
+    pub postgres: MigrationScript,
+}
 
-    // 商品表
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS products (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL,
-            price REAL NOT NULL,
-            description TEXT,
-            category_id INTEGER,
-            stock INTEGER NOT NULL DEFAULT 0,
-            images TEXT,
-            status INTEGER NOT NULL DEFAULT 1,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (category_id) REFERENCES categories(id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+/// 某一种方言的迁移脚本
+pub struct MigrationScript {
+    pub up: &'static str,
+    pub down: Option<&'static str>,
+}
 
-    // 订单表
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS orders (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            order_no TEXT NOT NULL UNIQUE,
-            user_id INTEGER NOT NULL,
-            total_amount REAL NOT NULL,
-            status TEXT NOT NULL DEFAULT 'pending',
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (user_id) REFERENCES users(id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+impl Migration {
+    pub fn script(&self, backend: Backend) -> &MigrationScript {
+        match backend {
+            Backend::Sqlite => &self.sqlite,
+            Backend::Postgres => &self.postgres,
+        }
+    }
 
-    // 订单商品表
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS order_items (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            order_id INTEGER NOT NULL,
-            product_id INTEGER NOT NULL,
-            product_name TEXT NOT NULL,
-            price REAL NOT NULL,
-            quantity INTEGER NOT NULL,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            FOREIGN KEY (order_id) REFERENCES orders(id),
-            FOREIGN KEY (product_id) REFERENCES products(id)
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+    /// 迁移内容的校验和，统一换行符，避免 Windows 检出时 CRLF 导致校验失败
+    pub fn checksum(&self, backend: Backend) -> String {
+        let normalized = self.script(backend).up.replace("\r\n", "\n");
+        Sha256::digest(normalized.as_bytes())
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
 
-    // 系统设置表
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS system_settings (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            setting_key TEXT NOT NULL UNIQUE,
-            setting_value TEXT NOT NULL,
-            setting_type TEXT NOT NULL DEFAULT 'string',
-            description TEXT,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
-            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+/// 所有迁移，必须按版本号严格递增排列；已发布的迁移不允许再修改，只能追加新迁移。
+/// 新增迁移时两种方言都要提供，表结构保持一致
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial_schema",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0001_initial_schema.sql"),
+            down: None,
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0001_initial_schema.sql"),
+            down: None,
+        },
+    },
+    Migration {
+        version: 2,
+        name: "create_logs",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0002_create_logs.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0002_create_logs.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0002_create_logs.sql"),
+            down: Some(include_str!("../../migrations/postgres/0002_create_logs.down.sql")),
+        },
+    },
+    Migration {
+        version: 3,
+        name: "backup_settings",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0003_backup_settings.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0003_backup_settings.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0003_backup_settings.sql"),
+            down: Some(include_str!("../../migrations/postgres/0003_backup_settings.down.sql")),
+        },
+    },
+    Migration {
+        version: 4,
+        name: "soft_delete",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0004_soft_delete.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0004_soft_delete.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0004_soft_delete.sql"),
+            down: Some(include_str!("../../migrations/postgres/0004_soft_delete.down.sql")),
+        },
+    },
+    Migration {
+        version: 5,
+        name: "row_version",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0005_row_version.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0005_row_version.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0005_row_version.sql"),
+            down: Some(include_str!("../../migrations/postgres/0005_row_version.down.sql")),
+        },
+    },
+    Migration {
+        version: 6,
+        name: "log_retention",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0006_log_retention.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0006_log_retention.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0006_log_retention.sql"),
+            down: Some(include_str!("../../migrations/postgres/0006_log_retention.down.sql")),
+        },
+    },
+    Migration {
+        version: 7,
+        name: "search",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0007_search.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0007_search.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0007_search.sql"),
+            down: Some(include_str!("../../migrations/postgres/0007_search.down.sql")),
+        },
+    },
+    Migration {
+        version: 8,
+        name: "tenants",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0008_tenants.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0008_tenants.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0008_tenants.sql"),
+            down: Some(include_str!("../../migrations/postgres/0008_tenants.down.sql")),
+        },
+    },
+    Migration {
+        version: 9,
+        name: "sync",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0009_sync.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0009_sync.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0009_sync.sql"),
+            down: Some(include_str!("../../migrations/postgres/0009_sync.down.sql")),
+        },
+    },
+    Migration {
+        version: 10,
+        name: "money",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0010_money.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0010_money.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0010_money.sql"),
+            down: Some(include_str!("../../migrations/postgres/0010_money.down.sql")),
+        },
+    },
+    Migration {
+        version: 11,
+        name: "variants",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0011_variants.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0011_variants.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0011_variants.sql"),
+            down: Some(include_str!("../../migrations/postgres/0011_variants.down.sql")),
+        },
+    },
+    Migration {
+        version: 12,
+        name: "stock_movements",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0012_stock_movements.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0012_stock_movements.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0012_stock_movements.sql"),
+            down: Some(include_str!("../../migrations/postgres/0012_stock_movements.down.sql")),
+        },
+    },
+    Migration {
+        version: 13,
+        name: "low_stock",
+        sqlite: MigrationScript {
+            up: include_str!("../../migrations/sqlite/0013_low_stock.sql"),
+            down: Some(include_str!("../../migrations/sqlite/0013_low_stock.down.sql")),
+        },
+        postgres: MigrationScript {
+            up: include_str!("../../migrations/postgres/0013_low_stock.sql"),
+            down: Some(include_str!("../../migrations/postgres/0013_low_stock.down.sql")),
+        },
+    },
+];
 
-    // 插入默认设置
-    sqlx::query(
-        r#"
-        INSERT OR IGNORE INTO system_settings (setting_key, setting_value, setting_type, description)
-        VALUES 
-            ('system_name', 'Rust Admin', 'string', '系统名称'),
-            ('system_description', '基于 Tauri + Vue 3 的后台管理系统', 'string', '系统描述'),
-            ('system_version', '1.0.0', 'string', '系统版本'),
-            ('theme_color', '#1890ff', 'string', '主题颜色'),
-            ('language', 'zh-CN', 'string', '系统语言'),
-            ('page_size', '10', 'number', '默认页面大小'),
-            ('max_login_attempts', '5', 'number', '最大登录尝试次数'),
-            ('lockout_duration', '300', 'number', '账户锁定时间（秒）'),
-            ('reset_attempts_after', '3600', 'number', '重置尝试次数的时间间隔（秒）')
-        "#,
-    )
-    .execute(pool)
-    .await?;
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied: bool,
+    pub applied_at: Option<DateTime<Utc>>,
+    pub reversible: bool,
+}
 
-    // 插入一些测试分类
-    sqlx::query(
-        r#"
-        INSERT OR IGNORE INTO categories (id, name, parent_id, sort_order, status)
-        VALUES 
-            (1, '电子产品', NULL, 1, 1),
-            (2, '服装', NULL, 2, 1),
-            (3, '图书', NULL, 3, 1)
-        "#,
-    )
-    .execute(pool)
-    .await?;
+struct AppliedMigration {
+    version: i64,
+    checksum: String,
+    applied_at: Option<DateTime<Utc>>,
+}
 
-    // 插入默认角色
-    sqlx::query(
-        r#"
-        INSERT OR REPLACE INTO roles (id, name, permissions)
-        VALUES 
-            (1, '超级管理员', '["*"]'),
-            (2, '管理员', '["dashboard:read", "user:read", "user:write", "product:read", "product:write", "order:read", "order:write", "category:read", "category:write"]'),
-            (3, '普通用户', '["dashboard:read", "order:read"]')
-        "#,
-    )
-    .execute(pool)
-    .await?;
+pub async fn run_migrations(pool: &DbPool) -> Result<()> {
+    ensure_ledger(pool).await?;
+
+    let applied = load_applied(pool).await?;
+    validate(pool.backend(), &applied)?;
+
+    for migration in MIGRATIONS.iter().filter(|m| !applied.iter().any(|a| a.version == m.version)) {
+        apply(pool, migration).await?;
+        println!("Applied migration {:04}_{}", migration.version, migration.name);
+    }
+
+    seed_defaults(pool).await?;
+
+    println!("Database migrations completed, schema version {}", current_version(pool).await?);
+
+    Ok(())
+}
+
+/// 迁移状态报告：列出每个迁移是否已应用，用于查看待执行的迁移
+pub async fn migration_status(pool: &DbPool) -> Result<Vec<MigrationStatus>> {
+    ensure_ledger(pool).await?;
+    let applied = load_applied(pool).await?;
+    let backend = pool.backend();
+
+    Ok(MIGRATIONS
+        .iter()
+        .map(|m| {
+            let record = applied.iter().find(|a| a.version == m.version);
+            MigrationStatus {
+                version: m.version,
+                name: m.name.to_string(),
+                checksum: m.checksum(backend),
+                applied: record.is_some(),
+                applied_at: record.and_then(|a| a.applied_at),
+                reversible: m.script(backend).down.is_some(),
+            }
+        })
+        .collect())
+}
 
-    // 插入默认用户
-    let password_hash = bcrypt::hash("123456", bcrypt::DEFAULT_COST).unwrap();
-    sqlx::query(
-        r#"
-        INSERT OR REPLACE INTO users (id, username, email, password_hash, role_id, status)
-        VALUES 
-            (1, 'admin', 'admin@example.com', ?, 1, 1),
-            (2, 'manager', 'manager@example.com', ?, 2, 1),
-            (3, 'user', 'user@example.com', ?, 3, 1)
-        "#,
+/// 回滚到指定版本：按版本倒序执行 down 迁移，任一迁移不可回滚则整体拒绝
+pub async fn rollback_to(pool: &DbPool, target_version: i64) -> Result<()> {
+    ensure_ledger(pool).await?;
+    let applied = load_applied(pool).await?;
+    let backend = pool.backend();
+    validate(backend, &applied)?;
+
+    let to_revert: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .rev()
+        .filter(|m| m.version > target_version && applied.iter().any(|a| a.version == m.version))
+        .collect();
+
+    if let Some(m) = to_revert.iter().find(|m| m.script(backend).down.is_none()) {
+        bail!("迁移 {:04}_{} 不支持回滚", m.version, m.name);
+    }
+
+    for migration in to_revert {
+        let down = migration.script(backend).down.unwrap_or_default();
+        with_pool!(pool, |pool| {
+            let mut tx = pool.begin().await?;
+            // 不带参数的 SQL 走简单查询协议，PostgreSQL 下也能一次执行多条语句
+            (&mut *tx).execute(down).await?;
+            sqlx::query("DELETE FROM schema_migrations WHERE version = $1")
+                .bind(migration.version)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await
+        })?;
+        println!("Reverted migration {:04}_{}", migration.version, migration.name);
+    }
+
+    Ok(())
+}
+
+/// 当前数据库的结构版本（最后一个已应用迁移的版本号）
+pub async fn current_version(pool: &DbPool) -> Result<i64> {
+    let version = with_pool!(pool, |pool| {
+        sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(version) FROM schema_migrations")
+            .fetch_one(pool)
+            .await
+    })?;
+    Ok(version.unwrap_or(0))
+}
+
+/// 检查另一个 SQLite 数据库（如备份文件）的迁移台账是否与当前程序兼容，返回其结构版本
+pub async fn check_compatible(pool: &SqlitePool) -> Result<i64> {
+    let has_ledger = sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'schema_migrations'"
     )
-    .bind(&password_hash)
-    .bind(&password_hash)
-    .bind(&password_hash)
-    .execute(pool)
+    .fetch_one(pool)
     .await?;
 
-    println!("Database migrations completed with login limit features!");
+    // 引入迁移台账之前的数据库，启动时会从头补齐迁移
+    if has_ledger == 0 {
+        return Ok(0);
+    }
+
+    let applied = load_applied(&DbPool::Sqlite(pool.clone())).await?;
+    validate(Backend::Sqlite, &applied)?;
 
-    // 移除权限验证部分，因为它可能导致查询失败
-    // 或者改为可选的验证
-    if let Ok(manager_permissions) = sqlx::query_scalar::<_, String>(
-        "SELECT permissions FROM roles WHERE name = '管理员'"
-    )
-    .fetch_optional(pool)
-    .await {
-        if let Some(permissions) = manager_permissions {
-            println!("Manager permissions: {}", permissions);
-            if permissions.contains("user:read") {
-                println!("✓ Manager has user:read permission");
-            } else {
-                println!("✗ Manager missing user:read permission");
+    Ok(applied.iter().map(|a| a.version).max().unwrap_or(0))
+}
+
+async fn ensure_ledger(pool: &DbPool) -> Result<()> {
+    let sql = match pool.backend() {
+        Backend::Sqlite => r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at DATETIME DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+        Backend::Postgres => r#"
+            CREATE TABLE IF NOT EXISTS schema_migrations (
+                version BIGINT PRIMARY KEY,
+                name TEXT NOT NULL,
+                checksum TEXT NOT NULL,
+                applied_at TIMESTAMPTZ DEFAULT CURRENT_TIMESTAMP
+            )
+            "#,
+    };
+
+    with_pool!(pool, |pool| sqlx::query(sql).execute(pool).await.map(|_| ()))?;
+
+    Ok(())
+}
+
+async fn load_applied(pool: &DbPool) -> Result<Vec<AppliedMigration>> {
+    let sql = "SELECT version, checksum, applied_at FROM schema_migrations ORDER BY version";
+    let applied = with_pool!(pool, |pool| {
+        sqlx::query(sql).fetch_all(pool).await.map(|rows| {
+            rows.into_iter()
+                .map(|row| AppliedMigration {
+                    version: row.get("version"),
+                    checksum: row.get("checksum"),
+                    applied_at: row.get("applied_at"),
+                })
+                .collect()
+        })
+    })?;
+
+    Ok(applied)
+}
+
+// 校验迁移台账：已应用的迁移未被修改，且没有比已应用版本更早的待执行迁移
+fn validate(backend: Backend, applied: &[AppliedMigration]) -> Result<()> {
+    if MIGRATIONS.windows(2).any(|w| w[0].version >= w[1].version) {
+        bail!("迁移列表必须按版本号严格递增");
+    }
+
+    for record in applied {
+        match MIGRATIONS.iter().find(|m| m.version == record.version) {
+            Some(m) if m.checksum(backend) != record.checksum => {
+                bail!("迁移 {:04}_{} 在应用后被修改（校验和不一致）", m.version, m.name);
             }
+            Some(_) => {}
+            None => bail!("数据库包含未知的迁移版本 {}，可能由更新版本的程序创建", record.version),
         }
     }
 
-    // 登录日志表 - 用于记录登录尝试
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS login_logs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            username TEXT NOT NULL,
-            ip_address TEXT,
-            user_agent TEXT,
-            success INTEGER NOT NULL DEFAULT 0,
-            failure_reason TEXT,
-            created_at DATETIME DEFAULT CURRENT_TIMESTAMP
-        )
-        "#,
-    )
-    .execute(pool)
-    .await?;
+    let latest_applied = applied.iter().map(|a| a.version).max().unwrap_or(0);
+    if let Some(m) = MIGRATIONS
+        .iter()
+        .find(|m| m.version < latest_applied && !applied.iter().any(|a| a.version == m.version))
+    {
+        bail!("迁移 {:04}_{} 早于已应用的版本 {}，拒绝乱序执行", m.version, m.name, latest_applied);
+    }
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+// 在事务中执行一个迁移并写入台账，失败时整体回滚
+async fn apply(pool: &DbPool, migration: &Migration) -> Result<()> {
+    let backend = pool.backend();
+    let up = migration.script(backend).up;
+
+    with_pool!(pool, |pool| {
+        let mut tx = pool.begin().await?;
+
+        (&mut *tx).execute(up).await?;
+
+        sqlx::query("INSERT INTO schema_migrations (version, name, checksum, applied_at) VALUES ($1, $2, $3, $4)")
+            .bind(migration.version)
+            .bind(migration.name)
+            .bind(migration.checksum(backend))
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await
+    })?;
+
+    Ok(())
+}
+
+// 默认账号：密码哈希每次生成都不同，不能放进带校验和的迁移。演示数据见 seed 模块
+async fn seed_defaults(pool: &DbPool) -> Result<()> {
+    // 插入默认用户（已存在则保留，不再覆盖修改过的密码）
+    let user_count = with_pool!(pool, |pool| {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
+            .fetch_one(pool)
+            .await
+    })?;
+    if user_count == 0 {
+        let password_hash = bcrypt::hash("123456", bcrypt::DEFAULT_COST)?;
+        with_pool!(pool, |pool| {
+            sqlx::query(
+                r#"
+                INSERT INTO users (id, username, email, password_hash, role_id, status)
+                VALUES
+                    (1, 'admin', 'admin@example.com', $1, 1, 1),
+                    (2, 'manager', 'manager@example.com', $1, 2, 1),
+                    (3, 'user', 'user@example.com', $1, 3, 1)
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(&password_hash)
+            .execute(pool)
+            .await
+            .map(|_| ())
+        })?;
+
+        // 默认账号加入默认店铺（SQLite 要求 INSERT ... SELECT 带 WHERE 才能解析 ON CONFLICT）
+        with_pool!(pool, |pool| {
+            sqlx::query("INSERT INTO tenant_users (tenant_id, user_id) SELECT 1, id FROM users WHERE true ON CONFLICT DO NOTHING")
+                .execute(pool)
+                .await
+                .map(|_| ())
+        })?;
+    }
+
+    // PostgreSQL 的自增序列不会因为显式指定 id 而前进
+    if pool.backend() == Backend::Postgres {
+        with_pool!(pool, |pool| {
+            pool.execute(
+                r#"
+                SELECT setval(pg_get_serial_sequence('users', 'id'), (SELECT MAX(id) FROM users));
+                "#,
+            )
+            .await
+            .map(|_| ())
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 按 MIGRATIONS 中的记录生成台账，校验和与当前脚本一致
+    fn ledger(backend: Backend, migrations: &[Migration]) -> Vec<AppliedMigration> {
+        migrations
+            .iter()
+            .map(|m| AppliedMigration { version: m.version, checksum: m.checksum(backend), applied_at: None })
+            .collect()
+    }
+
+    #[test]
+    fn accepts_any_prefix_of_the_migrations() {
+        for backend in [Backend::Sqlite, Backend::Postgres] {
+            for count in 0..=MIGRATIONS.len() {
+                assert!(validate(backend, &ledger(backend, &MIGRATIONS[..count])).is_ok(), "{:?} {}", backend, count);
+            }
+        }
+    }
+
+    #[test]
+    fn refuses_migrations_edited_after_they_were_applied() {
+        let mut applied = ledger(Backend::Sqlite, &MIGRATIONS[..2]);
+        applied[1].checksum = "0".repeat(64);
+        assert!(validate(Backend::Sqlite, &applied).is_err());
+
+        // 两种方言的脚本不同，校验和不能混用
+        let postgres = ledger(Backend::Postgres, &MIGRATIONS[..2]);
+        assert!(validate(Backend::Sqlite, &postgres).is_err());
+    }
+
+    #[test]
+    fn refuses_versions_unknown_to_this_build() {
+        let mut applied = ledger(Backend::Sqlite, MIGRATIONS);
+        applied.push(AppliedMigration {
+            version: MIGRATIONS.last().unwrap().version + 1,
+            checksum: "0".repeat(64),
+            applied_at: None,
+        });
+        assert!(validate(Backend::Sqlite, &applied).is_err());
+    }
+
+    #[test]
+    fn refuses_gaps_below_the_latest_applied_version() {
+        let mut applied = ledger(Backend::Sqlite, &MIGRATIONS[..3]);
+        applied.remove(1);
+        assert!(validate(Backend::Sqlite, &applied).is_err());
+
+        // 只应用过更晚的迁移同样是乱序
+        let applied = ledger(Backend::Sqlite, &MIGRATIONS[2..3]);
+        assert!(validate(Backend::Sqlite, &applied).is_err());
+    }
+}
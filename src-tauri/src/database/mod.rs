@@ -1,61 +1,354 @@
-use sqlx::SqlitePool;
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sqlx::{PgPool, Postgres, Sqlite, SqlitePool, Transaction};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
 
+pub mod backup;
+pub mod config;
+pub mod encryption;
+pub mod health;
 pub mod migrations;
+pub mod seed;
 
+pub use config::DatabaseConfig;
+pub use encryption::DatabaseKey;
+
+/// 等待写锁的最长时间，超过后返回 SQLITE_BUSY
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 不改动任何数据的写语句，执行后事务即持有写锁，效果等同于 `BEGIN IMMEDIATE`
+const SQLITE_LOCK_FOR_WRITE: &str = "UPDATE schema_migrations SET version = version WHERE 0";
+
+/// 数据库后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// 本地单机使用的 SQLite 文件（默认）
+    #[default]
+    Sqlite,
+    /// 集中部署、多用户共享的 PostgreSQL
+    Postgres,
+}
+
+/// 按后端区分的连接池，通过 `with_pool!` 在两种后端上执行同一段 sqlx 代码
+#[derive(Clone)]
+pub enum DbPool {
+    Sqlite(SqlitePool),
+    Postgres(PgPool),
+}
+
+/// 在当前后端的连接池上执行同一段 sqlx 代码，两个分支由编译器分别实例化。
+///
+/// SQL 需要使用两种方言通用的写法：`$1, $2` 形式的占位符、`RETURNING id` 获取新行 id、
+/// `ON CONFLICT DO NOTHING` 代替 `INSERT OR IGNORE`，时间统一绑定 `DateTime<Utc>`。
+///
+/// ```ignore
+/// let user = with_pool!(db.pool, |pool| {
+///     sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+///         .bind(id)
+///         .fetch_one(pool)
+///         .await
+/// })?;
+/// ```
+#[macro_export]
+macro_rules! with_pool {
+    ($pool:expr, |$conn:ident| $body:expr) => {
+        match &$pool {
+            $crate::database::DbPool::Sqlite($conn) => $body,
+            $crate::database::DbPool::Postgres($conn) => $body,
+        }
+    };
+}
+
+impl DbPool {
+    pub fn backend(&self) -> Backend {
+        match self {
+            DbPool::Sqlite(_) => Backend::Sqlite,
+            DbPool::Postgres(_) => Backend::Postgres,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        with_pool!(self, |pool| pool.is_closed())
+    }
+
+    /// 开启事务。SQLite 下开始时就取得写锁：sqlx 只会发出延迟的 `BEGIN`，先读后写的事务在 WAL 模式下
+    /// 如果读之后有其他连接提交，写入时会直接得到 SQLITE_BUSY_SNAPSHOT，`busy_timeout` 不起作用；
+    /// 提前取锁则是在这里排队等待，超过 `BUSY_TIMEOUT` 才失败
+    pub async fn begin(&self) -> sqlx::Result<DbTransaction> {
+        Ok(match self {
+            DbPool::Sqlite(pool) => {
+                let mut tx = pool.begin().await?;
+                if let Err(e) = sqlx::query(SQLITE_LOCK_FOR_WRITE).execute(&mut *tx).await {
+                    let _ = tx.rollback().await;
+                    return Err(e);
+                }
+                DbTransaction::Sqlite(tx)
+            }
+            DbPool::Postgres(pool) => DbTransaction::Postgres(pool.begin().await?),
+        })
+    }
+}
+
+/// 按后端区分的事务，未提交就被丢弃时自动回滚
+#[allow(clippy::large_enum_variant)] // 事务只在调用期间存在于栈上，不需要装箱
+pub enum DbTransaction {
+    Sqlite(Transaction<'static, Sqlite>),
+    Postgres(Transaction<'static, Postgres>),
+}
+
+impl DbTransaction {
+    pub async fn commit(self) -> sqlx::Result<()> {
+        match self {
+            DbTransaction::Sqlite(tx) => tx.commit().await,
+            DbTransaction::Postgres(tx) => tx.commit().await,
+        }
+    }
+
+    pub async fn rollback(self) -> sqlx::Result<()> {
+        match self {
+            DbTransaction::Sqlite(tx) => tx.rollback().await,
+            DbTransaction::Postgres(tx) => tx.rollback().await,
+        }
+    }
+}
+
+/// 仓储执行 SQL 的位置：直接使用连接池，或加入调用方已开启的事务
+pub enum DbConn<'a> {
+    Pool(&'a DbPool),
+    Tx(&'a mut DbTransaction),
+}
+
+impl DbConn<'_> {
+    pub fn backend(&self) -> Backend {
+        match self {
+            DbConn::Pool(pool) => pool.backend(),
+            DbConn::Tx(DbTransaction::Sqlite(_)) => Backend::Sqlite,
+            DbConn::Tx(DbTransaction::Postgres(_)) => Backend::Postgres,
+        }
+    }
+}
+
+impl<'a> From<&'a DbPool> for DbConn<'a> {
+    fn from(pool: &'a DbPool) -> Self {
+        DbConn::Pool(pool)
+    }
+}
+
+impl<'a> From<&'a mut DbTransaction> for DbConn<'a> {
+    fn from(tx: &'a mut DbTransaction) -> Self {
+        DbConn::Tx(tx)
+    }
+}
+
+/// 与 `with_pool!` 相同，但目标是 `&mut DbConn`：`$conn` 可能是连接池也可能是事务连接，
+/// 每次展开只能执行一条语句。
+///
+/// SQLite 连接池上的语句在取出的连接上执行，归还前再 ping 一次：`fetch_one` 拿到
+/// `INSERT ... RETURNING` 的第一行就返回，此时语句还没执行完、自动提交也没发生，
+/// 紧接着的查询如果分到另一个连接就看不到这一行。ping 排在同一连接的语句之后，返回时已经提交。
+#[macro_export]
+macro_rules! with_conn {
+    ($conn:expr, |$c:ident| $body:expr) => {
+        match $conn {
+            $crate::database::DbConn::Pool($crate::database::DbPool::Sqlite(pool)) => match pool.acquire().await {
+                Ok(mut connection) => {
+                    let $c = &mut *connection;
+                    let result = $body;
+                    let _ = ::sqlx::Connection::ping(&mut *connection).await;
+                    result
+                }
+                Err(e) => Err(e.into()),
+            },
+            $crate::database::DbConn::Pool($crate::database::DbPool::Postgres($c)) => $body,
+            $crate::database::DbConn::Tx($crate::database::DbTransaction::Sqlite(tx)) => {
+                let $c = &mut **tx;
+                $body
+            }
+            $crate::database::DbConn::Tx($crate::database::DbTransaction::Postgres(tx)) => {
+                let $c = &mut **tx;
+                $body
+            }
+        }
+    };
+}
+
+/// 在一个事务中完成一组操作（工作单元）：`$body` 返回 `Ok` 时提交；返回 `Err` 或中途用 `?`
+/// 提前返回时立即回滚。结果类型为 `Result<T, AppError>`。
+///
+/// 不能只靠丢弃事务回滚：那样回滚要等连接下次被取出时才执行，SQLite 的写锁在此之前一直不释放，
+/// 紧接着的写操作会得到 database is locked。
+///
+/// SQLite 下事务开始时就持有写锁（见 `DbPool::begin`），同一时间只有一个工作单元在执行，
+/// 事务中先读后写不会因为其他连接的提交而失败。
+///
+/// `$body` 直接展开在 async 块中，可以借用外部变量、使用 `.await`：
+///
+/// ```ignore
+/// transaction!(db.pool, |tx| {
+///     let mut settings = SettingRepository::new(&mut tx);
+///     settings.set("theme_color", &request.theme_color).await?;
+///     settings.set("language", &request.language).await?;
+///     Ok(())
+/// })
+/// ```
+#[macro_export]
+macro_rules! transaction {
+    ($pool:expr, |$tx:ident| $body:expr) => {
+        async {
+            let mut $tx = $pool.begin().await?;
+            let value: ::std::result::Result<_, $crate::error::AppError> = async { $body }.await;
+            match value {
+                Ok(value) => {
+                    $tx.commit().await?;
+                    Ok::<_, $crate::error::AppError>(value)
+                }
+                Err(e) => {
+                    // 回滚本身失败时仍返回业务的错误，它更能说明问题
+                    let _ = $tx.rollback().await;
+                    Err(e)
+                }
+            }
+        }
+        .await
+    };
+}
+
+#[derive(Clone)]
 pub struct Database {
-    pub pool: SqlitePool,
+    pub pool: DbPool,
+    pub path: PathBuf, // SQLite 数据库文件，PostgreSQL 后端下不使用
+    pub key: Option<DatabaseKey>, // 备份、恢复打开副本时使用同一个密钥
 }
 
 impl Database {
-    pub async fn new() -> Result<Self> {
-        // 获取当前工作目录
-        let current_dir = std::env::current_dir()?;
-        println!("Current working directory: {:?}", current_dir);
-        
-        // 从当前目录向上找到项目根目录
-        let project_root = if current_dir.ends_with("debug") {
-            // 如果在 target/debug 目录，向上三级到项目根目录
-            current_dir.parent()
-                .and_then(|p| p.parent()) // target
-                .and_then(|p| p.parent()) // src-tauri
-                .unwrap_or(&current_dir)
-        } else if current_dir.ends_with("src-tauri") {
-            // 如果在 src-tauri 目录，向上一级到项目根目录
-            current_dir.parent().unwrap_or(&current_dir)
-        } else {
-            // 否则假设就在项目根目录
-            &current_dir
+    pub async fn new(config: &DatabaseConfig) -> Result<Self> {
+        let db = match config.backend {
+            Backend::Sqlite => Self::open_sqlite(config).await?,
+            Backend::Postgres => Self::open_postgres(config).await?,
         };
-        
-        // 创建 data 目录路径
-        let data_dir = project_root.join("data");
-        
-        // 确保 data 目录存在
-        std::fs::create_dir_all(&data_dir)?;
-        
-        // 创建数据库文件路径
-        let db_path = data_dir.join("rust-admin.db");
-        let db_url = format!("sqlite:{}", db_path.display());
-        
-        println!("Project root: {:?}", project_root);
-        println!("Data directory: {:?}", data_dir);
-        println!("Database path: {}", db_url);
-        
-        // 检查目录权限
-        println!("Data directory exists: {}", data_dir.exists());
-        if let Ok(metadata) = data_dir.metadata() {
-            println!("Data directory is writable: {}", !metadata.permissions().readonly());
-        }
-        
-        // 连接数据库
-        let pool = SqlitePool::connect(&db_url).await?;
-        
-        let db = Database { pool };
-        
+
+        // 在损坏的文件上执行迁移只会让情况更糟，先做一次快速检查；
+        // 检查失败时关闭连接池，调用方才能安全地替换数据库文件
+        if let DbPool::Sqlite(pool) = &db.pool {
+            if let Err(e) = health::quick_check(pool).await {
+                pool.close().await;
+                return Err(e);
+            }
+        }
+
         // 运行数据库迁移
         migrations::run_migrations(&db.pool).await?;
-        
+
         Ok(db)
     }
-} 
\ No newline at end of file
+
+    /// 日志归档文件所在目录：与 backups 目录并列
+    pub fn archive_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .map(|dir| dir.join("archives"))
+            .unwrap_or_else(|| PathBuf::from("archives"))
+    }
+
+    /// 店铺导出文件所在目录：与 archives 目录并列
+    pub fn export_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .map(|dir| dir.join("exports"))
+            .unwrap_or_else(|| PathBuf::from("exports"))
+    }
+
+    /// 商品图片等上传文件所在目录：与 exports 目录并列
+    pub fn asset_dir(&self) -> PathBuf {
+        self.path
+            .parent()
+            .map(|dir| dir.join("assets"))
+            .unwrap_or_else(|| PathBuf::from("assets"))
+    }
+
+    /// SQLite 专属的功能（备份、加密等）通过这里取得连接池
+    pub fn sqlite(&self) -> Result<&SqlitePool> {
+        match &self.pool {
+            DbPool::Sqlite(pool) => Ok(pool),
+            DbPool::Postgres(_) => bail!("该功能仅支持 SQLite 后端，PostgreSQL 请使用 pg_dump 等数据库自带的工具"),
+        }
+    }
+
+    async fn open_sqlite(config: &DatabaseConfig) -> Result<Self> {
+        // 旧版本的数据库在 data/ 目录下，首次运行时迁移到新位置
+        config.migrate_legacy_database()?;
+
+        // 确保数据库所在目录存在
+        if let Some(data_dir) = config.path.parent() {
+            std::fs::create_dir_all(data_dir)?;
+        }
+
+        println!("Database path: {} (source: {:?})", config.path.display(), config.source);
+
+        // 启动参数要求的加密或密钥轮换在连接池打开之前完成
+        let key = match &config.key_action {
+            Some(action) => encryption::run_key_action(&config.path, config.key.as_ref(), action).await?,
+            None => config.key.clone(),
+        };
+        encryption::check_key(&config.path, key.as_ref())?;
+
+        // WAL 模式下读写互不阻塞，多个窗口可以并发读取
+        let mut options = SqliteConnectOptions::new()
+            .filename(&config.path)
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal)
+            .synchronous(SqliteSynchronous::Normal)
+            .busy_timeout(BUSY_TIMEOUT)
+            .foreign_keys(true);
+        if let Some(key) = &key {
+            options = key.apply(options);
+            println!("Database encryption: SQLCipher");
+        }
+
+        // SQLite 同一时间只有一个写者，连接数按 CPU 核数取值即可，过多只会增加锁竞争
+        let max_connections = std::thread::available_parallelism()
+            .map(|n| n.get() as u32)
+            .unwrap_or(4)
+            .clamp(4, 16);
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(max_connections)
+            .min_connections(1)
+            .acquire_timeout(Duration::from_secs(30))
+            .connect_with(options)
+            .await
+            .with_context(|| if key.is_some() { "无法打开数据库，口令可能不正确" } else { "无法打开数据库" })?;
+
+        Ok(Database { pool: DbPool::Sqlite(pool), path: config.path.clone(), key })
+    }
+
+    async fn open_postgres(config: &DatabaseConfig) -> Result<Self> {
+        let url = config.url.as_deref().context("PostgreSQL 后端需要配置 database_url")?;
+        if config.key.is_some() || config.key_action.is_some() {
+            bail!("SQLCipher 加密只适用于 SQLite 后端，PostgreSQL 请使用服务端的磁盘加密");
+        }
+
+        println!("Database backend: PostgreSQL (source: {:?})", config.source);
+
+        let pool = PgPoolOptions::new()
+            .max_connections(16)
+            .min_connections(1)
+            .acquire_timeout(Duration::from_secs(30))
+            .connect(url)
+            .await
+            .context("无法连接 PostgreSQL")?;
+
+        Ok(Database { pool: DbPool::Postgres(pool), path: config.path.clone(), key: None })
+    }
+}
+
+/// 在数据库文件名后追加后缀，得到 `-wal`、`-shm` 等伴随文件的路径
+pub(crate) fn append_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(suffix);
+    PathBuf::from(name)
+}
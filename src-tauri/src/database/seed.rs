@@ -0,0 +1,809 @@
+//! 演示数据生成：按配置的数量生成用户、分类、商品、订单（含明细）和操作日志。
+//!
+//! 与迁移分开，只在显式要求时写入（启动参数 `--seed-demo` 或 `seed_demo_data` 命令）。
+//! 同一个种子总是生成相同的数据，时间以生成当天的零点为基准向前分布，
+//! 可以用来演示界面，也可以生成上百万订单对列表和统计做压力测试。
+
+use std::time::Instant;
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use super::{DbPool, DbTransaction};
+use crate::{with_conn, with_pool};
+
+/// 生成演示数据的命令行参数：`--seed-demo` 使用默认数量，
+/// `--seed-demo=orders=1000000,seed=7` 覆盖部分选项
+pub const SEED_DEMO_FLAG: &str = "--seed-demo";
+
+/// 演示用户的登录密码，与默认账号相同
+const DEMO_PASSWORD: &str = "123456";
+/// 每条 INSERT 语句最多绑定的参数个数（SQLite 上限 32766，PostgreSQL 上限 65535）
+const MAX_PARAMS: usize = 10_000;
+/// 每个事务写入的订单数，同时也是打印进度的间隔
+const ORDER_CHUNK: usize = 10_000;
+
+/// 订单状态，与订单服务一致使用数字文本：待付款、已付款、已发货、已送达、已取消
+const ORDER_PENDING: &str = "1";
+const ORDER_PAID: &str = "2";
+const ORDER_SHIPPED: &str = "3";
+const ORDER_DELIVERED: &str = "4";
+const ORDER_CANCELLED: &str = "5";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeedOptions {
+    pub seed: u64,
+    pub users: usize,
+    pub categories: usize,
+    pub products: usize,
+    pub orders: usize,
+    pub logs: usize,
+    /// 订单和日志分布在最近多少天内
+    pub days: i64,
+}
+
+impl Default for SeedOptions {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            users: 200,
+            categories: 20,
+            products: 500,
+            orders: 5_000,
+            logs: 2_000,
+            days: 365,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SeedSummary {
+    pub users: u64,
+    pub categories: u64,
+    pub products: u64,
+    pub orders: u64,
+    pub order_items: u64,
+    pub logs: u64,
+    pub elapsed_ms: u64,
+}
+
+impl SeedOptions {
+    /// 读取启动参数，没有 `--seed-demo` 时返回 None
+    pub fn from_args() -> Result<Option<Self>> {
+        for arg in std::env::args().skip(1) {
+            if arg == SEED_DEMO_FLAG {
+                return Ok(Some(Self::default()));
+            }
+            if let Some(spec) = arg.strip_prefix(SEED_DEMO_FLAG).and_then(|v| v.strip_prefix('=')) {
+                return Self::parse(spec).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// 解析 `key=value,key=value` 形式的选项，未出现的选项使用默认值
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut options = Self::default();
+
+        for pair in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .with_context(|| format!("演示数据选项格式应为 key=value: {}", pair))?;
+            let value = value.trim().replace('_', "");
+            let invalid = || format!("演示数据选项 {} 的值无效: {}", key, value);
+
+            match key.trim() {
+                "seed" => options.seed = value.parse().with_context(invalid)?,
+                "users" => options.users = value.parse().with_context(invalid)?,
+                "categories" => options.categories = value.parse().with_context(invalid)?,
+                "products" => options.products = value.parse().with_context(invalid)?,
+                "orders" => options.orders = value.parse().with_context(invalid)?,
+                "logs" => options.logs = value.parse().with_context(invalid)?,
+                "days" => options.days = value.parse().with_context(invalid)?,
+                other => bail!("未知的演示数据选项: {}", other),
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// 写入演示数据。只能写入还没有商品和订单的数据库，保证同一个种子得到相同的结果
+pub async fn seed_demo_data(pool: &DbPool, options: &SeedOptions) -> Result<SeedSummary> {
+    if options.days <= 0 {
+        bail!("演示数据的时间跨度必须大于 0 天");
+    }
+    if options.orders > 0 && (options.users == 0 || options.products == 0) {
+        bail!("生成订单需要至少一个用户和一个商品");
+    }
+    if options.products > 0 && options.categories == 0 {
+        bail!("生成商品需要至少一个分类");
+    }
+
+    let existing = with_pool!(pool, |pool| {
+        sqlx::query_scalar::<_, i64>("SELECT (SELECT COUNT(*) FROM products) + (SELECT COUNT(*) FROM orders)")
+            .fetch_one(pool)
+            .await
+    })?;
+    if existing > 0 {
+        bail!("演示数据只能写入没有商品和订单的数据库，请使用一个新的数据库文件");
+    }
+
+    let started = Instant::now();
+    let seeder = Seeder::new(options);
+    let mut summary = SeedSummary::default();
+
+    // bcrypt 很慢，所有演示用户共用一个哈希，并且在开启事务之前算好
+    let password_hash = bcrypt::hash(DEMO_PASSWORD, bcrypt::DEFAULT_COST)?;
+
+    let mut tx = pool.begin().await?;
+    let users = seeder.users(&mut tx, &password_hash).await?;
+    let categories = seeder.categories(&mut tx).await?;
+    let products = seeder.products(&mut tx, &categories).await?;
+    seeder.logs(&mut tx, &users).await?;
+    tx.commit().await?;
+
+    summary.users = users.len() as u64;
+    summary.categories = categories.len() as u64;
+    summary.products = products.len() as u64;
+    summary.logs = options.logs as u64;
+    println!(
+        "Seeded {} users, {} categories, {} products, {} logs",
+        summary.users, summary.categories, summary.products, summary.logs
+    );
+
+    // 订单按时间先后生成，id 与下单时间的顺序一致
+    let times = seeder.order_times();
+    for (chunk_index, chunk) in times.chunks(ORDER_CHUNK).enumerate() {
+        let mut tx = pool.begin().await?;
+        let items = seeder
+            .orders(&mut tx, chunk_index * ORDER_CHUNK, chunk, &users, &products)
+            .await?;
+        tx.commit().await?;
+
+        summary.orders += chunk.len() as u64;
+        summary.order_items += items;
+        println!("Seeded {}/{} orders", summary.orders, options.orders);
+    }
+
+    summary.elapsed_ms = started.elapsed().as_millis() as u64;
+    Ok(summary)
+}
+
+// 写入后留在内存中、供后续数据引用的记录
+struct SeededUser {
+    id: i64,
+    role_id: i64,
+}
+
+struct SeededCategory {
+    id: i64,
+    catalog: usize,
+}
+
+struct SeededProduct {
+    id: i64,
+    name: String,
+    price: i64, // 分
+}
+
+// 每类数据使用独立的随机数序列，调整某一类的数量不会改变其他数据
+struct Seeder<'a> {
+    options: &'a SeedOptions,
+    anchor: DateTime<Utc>,
+}
+
+impl<'a> Seeder<'a> {
+    fn new(options: &'a SeedOptions) -> Self {
+        let anchor = Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .map(|t| t.and_utc())
+            .unwrap_or_else(Utc::now);
+        Self { options, anchor }
+    }
+
+    fn rng(&self, stream: u64) -> StdRng {
+        StdRng::seed_from_u64(self.options.seed.wrapping_add(stream.wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+    }
+
+    // 最近 days 天内的某个时刻；越近的日期数据越多，模拟业务增长
+    fn recent_time(&self, rng: &mut StdRng) -> DateTime<Utc> {
+        let days_ago = (self.options.days as f64 * (1.0 - rng.gen::<f64>().sqrt())) as i64;
+        self.anchor - Duration::days(days_ago.min(self.options.days - 1) + 1) + time_of_day(rng)
+    }
+
+    async fn users(&self, tx: &mut DbTransaction, password_hash: &str) -> Result<Vec<SeededUser>> {
+        let mut rng = self.rng(1);
+
+        let mut rows = Vec::with_capacity(self.options.users);
+        let mut roles = Vec::with_capacity(self.options.users);
+        for index in 0..self.options.users {
+            let chinese = rng.gen_bool(0.7);
+            let (username, phone, address) = if chinese {
+                let surname = pick(&mut rng, ZH_SURNAMES);
+                let given = pick(&mut rng, ZH_GIVEN_NAMES);
+                let (city, districts) = pick(&mut rng, ZH_CITIES);
+                (
+                    format!("{}{}{:04}", surname, given, index + 1),
+                    format!("1{}{:09}", pick(&mut rng, &[3, 5, 7, 8, 9]), rng.gen_range(0..1_000_000_000u64)),
+                    format!(
+                        "{}{}{}路{}号",
+                        city,
+                        pick(&mut rng, districts),
+                        pick(&mut rng, ZH_STREETS),
+                        rng.gen_range(1..300)
+                    ),
+                )
+            } else {
+                let first = pick(&mut rng, EN_FIRST_NAMES);
+                let last = pick(&mut rng, EN_LAST_NAMES);
+                (
+                    format!("{}.{}{:04}", first.to_lowercase(), last.to_lowercase(), index + 1),
+                    format!("+1-555-{:03}-{:04}", rng.gen_range(100..1000), rng.gen_range(0..10_000)),
+                    format!(
+                        "{} {} St, {}",
+                        rng.gen_range(1..9_999),
+                        pick(&mut rng, EN_STREETS),
+                        pick(&mut rng, EN_CITIES)
+                    ),
+                )
+            };
+            let domain = pick(&mut rng, &["example.com", "example.org", "example.net"]);
+            let role_id = if rng.gen_bool(0.05) { 2 } else { 3 };
+            let status = if rng.gen_bool(0.92) { 1 } else { 0 };
+            let created_at = self.recent_time(&mut rng);
+
+            roles.push(role_id);
+            rows.push(vec![
+                Value::Text(username.clone()),
+                Value::Text(format!("{}@{}", username, domain)),
+                Value::Text(password_hash.to_string()),
+                Value::OptText(Some(phone)),
+                Value::OptText(Some(address)),
+                Value::Int(role_id),
+                Value::Int(status),
+                Value::Time(created_at),
+                Value::Time(created_at),
+            ]);
+        }
+
+        let ids = insert_rows(
+            tx,
+            "users",
+            &["username", "email", "password_hash", "phone", "address", "role_id", "status", "created_at", "updated_at"],
+            rows,
+        )
+        .await?;
+
+        // 演示数据都写入默认店铺（各表 tenant_id 的默认值），用户也加入该店铺
+        with_conn!(&mut super::DbConn::from(&mut *tx), |conn| {
+            sqlx::query("INSERT INTO tenant_users (tenant_id, user_id) SELECT 1, id FROM users WHERE true ON CONFLICT DO NOTHING")
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+
+        Ok(ids.into_iter().zip(roles).map(|(id, role_id)| SeededUser { id, role_id }).collect())
+    }
+
+    // 先写入一级分类，再写入引用它们的二级分类；数量超过目录时按目录循环并加序号
+    async fn categories(&self, tx: &mut DbTransaction) -> Result<Vec<SeededCategory>> {
+        let mut rng = self.rng(2);
+        let count = self.options.categories;
+        let top_count = count.min(CATALOG.len());
+        let chinese: Vec<bool> = (0..CATALOG.len()).map(|_| rng.gen_bool(0.7)).collect();
+
+        let mut rows = Vec::new();
+        for (index, entry) in CATALOG.iter().take(top_count).enumerate() {
+            let name = if chinese[index] { entry.zh } else { entry.en };
+            rows.push(category_row(name, None, index as i64 + 1, self.recent_time(&mut rng)));
+        }
+        let parents = insert_rows(tx, "categories", &["name", "parent_id", "sort_order", "status", "created_at", "updated_at"], rows).await?;
+
+        let mut categories: Vec<SeededCategory> = parents
+            .iter()
+            .enumerate()
+            .map(|(catalog, &id)| SeededCategory { id, catalog })
+            .collect();
+
+        let mut rows = Vec::new();
+        let mut children = Vec::new();
+        for index in 0..count - top_count {
+            let catalog = index % top_count;
+            let entry = &CATALOG[catalog];
+            let leaf = &entry.leaves[(index / top_count) % entry.leaves.len()];
+            let round = index / (top_count * entry.leaves.len());
+            let base = if chinese[catalog] { leaf.zh } else { leaf.en };
+            let name = if round == 0 { base.to_string() } else { format!("{} {}", base, round + 1) };
+
+            children.push(catalog);
+            rows.push(category_row(&name, Some(parents[catalog]), index as i64 + 1, self.recent_time(&mut rng)));
+        }
+        let ids = insert_rows(tx, "categories", &["name", "parent_id", "sort_order", "status", "created_at", "updated_at"], rows).await?;
+        categories.extend(ids.into_iter().zip(children).map(|(id, catalog)| SeededCategory { id, catalog }));
+
+        Ok(categories)
+    }
+
+    async fn products(&self, tx: &mut DbTransaction, categories: &[SeededCategory]) -> Result<Vec<SeededProduct>> {
+        let mut rng = self.rng(3);
+
+        let mut rows = Vec::with_capacity(self.options.products);
+        let mut products = Vec::with_capacity(self.options.products);
+        for _ in 0..self.options.products {
+            let category = &categories[rng.gen_range(0..categories.len())];
+            let entry = &CATALOG[category.catalog];
+            let leaf = pick(&mut rng, entry.leaves);
+            let model = format!("{}{}", pick(&mut rng, MODEL_PREFIXES), rng.gen_range(1..100));
+
+            let (name, description) = if rng.gen_bool(0.7) {
+                (
+                    format!("{}{}{} {}", pick(&mut rng, entry.zh_brands), pick(&mut rng, ZH_ADJECTIVES), leaf.zh_noun, model),
+                    format!("{}，{}", leaf.zh_noun, pick(&mut rng, ZH_SELLING_POINTS)),
+                )
+            } else {
+                (
+                    format!("{} {} {} {}", pick(&mut rng, entry.en_brands), pick(&mut rng, EN_ADJECTIVES), leaf.en_noun, model),
+                    format!("{}. {}", leaf.en_noun, pick(&mut rng, EN_SELLING_POINTS)),
+                )
+            };
+            let price = retail_price(rng.gen_range(entry.price.0.ln()..entry.price.1.ln()).exp());
+            let stock = if rng.gen_bool(0.05) { 0 } else { rng.gen_range(1..500) };
+            let status = if rng.gen_bool(0.95) { 1 } else { 0 };
+            let created_at = self.recent_time(&mut rng);
+
+            rows.push(vec![
+                Value::Text(name.clone()),
+                Value::Int(price),
+                Value::OptText(Some(description)),
+                Value::OptInt(Some(category.id)),
+                Value::Int(stock),
+                Value::Int(status),
+                Value::Time(created_at),
+                Value::Time(created_at),
+            ]);
+            products.push(SeededProduct { id: 0, name, price });
+        }
+
+        let ids = insert_rows(
+            tx,
+            "products",
+            &["name", "price", "description", "category_id", "stock", "status", "created_at", "updated_at"],
+            rows,
+        )
+        .await?;
+        for (product, id) in products.iter_mut().zip(ids) {
+            product.id = id;
+        }
+
+        // 初始库存记为入库流水，库存对账才能平衡；演示订单是历史数据，不再扣减库存
+        with_conn!(&mut super::DbConn::from(&mut *tx), |conn| {
+            sqlx::query(
+                "INSERT INTO stock_movements (tenant_id, product_id, movement_type, quantity, stock_after, note, created_at) SELECT tenant_id, id, 'receipt', stock, stock, '演示数据初始库存', created_at FROM products WHERE stock <> 0"
+            )
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+
+        Ok(products)
+    }
+
+    async fn logs(&self, tx: &mut DbTransaction, users: &[SeededUser]) -> Result<()> {
+        let mut rng = self.rng(4);
+        // 操作日志主要由管理员产生
+        let operators: Vec<i64> = match users.iter().filter(|u| u.role_id != 3).map(|u| u.id).collect::<Vec<_>>() {
+            managers if !managers.is_empty() => managers,
+            _ => users.iter().map(|u| u.id).collect(),
+        };
+
+        let mut times: Vec<_> = (0..self.options.logs).map(|_| self.recent_time(&mut rng)).collect();
+        times.sort();
+
+        let mut rows = Vec::with_capacity(times.len());
+        for created_at in times {
+            let (action, description) = pick(&mut rng, LOG_ACTIONS);
+            let user_id = if operators.is_empty() { None } else { Some(operators[rng.gen_range(0..operators.len())]) };
+            rows.push(vec![
+                Value::OptInt(user_id),
+                Value::Text(action.to_string()),
+                Value::Text(format!("{} #{}", description, rng.gen_range(1..10_000))),
+                Value::Text(format!("192.168.{}.{}", rng.gen_range(0..10), rng.gen_range(2..255))),
+                Value::Time(created_at),
+            ]);
+        }
+
+        insert_rows(tx, "logs", &["user_id", "action", "description", "ip_address", "created_at"], rows).await?;
+        Ok(())
+    }
+
+    // 所有订单的下单时间，从早到晚排列
+    fn order_times(&self) -> Vec<DateTime<Utc>> {
+        let mut rng = self.rng(5);
+        let mut times: Vec<_> = (0..self.options.orders).map(|_| self.recent_time(&mut rng)).collect();
+        times.sort();
+        times
+    }
+
+    // 写入一批订单及其明细，返回明细条数
+    async fn orders(
+        &self,
+        tx: &mut DbTransaction,
+        first_index: usize,
+        times: &[DateTime<Utc>],
+        users: &[SeededUser],
+        products: &[SeededProduct],
+    ) -> Result<u64> {
+        // 每批使用自己的随机数序列，批次之间互不影响
+        let mut rng = self.rng(6 + (first_index / ORDER_CHUNK) as u64);
+
+        let mut order_rows = Vec::with_capacity(times.len());
+        let mut order_items = Vec::with_capacity(times.len());
+        for (offset, &created_at) in times.iter().enumerate() {
+            // 少数老客户贡献大部分订单，热门商品被反复购买
+            let user = &users[skewed_index(&mut rng, users.len(), 2.0)];
+            let item_count = weighted(&mut rng, &[(1, 50), (2, 25), (3, 13), (4, 8), (5, 4)]);
+
+            let mut items = Vec::with_capacity(item_count);
+            let mut total = 0;
+            for _ in 0..item_count {
+                let product = &products[skewed_index(&mut rng, products.len(), 3.0)];
+                let quantity = weighted(&mut rng, &[(1, 75), (2, 18), (3, 7)]) as i64;
+                total += product.price * quantity;
+                items.push((product, quantity));
+            }
+
+            let age = self.anchor - created_at;
+            let status = order_status(&mut rng, age);
+            let updated_at = if status == ORDER_PENDING {
+                created_at
+            } else {
+                (created_at + Duration::minutes(rng.gen_range(5..age.num_minutes().clamp(6, 7 * 24 * 60))))
+                    .min(self.anchor)
+            };
+
+            order_rows.push(vec![
+                Value::Text(format!("SO{}{:08}", created_at.format("%Y%m%d"), first_index + offset + 1)),
+                Value::Int(user.id),
+                Value::Int(total),
+                Value::Text(status.to_string()),
+                Value::Time(created_at),
+                Value::Time(updated_at),
+            ]);
+            order_items.push((items, created_at));
+        }
+
+        let order_ids = insert_rows(
+            tx,
+            "orders",
+            &["order_no", "user_id", "total_amount", "status", "created_at", "updated_at"],
+            order_rows,
+        )
+        .await?;
+
+        let mut item_rows = Vec::new();
+        for (order_id, (items, created_at)) in order_ids.into_iter().zip(order_items) {
+            for (product, quantity) in items {
+                item_rows.push(vec![
+                    Value::Int(order_id),
+                    Value::Int(product.id),
+                    Value::Text(product.name.clone()),
+                    Value::Int(product.price),
+                    Value::Int(quantity),
+                    Value::Time(created_at),
+                ]);
+            }
+        }
+
+        let count = item_rows.len() as u64;
+        insert_rows(
+            tx,
+            "order_items",
+            &["order_id", "product_id", "product_name", "price", "quantity", "created_at"],
+            item_rows,
+        )
+        .await?;
+
+        Ok(count)
+    }
+}
+
+// 绑定到 INSERT 语句的值，按列的类型区分，PostgreSQL 的空值也需要类型
+enum Value {
+    Int(i64),
+    OptInt(Option<i64>),
+    Text(String),
+    OptText(Option<String>),
+    Time(DateTime<Utc>),
+}
+
+/// 多行 INSERT，按参数个数上限分批执行，返回按写入顺序排列的新行 id。
+/// 两种后端都按 VALUES 的顺序为每一行分配自增 id，排序后即与输入的行一一对应。
+async fn insert_rows(tx: &mut DbTransaction, table: &str, columns: &[&str], rows: Vec<Vec<Value>>) -> Result<Vec<i64>> {
+    let per_statement = (MAX_PARAMS / columns.len()).max(1);
+    let mut ids = Vec::with_capacity(rows.len());
+
+    for batch in rows.chunks(per_statement) {
+        let values: Vec<String> = (0..batch.len())
+            .map(|row| {
+                let placeholders: Vec<String> = (1..=columns.len())
+                    .map(|column| format!("${}", row * columns.len() + column))
+                    .collect();
+                format!("({})", placeholders.join(", "))
+            })
+            .collect();
+        let sql = format!(
+            "INSERT INTO {} ({}) VALUES {} RETURNING id",
+            table,
+            columns.join(", "),
+            values.join(", ")
+        );
+
+        let mut batch_ids = with_conn!(&mut super::DbConn::from(&mut *tx), |conn| {
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for value in batch.iter().flatten() {
+                query = match value {
+                    Value::Int(v) => query.bind(*v),
+                    Value::OptInt(v) => query.bind(*v),
+                    Value::Text(v) => query.bind(v.as_str()),
+                    Value::OptText(v) => query.bind(v.as_deref()),
+                    Value::Time(v) => query.bind(*v),
+                };
+            }
+            query.fetch_all(conn).await
+        })?;
+        batch_ids.sort_unstable();
+        ids.extend(batch_ids);
+    }
+
+    Ok(ids)
+}
+
+fn category_row(name: &str, parent_id: Option<i64>, sort_order: i64, created_at: DateTime<Utc>) -> Vec<Value> {
+    vec![
+        Value::Text(name.to_string()),
+        Value::OptInt(parent_id),
+        Value::Int(sort_order),
+        Value::Int(1),
+        Value::Time(created_at),
+        Value::Time(created_at),
+    ]
+}
+
+// 越早的订单越可能已经送达或取消，最近的订单多为待付款、已付款
+fn order_status(rng: &mut StdRng, age: Duration) -> &'static str {
+    let weights: &[(&'static str, u32)] = if age < Duration::days(2) {
+        &[(ORDER_PENDING, 35), (ORDER_PAID, 40), (ORDER_SHIPPED, 20), (ORDER_CANCELLED, 5)]
+    } else if age < Duration::days(10) {
+        &[(ORDER_PENDING, 3), (ORDER_PAID, 7), (ORDER_SHIPPED, 35), (ORDER_DELIVERED, 48), (ORDER_CANCELLED, 7)]
+    } else {
+        &[(ORDER_DELIVERED, 92), (ORDER_CANCELLED, 8)]
+    };
+    weighted(rng, weights)
+}
+
+// 一天中的时刻：多数在 9 点到 23 点之间，少量在深夜
+fn time_of_day(rng: &mut StdRng) -> Duration {
+    let hour = if rng.gen_bool(0.9) { rng.gen_range(9..23) } else { rng.gen_range(0..9) };
+    Duration::hours(hour) + Duration::seconds(rng.gen_range(0..3_600))
+}
+
+// 价格取到常见的零售尾数：大额以 9 元结尾，小额以 .9 元结尾；返回值以分为单位
+fn retail_price(raw: f64) -> i64 {
+    if raw >= 100.0 {
+        ((raw / 10.0).round() as i64 * 10 - 1) * 100
+    } else {
+        raw.round().max(1.0) as i64 * 100 - 10
+    }
+}
+
+// 偏向列表前部的下标，exponent 越大越集中
+fn skewed_index(rng: &mut StdRng, len: usize, exponent: f64) -> usize {
+    ((rng.gen::<f64>().powf(exponent) * len as f64) as usize).min(len - 1)
+}
+
+fn weighted<T: Copy>(rng: &mut StdRng, choices: &[(T, u32)]) -> T {
+    let total: u32 = choices.iter().map(|(_, w)| w).sum();
+    let mut roll = rng.gen_range(0..total);
+    for &(choice, weight) in choices {
+        if roll < weight {
+            return choice;
+        }
+        roll -= weight;
+    }
+    choices[choices.len() - 1].0
+}
+
+fn pick<'t, T>(rng: &mut StdRng, items: &'t [T]) -> &'t T {
+    &items[rng.gen_range(0..items.len())]
+}
+
+struct CatalogEntry {
+    zh: &'static str,
+    en: &'static str,
+    leaves: &'static [Leaf],
+    zh_brands: &'static [&'static str],
+    en_brands: &'static [&'static str],
+    /// 价格区间（元），在区间内按对数均匀分布
+    price: (f64, f64),
+}
+
+struct Leaf {
+    zh: &'static str,
+    en: &'static str,
+    zh_noun: &'static str,
+    en_noun: &'static str,
+}
+
+const fn leaf(zh: &'static str, en: &'static str, zh_noun: &'static str, en_noun: &'static str) -> Leaf {
+    Leaf { zh, en, zh_noun, en_noun }
+}
+
+const CATALOG: &[CatalogEntry] = &[
+    CatalogEntry {
+        zh: "电子产品",
+        en: "Electronics",
+        leaves: &[
+            leaf("手机", "Phones", "手机", "Phone"),
+            leaf("笔记本电脑", "Laptops", "笔记本电脑", "Laptop"),
+            leaf("耳机", "Headphones", "蓝牙耳机", "Headphones"),
+            leaf("智能手表", "Smart Watches", "智能手表", "Smart Watch"),
+        ],
+        zh_brands: &["华为", "小米", "联想", "荣耀", "OPPO"],
+        en_brands: &["Apple", "Samsung", "Sony", "Dell", "Lenovo"],
+        price: (99.0, 12_999.0),
+    },
+    CatalogEntry {
+        zh: "服装",
+        en: "Clothing",
+        leaves: &[
+            leaf("男装", "Men", "男士衬衫", "Shirt"),
+            leaf("女装", "Women", "连衣裙", "Dress"),
+            leaf("童装", "Kids", "儿童外套", "Kids Jacket"),
+            leaf("运动服", "Sportswear", "运动卫衣", "Hoodie"),
+        ],
+        zh_brands: &["李宁", "安踏", "波司登", "太平鸟"],
+        en_brands: &["Uniqlo", "Levi's", "Zara", "Nike"],
+        price: (39.0, 1_299.0),
+    },
+    CatalogEntry {
+        zh: "图书",
+        en: "Books",
+        leaves: &[
+            leaf("小说", "Fiction", "长篇小说", "Novel"),
+            leaf("科技", "Technology", "编程指南", "Programming Guide"),
+            leaf("少儿", "Children", "绘本", "Picture Book"),
+            leaf("历史", "History", "通史", "History"),
+        ],
+        zh_brands: &["人民文学", "中信", "商务印书馆", "机械工业"],
+        en_brands: &["Penguin", "O'Reilly", "HarperCollins", "Vintage"],
+        price: (9.9, 199.0),
+    },
+    CatalogEntry {
+        zh: "家居",
+        en: "Home",
+        leaves: &[
+            leaf("厨具", "Kitchen", "不粘锅", "Frying Pan"),
+            leaf("床上用品", "Bedding", "四件套", "Bedding Set"),
+            leaf("收纳", "Storage", "收纳箱", "Storage Box"),
+            leaf("灯具", "Lighting", "台灯", "Desk Lamp"),
+        ],
+        zh_brands: &["苏泊尔", "九阳", "罗莱", "美的"],
+        en_brands: &["IKEA", "Philips", "Tefal", "Muji"],
+        price: (19.0, 2_999.0),
+    },
+    CatalogEntry {
+        zh: "食品",
+        en: "Food",
+        leaves: &[
+            leaf("零食", "Snacks", "坚果礼盒", "Mixed Nuts"),
+            leaf("茶叶", "Tea", "龙井茶", "Green Tea"),
+            leaf("咖啡", "Coffee", "咖啡豆", "Coffee Beans"),
+            leaf("粮油", "Pantry", "五常大米", "Rice"),
+        ],
+        zh_brands: &["三只松鼠", "良品铺子", "西湖", "金龙鱼"],
+        en_brands: &["Nestle", "Lipton", "Lavazza", "Kellogg's"],
+        price: (5.0, 599.0),
+    },
+    CatalogEntry {
+        zh: "美妆",
+        en: "Beauty",
+        leaves: &[
+            leaf("护肤", "Skincare", "保湿面霜", "Moisturizer"),
+            leaf("彩妆", "Makeup", "口红", "Lipstick"),
+            leaf("香水", "Fragrance", "淡香水", "Eau de Toilette"),
+        ],
+        zh_brands: &["百雀羚", "自然堂", "珀莱雅", "花西子"],
+        en_brands: &["L'Oreal", "Estee Lauder", "Clinique", "Chanel"],
+        price: (29.0, 1_999.0),
+    },
+    CatalogEntry {
+        zh: "运动户外",
+        en: "Sports & Outdoors",
+        leaves: &[
+            leaf("跑步", "Running", "跑鞋", "Running Shoes"),
+            leaf("露营", "Camping", "帐篷", "Tent"),
+            leaf("健身", "Fitness", "哑铃", "Dumbbell"),
+        ],
+        zh_brands: &["李宁", "特步", "牧高笛", "迪卡侬"],
+        en_brands: &["Adidas", "Coleman", "The North Face", "Garmin"],
+        price: (29.0, 4_999.0),
+    },
+];
+
+// 用户名使用姓名的拼音
+const ZH_SURNAMES: &[&str] = &[
+    "wang", "li", "zhang", "liu", "chen", "yang", "huang", "zhao", "wu", "zhou",
+    "xu", "sun", "ma", "zhu", "hu", "guo", "he", "lin", "luo", "gao",
+];
+
+const ZH_GIVEN_NAMES: &[&str] = &[
+    "wei", "fang", "na", "min", "jing", "qiang", "lei", "yang", "yan", "yong",
+    "jun", "jie", "juan", "tao", "ming", "chao", "xiuying", "xiaodong", "zihan", "haoran",
+];
+
+const ZH_CITIES: &[(&str, &[&str])] = &[
+    ("北京市", &["朝阳区", "海淀区", "东城区", "丰台区"]),
+    ("上海市", &["浦东新区", "徐汇区", "静安区", "黄浦区"]),
+    ("广州市", &["天河区", "越秀区", "海珠区"]),
+    ("深圳市", &["南山区", "福田区", "宝安区"]),
+    ("杭州市", &["西湖区", "滨江区", "余杭区"]),
+    ("成都市", &["锦江区", "武侯区", "高新区"]),
+    ("武汉市", &["江汉区", "洪山区", "武昌区"]),
+    ("南京市", &["鼓楼区", "玄武区", "建邺区"]),
+];
+
+const ZH_STREETS: &[&str] = &["建设", "人民", "中山", "解放", "文化", "科技", "新华", "长江"];
+
+const EN_FIRST_NAMES: &[&str] = &[
+    "James", "Mary", "John", "Patricia", "Robert", "Jennifer", "Michael", "Linda",
+    "David", "Emily", "Daniel", "Sarah", "Thomas", "Olivia", "Kevin", "Emma",
+];
+
+const EN_LAST_NAMES: &[&str] = &[
+    "Smith", "Johnson", "Williams", "Brown", "Jones", "Miller", "Davis", "Wilson",
+    "Anderson", "Taylor", "Moore", "Clark", "Lewis", "Walker", "Young", "King",
+];
+
+const EN_STREETS: &[&str] = &["Main", "Oak", "Maple", "Cedar", "Elm", "Pine", "Lake", "Hill"];
+
+const EN_CITIES: &[&str] = &["Springfield", "Riverside", "Fairview", "Madison", "Georgetown", "Franklin"];
+
+const ZH_ADJECTIVES: &[&str] = &["新款", "经典", "轻薄", "旗舰", "高端", "便携", "专业版", "升级款"];
+
+const EN_ADJECTIVES: &[&str] = &["Classic", "Pro", "Lite", "Premium", "Essential", "Ultra", "Compact"];
+
+const MODEL_PREFIXES: &[&str] = &["X", "S", "M", "Pro ", "Max ", "A"];
+
+const ZH_SELLING_POINTS: &[&str] = &[
+    "品质保证，七天无理由退换",
+    "限时优惠，满减包邮",
+    "官方正品，全国联保",
+    "热销爆款，好评如潮",
+    "新品上市，抢先体验",
+];
+
+const EN_SELLING_POINTS: &[&str] = &[
+    "Free returns within 7 days.",
+    "Limited-time offer with free shipping.",
+    "Official product with nationwide warranty.",
+    "Best seller with thousands of reviews.",
+    "New arrival.",
+];
+
+const LOG_ACTIONS: &[(&str, &str)] = &[
+    ("login", "登录系统"),
+    ("logout", "退出登录"),
+    ("create_product", "新增商品"),
+    ("update_product", "修改商品"),
+    ("delete_product", "删除商品"),
+    ("update_order", "更新订单状态"),
+    ("create_user", "新增用户"),
+    ("update_user", "修改用户"),
+    ("update_settings", "修改系统设置"),
+    ("export", "导出数据"),
+];
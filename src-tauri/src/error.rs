@@ -0,0 +1,101 @@
+use std::fmt;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 服务层和仓储返回的领域错误
+#[derive(Debug)]
+pub enum AppError {
+    /// 记录不存在
+    NotFound(String),
+    /// 与已有数据冲突，例如用户名重复
+    Conflict(String),
+    /// 提交的版本号已过期，附带服务端当前的数据
+    VersionConflict(serde_json::Value),
+    /// 不满足业务规则
+    Invalid(String),
+    /// 数据库错误
+    Database(sqlx::Error),
+    /// 其他内部错误
+    Internal(String),
+}
+
+impl AppError {
+    /// 业务错误可以直接提示给用户，数据库和内部错误属于命令执行失败
+    pub fn is_business(&self) -> bool {
+        matches!(
+            self,
+            AppError::NotFound(_) | AppError::Conflict(_) | AppError::VersionConflict(_) | AppError::Invalid(_)
+        )
+    }
+
+    /// 版本冲突错误，`current` 为数据库中的最新记录
+    pub fn version_conflict(current: &impl Serialize) -> Self {
+        match serde_json::to_value(current) {
+            Ok(value) => AppError::VersionConflict(value),
+            Err(e) => e.into(),
+        }
+    }
+
+    /// 在返回给调用方之前处理冲突中的最新记录，例如脱敏；其他错误原样返回
+    pub fn map_current<T, F>(self, f: F) -> Self
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce(&mut T),
+    {
+        match self {
+            AppError::VersionConflict(value) => match serde_json::from_value::<T>(value) {
+                Ok(mut current) => {
+                    f(&mut current);
+                    AppError::version_conflict(&current)
+                }
+                Err(e) => e.into(),
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::Invalid(message)
+            | AppError::Internal(message) => f.write_str(message),
+            AppError::VersionConflict(_) => f.write_str("数据已被其他人修改，请刷新后重试"),
+            AppError::Database(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        AppError::Database(e)
+    }
+}
+
+impl From<bcrypt::BcryptError> for AppError {
+    fn from(e: bcrypt::BcryptError) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        AppError::Internal(e.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(e: AppError) -> Self {
+        e.to_string()
+    }
+}
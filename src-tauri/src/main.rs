@@ -3,32 +3,108 @@
 
 mod api;
 mod database;
+mod error;
 mod models;
+mod repositories;
+mod services;
 mod utils;
 
-use database::Database;
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use database::{Database, DatabaseConfig};
+use database::health::DatabaseCorrupted;
+use database::seed::SeedOptions;
+use tauri::api::notification::Notification;
+use tauri::http::ResponseBuilder;
+use utils::permissions::PermissionCache;
 
 pub struct AppState {
-    db: Arc<Mutex<Database>>,
+    db: Database, // 内部是连接池，命令之间无需加锁即可并发访问
+    permission_cache: PermissionCache,
 }
 
 #[tokio::main]
 async fn main() {
+    let context = tauri::generate_context!();
+
+    // 确定数据库位置（默认使用平台的应用数据目录）
+    let db_config = DatabaseConfig::resolve(
+        tauri::api::path::app_data_dir(context.config()),
+        tauri::api::path::app_config_dir(context.config()),
+    )
+    .expect("Failed to resolve database location");
+
     // 初始化数据库
-    let db = Database::new().await.expect("Failed to initialize database");
+    let db = open_database(&db_config).await;
+
+    // 启动参数 --seed-demo 要求时写入演示数据
+    let seed_options = SeedOptions::from_args().unwrap_or_else(|e| exit_with_error(&format!("{:#}", e)));
+    if let Some(options) = seed_options {
+        match database::seed::seed_demo_data(&db.pool, &options).await {
+            Ok(summary) => println!("Demo data seeded in {} ms", summary.elapsed_ms),
+            Err(e) => exit_with_error(&format!("生成演示数据失败: {:#}", e)),
+        }
+    }
+
+    // 启动定时备份
+    database::backup::spawn_scheduler(db.clone());
+
+    // 启动回收站自动清除
+    services::recycle_bin::spawn_purge_task(db.pool.clone());
+
+    // 启动日志保留期清除
+    services::log_retention::spawn_retention_task(db.pool.clone(), db.archive_dir());
+
+    // 启动自动同步（配置了中心节点时）
+    services::sync::spawn_sync_task(db.pool.clone());
+
+    // 启动库存预警，新跌破预警值的商品通过系统通知提醒
+    let identifier = context.config().tauri.bundle.identifier.clone();
+    services::stock::spawn_low_stock_task(db.pool.clone(), move |products| {
+        let body = match products {
+            [product] => format!("“{}”库存 {}，已不高于预警值 {}", product.product_name, product.stock, product.threshold),
+            [first, ..] => format!("“{}”等 {} 个商品库存不足，请及时补货", first.product_name, products.len()),
+            [] => return,
+        };
+        if let Err(e) = Notification::new(&identifier).title("库存预警").body(body).show() {
+            println!("Failed to show low stock notification: {}", e);
+        }
+    });
+
+    // 启动图片清理，删除不再被商品引用的图片
+    let asset_dir = db.asset_dir();
+    services::asset::spawn_cleanup_task(db.pool.clone(), asset_dir.clone());
+
     let app_state = AppState {
-        db: Arc::new(Mutex::new(db)),
+        db,
+        permission_cache: PermissionCache::default(),
     };
 
     tauri::Builder::default()
         .manage(app_state)
+        // 商品图片通过 media 协议提供给前端，文件名就是内容的哈希，可以长期缓存
+        .register_uri_scheme_protocol("media", move |_app, request| {
+            let file = services::asset::resolve(&asset_dir, request.uri())
+                .and_then(|(path, mime_type)| std::fs::read(path).ok().map(|bytes| (bytes, mime_type)));
+            match file {
+                Some((bytes, mime_type)) => ResponseBuilder::new()
+                    .mimetype(mime_type)
+                    .header("Cache-Control", "max-age=31536000, immutable")
+                    .body(bytes),
+                None => ResponseBuilder::new().status(404).body(Vec::new()),
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             // 认证相关
             api::auth::login,
             api::auth::logout,
             api::auth::get_current_user,
+            api::auth::check_permission,
+            api::auth::get_user_permissions,
+            
+            // 菜单管理
+            api::menus::get_menu_tree,
+            api::menus::get_all_menus,
+            api::menus::update_menu,
+            api::menus::sort_menus,
             
             // 用户管理
             api::users::get_users,
@@ -40,8 +116,17 @@ async fn main() {
             api::products::get_products,
             api::products::create_product,
             api::products::update_product,
+            api::products::get_product_variants,
             api::products::delete_product,
             
+            // 库存
+            api::stock::adjust_stock,
+            api::stock::get_stock_movements,
+            api::stock::check_stock_reconciliation,
+            api::stock::get_low_stock_products,
+            api::assets::import_product_image,
+            api::assets::cleanup_assets,
+            
             // 订单管理
             api::orders::get_orders,
             api::orders::create_order,
@@ -55,6 +140,7 @@ async fn main() {
             
             // 角色管理
             api::roles::get_roles,
+            api::roles::update_role,
             
             // 统计信息
             api::stats::get_dashboard_stats,
@@ -64,11 +150,105 @@ async fn main() {
             api::logs::get_logs,
             api::logs::create_log,
             api::logs::get_login_logs,
+            api::logs::get_log_retention_status,
+            api::logs::run_log_retention,
+            api::logs::get_log_retention_settings,
+            api::logs::save_log_retention_settings,
             api::settings::get_all_settings,
             api::settings::save_system_settings,
             api::settings::save_ui_settings,
             api::settings::save_security_settings,
+            api::settings::save_money_settings,
+            
+            // 数据库维护
+            api::maintenance::get_migration_status,
+            api::maintenance::rollback_migration,
+            api::maintenance::check_database_integrity,
+            api::maintenance::vacuum_database,
+            api::maintenance::analyze_database,
+            api::maintenance::checkpoint_database,
+            api::maintenance::get_database_stats,
+            api::maintenance::seed_demo_data,
+            
+            // 备份与恢复
+            api::backup::create_backup,
+            api::backup::list_backups,
+            api::backup::restore_backup,
+            api::backup::get_backup_settings,
+            api::backup::save_backup_settings,
+            
+            // 回收站
+            api::recycle_bin::get_recycle_bin,
+            api::recycle_bin::restore_deleted,
+            api::recycle_bin::purge_recycle_bin,
+            api::recycle_bin::get_recycle_bin_settings,
+            api::recycle_bin::save_recycle_bin_settings,
+            // 店铺管理
+            api::tenants::get_my_tenants,
+            api::tenants::switch_tenant,
+            api::tenants::get_tenants,
+            api::tenants::create_tenant,
+            api::tenants::update_tenant,
+            api::tenants::get_tenant_members,
+            api::tenants::add_tenant_member,
+            api::tenants::remove_tenant_member,
+            api::tenants::export_tenant,
+            // 数据同步
+            api::sync::get_sync_status,
+            api::sync::get_sync_conflicts,
+            api::sync::run_sync,
+            api::sync::get_sync_settings,
+            api::sync::save_sync_settings,
         ])
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running tauri application");
 }
+
+// 辅助函数：打开数据库；文件损坏时询问是否从最新备份恢复，其他错误提示后退出
+async fn open_database(config: &DatabaseConfig) -> Database {
+    let error = match Database::new(config).await {
+        Ok(db) => return db,
+        Err(e) => e,
+    };
+    eprintln!("Failed to initialize database: {:#}", error);
+
+    let Some(corrupted) = error.downcast_ref::<DatabaseCorrupted>() else {
+        exit_with_error(&format!("数据库初始化失败: {:#}", error));
+    };
+
+    let question = format!(
+        "{}\n\n是否从最新的备份恢复？损坏的数据库文件会移到备份目录中保留。",
+        corrupted
+    );
+    // 此时 Tauri 的事件循环还没有启动，tauri::api::dialog 会一直等待，只能直接用 rfd 同步显示
+    let recover = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title("数据库已损坏")
+        .set_description(&question)
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show();
+    if !recover {
+        exit_with_error("数据库已损坏，应用无法启动。可以在备份目录中找到之前的备份。");
+    }
+
+    if let Err(e) = database::backup::recover_from_latest_backup(config).await {
+        exit_with_error(&format!("从备份恢复失败: {:#}", e));
+    }
+
+    match Database::new(config).await {
+        Ok(db) => db,
+        Err(e) => exit_with_error(&format!("恢复后仍无法打开数据库: {:#}", e)),
+    }
+}
+
+// 启动阶段的致命错误：写到 stderr，同时弹窗提示（Windows 发布版没有控制台），然后退出
+fn exit_with_error(message: &str) -> ! {
+    eprintln!("{}", message);
+    rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Error)
+        .set_title("Rust Admin")
+        .set_description(message)
+        .set_buttons(rfd::MessageButtons::Ok)
+        .show();
+    std::process::exit(1);
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// 导入后的图片。`reference` 保存到商品的 images 或规格的 image 中，
+/// 前端换算为 media 协议的地址显示
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Asset {
+    /// 形如 `media:<sha256>.jpg`
+    pub reference: String,
+    /// 原始文件内容的 SHA-256，相同的文件只保存一份
+    pub hash: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    /// 去掉 EXIF 后保存的文件大小（字节）
+    pub size: u64,
+    /// 已经导入过相同的文件，没有重新生成
+    pub deduplicated: bool,
+}
+
+/// 一次清理的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AssetCleanup {
+    pub files_removed: usize,
+    pub bytes_freed: u64,
+}
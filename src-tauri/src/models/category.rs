@@ -5,11 +5,15 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct Category {
     pub id: i64,
+    pub tenant_id: i64,
     pub name: String,
     pub parent_id: Option<i64>,
     pub sort_order: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// 分类下商品的默认库存预警值，商品可以单独设置
+    pub reorder_threshold: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Default)]
@@ -21,6 +25,8 @@ pub struct CategoryWithParent {
     pub sort_order: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    pub reorder_threshold: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -28,6 +34,8 @@ pub struct CreateCategoryRequest {
     pub name: String,
     pub parent_id: Option<i64>,
     pub sort_order: i32,
+    #[serde(default)]
+    pub reorder_threshold: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,4 +43,8 @@ pub struct UpdateCategoryRequest {
     pub name: String,
     pub parent_id: Option<i64>,
     pub sort_order: i32,
+    #[serde(default)]
+    pub reorder_threshold: Option<i32>,
+    /// 读取分类时的版本号
+    pub version: i64,
 } 
\ No newline at end of file
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct Log {
     pub id: i64,
     pub user_id: Option<i64>,
@@ -29,4 +29,33 @@ pub struct LogWithUser {
     pub resource: String,
     pub details: Option<String>,
     pub created_at: DateTime<Utc>,
-} 
\ No newline at end of file
+} 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogRetentionSettings {
+    /// 操作日志保留天数，0 表示永久保留
+    pub log_days: i64,
+    /// 登录日志保留天数，0 表示永久保留
+    pub login_log_days: i64,
+    /// 清除前归档到压缩文件
+    pub archive: bool,
+}
+
+impl Default for LogRetentionSettings {
+    fn default() -> Self {
+        Self {
+            log_days: 180,
+            login_log_days: 90,
+            archive: false,
+        }
+    }
+}
+
+/// 最近一次日志清除的结果
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct LogRetentionStatus {
+    pub last_run: Option<DateTime<Utc>>,
+    pub logs_purged: u64,
+    pub login_logs_purged: u64,
+    pub archive_files: Vec<String>,
+    pub error: Option<String>,
+}
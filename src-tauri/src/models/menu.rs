@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Default, Clone)]
+pub struct Menu {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    pub title: String, // 国际化 key 或直接显示的标题
+    pub path: Option<String>,
+    pub icon: Option<String>,
+    pub permission: Option<String>, // 访问该菜单所需的权限，为空表示不限制
+    pub sort_order: i32,
+    pub visible: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct MenuTreeNode {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub name: String,
+    pub title: String,
+    pub path: Option<String>,
+    pub icon: Option<String>,
+    pub permission: Option<String>,
+    pub sort_order: i32,
+    pub visible: i32,
+    pub children: Vec<MenuTreeNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateMenuRequest {
+    pub parent_id: Option<i64>,
+    pub title: String,
+    pub path: Option<String>,
+    pub icon: Option<String>,
+    pub permission: Option<String>,
+    pub sort_order: i32,
+    pub visible: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MenuSortItem {
+    pub id: i64,
+    pub parent_id: Option<i64>,
+    pub sort_order: i32,
+}
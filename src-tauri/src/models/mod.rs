@@ -5,4 +5,11 @@ pub mod category;
 pub mod role;
 pub mod stats;
 pub mod log;
-pub mod settings; // 添加这行 
\ No newline at end of file
+pub mod settings; // 添加这行
+pub mod menu;
+pub mod recycle_bin;
+pub mod tenant;
+pub mod sync;
+pub mod money;
+pub mod stock;
+pub mod asset;
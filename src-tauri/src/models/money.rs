@@ -0,0 +1,278 @@
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// 默认币种：人民币
+pub const DEFAULT_CURRENCY: &str = "CNY";
+
+/// 支持的币种及其小数位数（最小货币单位是 1 / 10^位数）
+const CURRENCIES: &[(&str, u32)] = &[
+    ("CNY", 2),
+    ("HKD", 2),
+    ("TWD", 2),
+    ("USD", 2),
+    ("EUR", 2),
+    ("GBP", 2),
+    ("SGD", 2),
+    ("JPY", 0),
+    ("KRW", 0),
+];
+
+/// 币种的小数位数，不支持的币种返回 `None`
+pub fn currency_digits(currency: &str) -> Option<u32> {
+    CURRENCIES.iter().find(|(code, _)| *code == currency).map(|(_, digits)| *digits)
+}
+
+/// 金额：最小货币单位（例如分）的整数加上币种代码，计算过程中不会产生浮点误差
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Money {
+    pub amount: i64,
+    pub currency: String,
+}
+
+impl Money {
+    pub fn new(amount: i64, currency: impl Into<String>) -> Self {
+        Self { amount, currency: currency.into() }
+    }
+
+    pub fn zero(currency: impl Into<String>) -> Self {
+        Self::new(0, currency)
+    }
+
+    /// 相加，币种不同或溢出时返回 `None`
+    pub fn checked_add(&self, other: &Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+        Some(Money::new(self.amount.checked_add(other.amount)?, self.currency.clone()))
+    }
+
+    /// 乘以数量（单价 × 件数），溢出时返回 `None`
+    pub fn checked_mul(&self, quantity: i64) -> Option<Money> {
+        Some(Money::new(self.amount.checked_mul(quantity)?, self.currency.clone()))
+    }
+}
+
+/// 输入金额的小数位数超过币种允许的位数时的舍入方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    /// 四舍五入
+    #[default]
+    HalfUp,
+    /// 银行家舍入：恰好一半时取偶数
+    HalfEven,
+    /// 直接舍去（向零取整）
+    Down,
+    /// 只要有余数就进位（远离零）
+    Up,
+}
+
+impl RoundingMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoundingMode::HalfUp => "half_up",
+            RoundingMode::HalfEven => "half_even",
+            RoundingMode::Down => "down",
+            RoundingMode::Up => "up",
+        }
+    }
+}
+
+impl FromStr for RoundingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "half_up" => Ok(RoundingMode::HalfUp),
+            "half_even" => Ok(RoundingMode::HalfEven),
+            "down" => Ok(RoundingMode::Down),
+            "up" => Ok(RoundingMode::Up),
+            _ => Err(format!("不支持的舍入方式: {}", s)),
+        }
+    }
+}
+
+/// 店铺的金额设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoneySettings {
+    /// 新建商品和订单使用的币种
+    pub currency: String,
+    pub rounding: RoundingMode,
+}
+
+impl Default for MoneySettings {
+    fn default() -> Self {
+        Self { currency: DEFAULT_CURRENCY.to_string(), rounding: RoundingMode::default() }
+    }
+}
+
+/// 请求中以元为单位的十进制金额，接受 `"12.30"` 或 `12.3`，按文本解析，不经过浮点运算
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct DecimalAmount(pub String);
+
+impl<'de> Deserialize<'de> for DecimalAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Text(String),
+            Number(serde_json::Number),
+        }
+
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Text(text) => DecimalAmount(text),
+            Raw::Number(number) => DecimalAmount(number.to_string()),
+        })
+    }
+}
+
+impl DecimalAmount {
+    /// 换算为店铺币种的金额，多出的小数位按店铺的舍入方式处理
+    pub fn to_money(&self, settings: &MoneySettings) -> Result<Money, String> {
+        let digits = currency_digits(&settings.currency).ok_or_else(|| format!("不支持的币种: {}", settings.currency))?;
+        let amount = parse_minor_units(&self.0, digits, settings.rounding)
+            .ok_or_else(|| format!("金额格式不正确: {}", self.0))?;
+        Ok(Money::new(amount, settings.currency.clone()))
+    }
+
+    /// 与 `to_money` 相同，但不允许负数，用于商品价格
+    pub fn to_price(&self, settings: &MoneySettings) -> Result<Money, String> {
+        let price = self.to_money(settings)?;
+        if price.amount < 0 {
+            return Err("价格不能为负数".to_string());
+        }
+        Ok(price)
+    }
+}
+
+// 解析 `-12.345` 形式的十进制文本，换算为 `digits` 位小数的最小单位；格式错误或溢出时返回 None
+fn parse_minor_units(text: &str, digits: u32, mode: RoundingMode) -> Option<i64> {
+    let text = text.trim();
+    let (negative, text) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (integer, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if integer.is_empty() && fraction.is_empty()
+        || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let digits = digits as usize;
+    let (kept, dropped) = fraction.split_at(fraction.len().min(digits));
+    let mut units: i64 = 0;
+    for c in integer.chars().chain(kept.chars()).chain(std::iter::repeat_n('0', digits - kept.len())) {
+        units = units.checked_mul(10)?.checked_add(c.to_digit(10)? as i64)?;
+    }
+
+    // 根据被舍去的部分决定是否进位：与一半比较即可，不需要知道精确值
+    let first = dropped.chars().next().and_then(|c| c.to_digit(10)).unwrap_or(0);
+    let rest_nonzero = dropped.chars().skip(1).any(|c| c != '0');
+    let round_up = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Up => first > 0 || rest_nonzero,
+        RoundingMode::HalfUp => first >= 5,
+        RoundingMode::HalfEven => first > 5 || (first == 5 && (rest_nonzero || units % 2 == 1)),
+    };
+    if round_up {
+        units = units.checked_add(1)?;
+    }
+
+    Some(if negative { -units } else { units })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str, digits: u32, mode: RoundingMode) -> Option<i64> {
+        parse_minor_units(text, digits, mode)
+    }
+
+    #[test]
+    fn parses_plain_amounts() {
+        assert_eq!(parse("12.30", 2, RoundingMode::HalfUp), Some(1230));
+        assert_eq!(parse("12.3", 2, RoundingMode::HalfUp), Some(1230));
+        assert_eq!(parse("12", 2, RoundingMode::HalfUp), Some(1200));
+        assert_eq!(parse(".5", 2, RoundingMode::HalfUp), Some(50));
+        assert_eq!(parse("7.", 2, RoundingMode::HalfUp), Some(700));
+        assert_eq!(parse(" +1.05 ", 2, RoundingMode::HalfUp), Some(105));
+        assert_eq!(parse("1500", 0, RoundingMode::HalfUp), Some(1500));
+    }
+
+    #[test]
+    fn rejects_malformed_text() {
+        for text in ["", ".", "-", "abc", "1.2.3", "1,00", "1e3", "--1", "1 2"] {
+            assert_eq!(parse(text, 2, RoundingMode::HalfUp), None, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn rounds_extra_fraction_digits_half_up() {
+        assert_eq!(parse("1.234", 2, RoundingMode::HalfUp), Some(123));
+        assert_eq!(parse("1.235", 2, RoundingMode::HalfUp), Some(124));
+        assert_eq!(parse("1.2349999", 2, RoundingMode::HalfUp), Some(123));
+        assert_eq!(parse("0.5", 0, RoundingMode::HalfUp), Some(1));
+    }
+
+    #[test]
+    fn rounds_ties_to_even() {
+        assert_eq!(parse("1.225", 2, RoundingMode::HalfEven), Some(122));
+        assert_eq!(parse("1.235", 2, RoundingMode::HalfEven), Some(124));
+        assert_eq!(parse("1.2250", 2, RoundingMode::HalfEven), Some(122));
+        // 超过一半就不是平局，总是进位
+        assert_eq!(parse("1.2251", 2, RoundingMode::HalfEven), Some(123));
+        assert_eq!(parse("1.224", 2, RoundingMode::HalfEven), Some(122));
+        assert_eq!(parse("0.5", 0, RoundingMode::HalfEven), Some(0));
+        assert_eq!(parse("1.5", 0, RoundingMode::HalfEven), Some(2));
+        assert_eq!(parse("2.5", 0, RoundingMode::HalfEven), Some(2));
+    }
+
+    #[test]
+    fn rounds_down_and_up() {
+        assert_eq!(parse("1.239", 2, RoundingMode::Down), Some(123));
+        assert_eq!(parse("1.230", 2, RoundingMode::Up), Some(123));
+        assert_eq!(parse("1.2301", 2, RoundingMode::Up), Some(124));
+        assert_eq!(parse("1.231", 2, RoundingMode::Up), Some(124));
+        assert_eq!(parse("0.001", 0, RoundingMode::Up), Some(1));
+    }
+
+    #[test]
+    fn rounds_negative_amounts_by_magnitude() {
+        assert_eq!(parse("-1.235", 2, RoundingMode::HalfUp), Some(-124));
+        assert_eq!(parse("-1.225", 2, RoundingMode::HalfEven), Some(-122));
+        assert_eq!(parse("-1.239", 2, RoundingMode::Down), Some(-123));
+        assert_eq!(parse("-1.231", 2, RoundingMode::Up), Some(-124));
+        assert_eq!(parse("-0", 2, RoundingMode::HalfUp), Some(0));
+    }
+
+    #[test]
+    fn reports_overflow() {
+        // i64::MAX = 9223372036854775807
+        assert_eq!(parse("92233720368547758.07", 2, RoundingMode::HalfUp), Some(i64::MAX));
+        assert_eq!(parse("92233720368547758.08", 2, RoundingMode::HalfUp), None);
+        assert_eq!(parse("92233720368547758.075", 2, RoundingMode::Up), None);
+        assert_eq!(parse("92233720368547758.074", 2, RoundingMode::Down), Some(i64::MAX));
+        assert_eq!(parse("99999999999999999999", 0, RoundingMode::HalfUp), None);
+    }
+
+    #[test]
+    fn converts_with_shop_settings() {
+        let settings = MoneySettings { currency: "JPY".to_string(), rounding: RoundingMode::HalfUp };
+        assert_eq!(DecimalAmount("1500.5".to_string()).to_money(&settings), Ok(Money::new(1501, "JPY")));
+        assert!(DecimalAmount("-1".to_string()).to_price(&settings).is_err());
+
+        let unknown = MoneySettings { currency: "XXX".to_string(), rounding: RoundingMode::HalfUp };
+        assert!(DecimalAmount("1".to_string()).to_money(&unknown).is_err());
+    }
+
+    #[test]
+    fn rounding_mode_round_trips_through_text() {
+        for mode in [RoundingMode::HalfUp, RoundingMode::HalfEven, RoundingMode::Down, RoundingMode::Up] {
+            assert_eq!(mode.as_str().parse::<RoundingMode>(), Ok(mode));
+        }
+        assert!("nearest".parse::<RoundingMode>().is_err());
+    }
+}
@@ -1,28 +1,38 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct Order {
     pub id: i64,
+    pub tenant_id: i64,
     pub order_no: String,
     pub user_id: i64,
-    pub total_amount: f64,
+    pub total_amount: i64, // 最小货币单位（分）
+    pub currency: String,
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct OrderWithUser {
     pub id: i64,
     pub order_no: String,
     pub user_id: i64,
     pub username: String,
-    pub total_amount: f64,
+    pub total_amount: i64,
+    pub currency: String,
     pub status: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// 搜索命中时订单号的高亮片段
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -31,9 +41,12 @@ pub struct OrderItem {
     pub order_id: i64,
     pub product_id: i64,
     pub product_name: String,
-    pub price: f64,
+    pub price: i64, // 下单时的单价，币种与订单相同
     pub quantity: i32,
     pub created_at: DateTime<Utc>,
+    /// 购买的规格，`sku` 为下单时的快照
+    pub variant_id: Option<i64>,
+    pub sku: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -45,5 +58,19 @@ pub struct CreateOrderRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateOrderItemRequest {
     pub product_id: i64,
+    /// 有规格的商品必须选择其中一个规格
+    #[serde(default)]
+    pub variant_id: Option<i64>,
+    pub quantity: i32,
+}
+
+/// 待写入的订单明细，单价和名称已经按下单时的商品和规格确定
+#[derive(Debug)]
+pub struct NewOrderItem {
+    pub product_id: i64,
+    pub variant_id: Option<i64>,
+    pub sku: Option<String>,
+    pub product_name: String,
+    pub price: i64,
     pub quantity: i32,
 } 
\ No newline at end of file
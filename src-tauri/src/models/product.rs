@@ -1,12 +1,16 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
+use crate::models::money::DecimalAmount;
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct Product {
     pub id: i64,
+    pub tenant_id: i64,
     pub name: String,
-    pub price: f64,
+    pub price: i64, // 最小货币单位（分）
+    pub currency: String,
     pub description: Option<String>,
     pub category_id: Option<i64>,
     pub stock: i32,
@@ -14,40 +18,197 @@ pub struct Product {
     pub status: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// 规格项定义，`VariantOption` 数组的 JSON 字符串；没有规格时为空
+    pub variant_options: Option<String>,
+    /// 库存预警值，为空时使用所属分类的预警值
+    pub reorder_threshold: Option<i32>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct ProductWithCategory {
     pub id: i64,
     pub name: String,
-    pub price: f64,
+    pub price: i64,
+    pub currency: String,
     pub description: Option<String>,
     pub category_id: Option<i64>,
     pub category_name: Option<String>,
+    /// 有规格的商品为各规格库存之和
     pub stock: i32,
     pub images: Option<String>,
     pub status: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    pub variant_count: i64,
+    pub reorder_threshold: Option<i32>,
+    /// 搜索时命中关键词的字段及其高亮片段（HTML，关键词用 `<mark>` 包裹）
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateProductRequest {
     pub name: String,
-    pub price: f64,
+    /// 以元为单位，按店铺的币种和舍入方式换算
+    pub price: DecimalAmount,
     pub description: Option<String>,
     pub category_id: Option<i64>,
+    /// 初始库存，记为一笔入库流水
     pub stock: i32,
     pub images: Vec<String>,
+    /// 库存预警值，为空时使用所属分类的预警值
+    #[serde(default)]
+    pub reorder_threshold: Option<i32>,
+    /// 规格项和规格，不提交时商品没有规格
+    #[serde(default)]
+    pub variants: Option<VariantsRequest>,
 }
 
+/// 修改商品资料；库存不能直接改写，只能通过库存调整或订单变化
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateProductRequest {
     pub name: String,
-    pub price: f64,
+    pub price: DecimalAmount,
     pub description: Option<String>,
     pub category_id: Option<i64>,
-    pub stock: i32,
     pub images: Vec<String>,
     pub status: i32,
-} 
\ No newline at end of file
+    #[serde(default)]
+    pub reorder_threshold: Option<i32>,
+    /// 读取商品时的版本号，与当前版本不一致说明已被他人修改
+    pub version: i64,
+    /// 提交时整体替换商品的规格项和规格，不提交时保持不变
+    #[serde(default)]
+    pub variants: Option<VariantsRequest>,
+}
+
+/// 规格项，例如尺码：S、M、L
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantOption {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+/// 商品的一个规格组合，有自己的 SKU、条码、价格、库存和图片
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductVariant {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub product_id: i64,
+    pub sku: String,
+    pub barcode: Option<String>,
+    /// 各规格项的取值，`{"尺码":"M","颜色":"红"}` 形式的 JSON 字符串
+    pub options: String,
+    /// 单独设置的价格（最小货币单位，币种与商品相同），为空时使用商品价格
+    pub price: Option<i64>,
+    pub stock: i32,
+    pub image: Option<String>,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 商品的规格项定义和全部规格
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductVariants {
+    pub options: Vec<VariantOption>,
+    pub variants: Vec<ProductVariant>,
+}
+
+/// 一起提交的规格项和规格；每个规格必须为每个规格项选择一个已定义的值，且组合不能重复
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VariantsRequest {
+    pub options: Vec<VariantOption>,
+    pub variants: Vec<VariantRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VariantRequest {
+    /// 修改已有的规格时填写，为空时新建
+    pub id: Option<i64>,
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub options: BTreeMap<String, String>,
+    /// 以元为单位，为空时使用商品价格
+    pub price: Option<DecimalAmount>,
+    /// 只在新建规格时作为初始库存，已有规格的库存通过库存调整修改
+    #[serde(default)]
+    pub stock: i32,
+    pub image: Option<String>,
+}
+/// 商品列表的筛选和排序条件，所有字段都可以省略
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProductQuery {
+    pub category_id: Option<i64>,
+    /// 同时包含 `category_id` 下所有子分类的商品
+    pub include_subcategories: bool,
+    /// 价格区间，单位为最小货币单位（分）
+    pub min_price: Option<i64>,
+    pub max_price: Option<i64>,
+    pub min_stock: Option<i32>,
+    pub max_stock: Option<i32>,
+    pub status: Option<i32>,
+    /// 时间范围两端都包含在内
+    pub created_from: Option<DateTime<Utc>>,
+    pub created_to: Option<DateTime<Utc>>,
+    pub updated_from: Option<DateTime<Utc>>,
+    pub updated_to: Option<DateTime<Utc>>,
+    pub sort_by: ProductSortField,
+    pub sort_order: SortOrder,
+}
+
+/// 商品列表可以排序的字段
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProductSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Name,
+    Price,
+    Stock,
+}
+
+impl ProductSortField {
+    pub fn column(self) -> &'static str {
+        match self {
+            ProductSortField::CreatedAt => "p.created_at",
+            ProductSortField::UpdatedAt => "p.updated_at",
+            ProductSortField::Name => "p.name",
+            ProductSortField::Price => "p.price",
+            ProductSortField::Stock => "p.stock",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
+
+/// 写入数据库的规格字段：取值已序列化为 JSON，价格已按店铺币种换算
+#[derive(Debug)]
+pub struct VariantFields {
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub options: String,
+    pub price: Option<i64>,
+    pub image: Option<String>,
+    pub sort_order: i32,
+}
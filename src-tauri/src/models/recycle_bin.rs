@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+/// 支持软删除的实体类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecycleBinEntity {
+    User,
+    Product,
+    Category,
+}
+
+impl RecycleBinEntity {
+    pub const ALL: [RecycleBinEntity; 3] = [Self::User, Self::Product, Self::Category];
+
+    /// 查看和恢复该类数据所需的权限，与删除时要求的写权限一致
+    pub fn write_permission(self) -> &'static str {
+        match self {
+            Self::User => "user:write",
+            Self::Product => "product:write",
+            Self::Category => "category:write",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
+pub struct RecycleBinItem {
+    #[sqlx(skip)]
+    pub entity: Option<RecycleBinEntity>,
+    pub id: i64,
+    pub name: String,
+    pub deleted_at: DateTime<Utc>,
+    pub deleted_by: Option<i64>,
+    pub deleted_by_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PurgeResult {
+    pub users: u64,
+    pub products: u64,
+    pub categories: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecycleBinSettings {
+    pub retention_days: i64,
+}
+
+impl Default for RecycleBinSettings {
+    fn default() -> Self {
+        Self { retention_days: 30 }
+    }
+}
@@ -2,10 +2,16 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct Role {
     pub id: i64,
     pub name: String,
     pub permissions: String,
     pub created_at: DateTime<Utc>,
-} 
\ No newline at end of file
+} 
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateRoleRequest {
+    pub name: String,
+    pub permissions: Vec<String>,
+}
@@ -5,6 +5,7 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct SystemSetting {
     pub id: i64,
+    pub tenant_id: i64,
     pub setting_key: String,
     pub setting_value: String,
     pub setting_type: String,
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::money::Money;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DashboardStats {
@@ -6,6 +7,8 @@ pub struct DashboardStats {
     pub total_products: i64,
     pub total_orders: i64,
     pub total_categories: i64,
+    /// 未取消订单的金额合计，每个币种一项
+    pub total_revenue: Vec<Money>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
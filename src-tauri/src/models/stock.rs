@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+/// 库存变化的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementType {
+    /// 下单出库，由订单自动记录
+    Sale,
+    /// 退货入库，取消订单时自动记录，也可以手工登记
+    Return,
+    /// 盘点等原因的调整，可增可减
+    Adjustment,
+    /// 采购收货入库
+    Receipt,
+    /// 调拨，调入为正、调出为负
+    Transfer,
+}
+
+impl MovementType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MovementType::Sale => "sale",
+            MovementType::Return => "return",
+            MovementType::Adjustment => "adjustment",
+            MovementType::Receipt => "receipt",
+            MovementType::Transfer => "transfer",
+        }
+    }
+}
+
+/// 一条库存流水
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StockMovement {
+    pub id: i64,
+    pub tenant_id: i64,
+    pub product_id: i64,
+    /// 为空时是商品本身的库存
+    pub variant_id: Option<i64>,
+    pub movement_type: String,
+    /// 变化量，入库为正、出库为负
+    pub quantity: i32,
+    /// 变化后的库存
+    pub stock_after: i32,
+    /// 单据号，例如订单号、采购单号
+    pub reference: Option<String>,
+    pub order_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub actor_name: Option<String>,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 待记录的库存变化
+#[derive(Debug)]
+pub struct NewStockMovement {
+    pub product_id: i64,
+    pub variant_id: Option<i64>,
+    pub movement_type: MovementType,
+    pub quantity: i32,
+    pub reference: Option<String>,
+    pub order_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub note: Option<String>,
+}
+
+/// 手工调整库存，必须说明原因；销售出库只能由订单产生
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdjustStockRequest {
+    pub product_id: i64,
+    /// 有规格的商品必须指定规格
+    pub variant_id: Option<i64>,
+    pub movement_type: MovementType,
+    /// 变化量，入库为正、出库为负
+    pub quantity: i32,
+    pub reference: Option<String>,
+    pub reason: String,
+}
+
+/// 对账发现的不一致：当前库存与流水合计不相等
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StockDiscrepancy {
+    pub product_id: i64,
+    pub variant_id: Option<i64>,
+    pub product_name: String,
+    pub sku: Option<String>,
+    pub stock: i64,
+    pub ledger_total: i64,
+}
+
+/// 库存不高于预警值的商品，有规格时按各规格库存之和计算
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LowStockProduct {
+    pub product_id: i64,
+    pub tenant_id: i64,
+    pub product_name: String,
+    pub category_name: Option<String>,
+    pub stock: i64,
+    /// 生效的预警值：商品自己的，或者所属分类的
+    pub threshold: i64,
+}
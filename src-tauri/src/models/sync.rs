@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+/// 参与同步的数据类型，值与表名一致
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncEntity {
+    Categories,
+    Products,
+    Orders,
+}
+
+impl SyncEntity {
+    /// 被引用的数据排在前面
+    pub const ALL: [SyncEntity; 3] = [Self::Categories, Self::Products, Self::Orders];
+
+    pub fn table(self) -> &'static str {
+        match self {
+            Self::Categories => "categories",
+            Self::Products => "products",
+            Self::Orders => "orders",
+        }
+    }
+
+    pub fn from_table(table: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|entity| entity.table() == table)
+    }
+}
+
+/// 推送到中心节点或从中心节点拉取的一条修改
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncChange {
+    pub entity: SyncEntity,
+    pub sync_id: String,
+    /// 修改的 HLC 时间戳，同一行以 HLC 较大的修改为准
+    pub hlc: String,
+    /// 产生修改的安装
+    pub node_id: String,
+    /// 修改后的整行数据，行被永久删除时为 None
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PushRequest<'a> {
+    pub node_id: &'a str,
+    pub changes: &'a [SyncChange],
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PushResponse {
+    pub accepted: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullResponse {
+    /// 按中心节点收到的顺序排列
+    pub changes: Vec<SyncChange>,
+    /// 本批最后一条修改在中心节点的序号，下次从它之后拉取
+    pub last_seq: i64,
+    pub has_more: bool,
+}
+
+/// 发件箱中一行数据最近的一次修改
+#[derive(Debug, FromRow)]
+pub struct OutboxEntry {
+    pub seq: i64,
+    pub entity: String,
+    pub sync_id: String,
+    /// 毫秒时间戳
+    pub changed_at: i64,
+}
+
+// 同步的整行数据中，外键都换成被引用行的 sync_id（用户换成用户名、店铺换成编码），
+// 本地自增 ID 在不同安装之间没有意义
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct CategorySyncData {
+    pub tenant_code: String,
+    pub tenant_name: String,
+    pub name: String,
+    pub parent_sync_id: Option<String>,
+    pub sort_order: i32,
+    pub status: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by_name: Option<String>,
+    #[serde(default)]
+    pub reorder_threshold: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ProductSyncData {
+    pub tenant_code: String,
+    pub tenant_name: String,
+    pub name: String,
+    pub price: i64,
+    pub currency: String,
+    pub description: Option<String>,
+    pub category_sync_id: Option<String>,
+    pub stock: i32,
+    pub images: Option<String>,
+    pub status: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by_name: Option<String>,
+    #[serde(default)]
+    pub variant_options: Option<String>,
+    #[serde(default)]
+    pub reorder_threshold: Option<i32>,
+    /// 未删除的规格，各安装之间按 SKU 对应
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub variants: Vec<VariantSyncData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct VariantSyncData {
+    pub sku: String,
+    pub barcode: Option<String>,
+    pub options: String,
+    pub price: Option<i64>,
+    pub stock: i32,
+    pub image: Option<String>,
+    pub sort_order: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrderSyncData {
+    pub tenant_code: String,
+    pub tenant_name: String,
+    pub order_no: String,
+    /// 下单用户，本地没有同名用户时记到默认管理员名下
+    pub username: Option<String>,
+    pub total_amount: i64,
+    pub currency: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub items: Vec<OrderItemSyncData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct OrderItemSyncData {
+    pub product_sync_id: Option<String>,
+    /// 购买的规格的 SKU，对应到同一商品下 SKU 相同的规格
+    #[serde(default)]
+    pub sku: Option<String>,
+    pub product_name: String,
+    pub price: i64,
+    pub quantity: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 收到但没有生效的修改
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SyncConflict {
+    pub id: i64,
+    pub entity: String,
+    pub sync_id: String,
+    pub local_hlc: Option<String>,
+    pub remote_hlc: String,
+    pub remote_node: String,
+    pub reason: String,
+    pub payload: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncSettings {
+    /// 中心节点地址，为空时不同步
+    pub hub_url: String,
+    pub token: String,
+    /// 自动同步间隔（分钟），0 表示只手动同步
+    pub interval_minutes: i64,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        Self {
+            hub_url: String::new(),
+            token: String::new(),
+            interval_minutes: 5,
+        }
+    }
+}
+
+/// 最近一次同步的结果
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SyncRunResult {
+    pub last_run: Option<DateTime<Utc>>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub pushed: usize,
+    pub pulled: usize,
+    pub applied: usize,
+    pub conflicts: usize,
+    pub error: Option<String>,
+}
+
+/// 一类数据的同步状态
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncEntityStatus {
+    pub entity: SyncEntity,
+    /// 有未推送修改的行数
+    pub pending: i64,
+    /// 已经同步过的行数
+    pub synced: i64,
+    /// 没有生效的远端修改数
+    pub conflicts: i64,
+    pub last_conflict_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub enabled: bool,
+    pub hub_url: String,
+    pub node_id: String,
+    /// 已拉取到的中心节点序号
+    pub pulled_seq: i64,
+    pub last_result: SyncRunResult,
+    pub entities: Vec<SyncEntityStatus>,
+}
@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use chrono::{DateTime, Utc};
+
+use super::category::Category;
+use super::order::{Order, OrderItem};
+use super::product::{Product, ProductVariant};
+use super::settings::SystemSetting;
+use super::stock::StockMovement;
+
+/// 店铺（租户），分类、商品、订单和店铺级设置都归属于一个店铺
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
+pub struct Tenant {
+    pub id: i64,
+    pub name: String,
+    /// 店铺编码，全局唯一，用于导出文件名等
+    pub code: String,
+    pub status: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub version: i64,
+}
+
+/// 用户可以切换到的店铺
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
+pub struct UserTenant {
+    pub id: i64,
+    pub name: String,
+    pub code: String,
+    /// 是否为用户当前所在的店铺
+    #[sqlx(default)]
+    pub current: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct TenantMember {
+    pub user_id: i64,
+    pub username: String,
+    pub role_name: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTenantRequest {
+    pub name: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateTenantRequest {
+    pub name: String,
+    pub status: i32,
+    /// 读取店铺时的版本号
+    pub version: i64,
+}
+
+/// 单个店铺的导出内容，写入导出目录下的 JSON 文件
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantExport {
+    pub exported_at: DateTime<Utc>,
+    pub tenant: Tenant,
+    /// 店铺单独设置的值，不含全局设置
+    pub settings: Vec<SystemSetting>,
+    pub members: Vec<TenantMember>,
+    pub categories: Vec<Category>,
+    pub products: Vec<Product>,
+    /// 包括已删除的规格，订单明细可能引用它们
+    pub product_variants: Vec<ProductVariant>,
+    pub stock_movements: Vec<StockMovement>,
+    pub orders: Vec<Order>,
+    pub order_items: Vec<OrderItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct TenantExportInfo {
+    pub path: String,
+    pub size: u64,
+    pub categories: usize,
+    pub products: usize,
+    pub orders: usize,
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use chrono::{DateTime, Utc};
@@ -19,9 +20,10 @@ pub struct User {
     pub last_login: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, FromRow, Default)]
 pub struct UserWithRole {
     pub id: i64,
     pub username: String,
@@ -35,6 +37,11 @@ pub struct UserWithRole {
     pub status: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
+    /// 搜索命中的用户名、邮箱高亮片段，按字段名索引
+    #[sqlx(skip)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
@@ -51,6 +58,7 @@ pub struct UserResponse {
     pub status: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub version: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -72,6 +80,8 @@ pub struct UpdateUserRequest {
     pub address: Option<String>,
     pub avatar: Option<String>,
     pub role_id: i64,
+    /// 编辑前读取到的版本号，与当前版本不一致说明已被他人修改
+    pub version: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
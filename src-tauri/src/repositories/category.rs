@@ -0,0 +1,181 @@
+use chrono::{DateTime, Utc};
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::category::*;
+use crate::models::recycle_bin::RecycleBinItem;
+use crate::with_conn;
+
+pub struct CategoryRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> CategoryRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, tenant_id: i64, id: i64) -> Result<Category, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = $1 AND tenant_id = $2 AND deleted_at IS NULL")
+                .bind(id)
+                .bind(tenant_id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("分类不存在".to_string()))
+    }
+
+    pub async fn list(&mut self, tenant_id: i64) -> Result<Vec<CategoryWithParent>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, CategoryWithParent>(
+                r#"
+                SELECT c.id, c.name, c.parent_id, p.name as parent_name, c.sort_order, c.created_at, c.updated_at, c.version, c.reorder_threshold
+                FROM categories c
+                LEFT JOIN categories p ON c.parent_id = p.id
+                WHERE c.tenant_id = $1 AND c.deleted_at IS NULL
+                ORDER BY c.sort_order
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn count(&mut self, tenant_id: i64) -> Result<i64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM categories WHERE tenant_id = $1 AND deleted_at IS NULL")
+                .bind(tenant_id)
+                .fetch_one(conn)
+                .await
+        })?)
+    }
+
+    /// 分类名在店铺内唯一，不同店铺可以有同名分类
+    pub async fn name_exists(&mut self, tenant_id: i64, name: &str) -> Result<bool, AppError> {
+        let count = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM categories WHERE name = $1 AND tenant_id = $2 AND deleted_at IS NULL")
+                .bind(name)
+                .bind(tenant_id)
+                .fetch_one(conn)
+                .await
+        })?;
+        Ok(count > 0)
+    }
+
+    pub async fn create(&mut self, tenant_id: i64, request: &CreateCategoryRequest) -> Result<Category, AppError> {
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO categories (tenant_id, name, parent_id, sort_order, reorder_threshold, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id"
+            )
+            .bind(tenant_id)
+            .bind(&request.name)
+            .bind(request.parent_id)
+            .bind(request.sort_order)
+            .bind(request.reorder_threshold)
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        self.find(tenant_id, id).await
+    }
+
+    pub async fn update(&mut self, tenant_id: i64, id: i64, request: &UpdateCategoryRequest) -> Result<Category, AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "UPDATE categories SET name = $1, parent_id = $2, sort_order = $3, reorder_threshold = $4, updated_at = $5, version = version + 1 WHERE id = $6 AND tenant_id = $7 AND deleted_at IS NULL AND version = $8"
+            )
+            .bind(&request.name)
+            .bind(request.parent_id)
+            .bind(request.sort_order)
+            .bind(request.reorder_threshold)
+            .bind(Utc::now())
+            .bind(id)
+            .bind(tenant_id)
+            .bind(request.version)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            // 记录仍然存在，说明提交的版本已过期
+            let current = self.find(tenant_id, id).await?;
+            return Err(AppError::version_conflict(&current));
+        }
+
+        self.find(tenant_id, id).await
+    }
+
+    /// 软删除：移入回收站，`deleted_by` 为操作人
+    pub async fn delete(&mut self, tenant_id: i64, id: i64, deleted_by: Option<i64>) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE categories SET deleted_at = $1, deleted_by = $2 WHERE id = $3 AND tenant_id = $4 AND deleted_at IS NULL")
+                .bind(Utc::now())
+                .bind(deleted_by)
+                .bind(id)
+                .bind(tenant_id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("分类不存在".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 回收站中的分类，最近删除的在前
+    pub async fn list_deleted(&mut self, tenant_id: i64) -> Result<Vec<RecycleBinItem>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, RecycleBinItem>(
+                r#"
+                SELECT t.id, t.name as name, t.deleted_at, t.deleted_by, d.username as deleted_by_name
+                FROM categories t
+                LEFT JOIN users d ON t.deleted_by = d.id
+                WHERE t.tenant_id = $1 AND t.deleted_at IS NOT NULL
+                ORDER BY t.deleted_at DESC
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn restore(&mut self, tenant_id: i64, id: i64) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE categories SET deleted_at = NULL, deleted_by = NULL, updated_at = $1 WHERE id = $2 AND tenant_id = $3 AND deleted_at IS NOT NULL")
+                .bind(Utc::now())
+                .bind(id)
+                .bind(tenant_id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("回收站中没有该分类".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 永久删除在 `before` 之前移入回收站的分类，仍被商品或子分类引用的分类保留在回收站中
+    pub async fn purge_deleted(&mut self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                r#"
+                DELETE FROM categories
+                WHERE deleted_at IS NOT NULL AND deleted_at < $1
+                  AND NOT EXISTS (SELECT 1 FROM products p WHERE p.category_id = categories.id)
+                  AND NOT EXISTS (SELECT 1 FROM categories c WHERE c.parent_id = categories.id)
+                "#
+            )
+            .bind(before)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?)
+    }
+}
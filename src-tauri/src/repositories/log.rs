@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::log::*;
+use crate::models::user::LoginAttempt;
+use crate::with_conn;
+
+pub struct LogRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> LogRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, id: i64) -> Result<Log, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Log>("SELECT * FROM logs WHERE id = $1")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("日志不存在".to_string()))
+    }
+
+    pub async fn list(&mut self, limit: i32, offset: i32) -> Result<Vec<Log>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Log>("SELECT * FROM logs ORDER BY created_at DESC LIMIT $1 OFFSET $2")
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn count(&mut self) -> Result<i64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM logs")
+                .fetch_one(conn)
+                .await
+        })?)
+    }
+
+    pub async fn create(&mut self, request: &CreateLogRequest) -> Result<Log, AppError> {
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO logs (user_id, action, description, ip_address, created_at) VALUES ($1, $2, $3, $4, $5) RETURNING id"
+            )
+            .bind(request.user_id)
+            .bind(&request.action)
+            .bind(&request.description)
+            .bind(&request.ip_address)
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        self.find(id).await
+    }
+
+    pub async fn list_login_attempts(&mut self, limit: i32, offset: i32) -> Result<Vec<LoginAttempt>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, LoginAttempt>("SELECT * FROM login_logs ORDER BY created_at DESC LIMIT $1 OFFSET $2")
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn count_login_attempts(&mut self) -> Result<i64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM login_logs")
+                .fetch_one(conn)
+                .await
+        })?)
+    }
+
+    /// 早于 before 的操作日志，按 id 分批读取（id 大于 after_id），用于归档
+    pub async fn list_before(&mut self, before: DateTime<Utc>, after_id: i64, limit: i64) -> Result<Vec<Log>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Log>("SELECT * FROM logs WHERE created_at < $1 AND id > $2 ORDER BY id LIMIT $3")
+                .bind(before)
+                .bind(after_id)
+                .bind(limit)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    /// 删除早于 before 的操作日志；指定 up_to_id 时只删除已经归档的部分
+    pub async fn delete_before(&mut self, before: DateTime<Utc>, up_to_id: Option<i64>) -> Result<u64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM logs WHERE created_at < $1 AND ($2 IS NULL OR id <= $2)")
+                .bind(before)
+                .bind(up_to_id)
+                .execute(conn)
+                .await
+                .map(|result| result.rows_affected())
+        })?)
+    }
+
+    pub async fn list_login_attempts_before(
+        &mut self,
+        before: DateTime<Utc>,
+        after_id: i64,
+        limit: i64,
+    ) -> Result<Vec<LoginAttempt>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, LoginAttempt>("SELECT * FROM login_logs WHERE created_at < $1 AND id > $2 ORDER BY id LIMIT $3")
+                .bind(before)
+                .bind(after_id)
+                .bind(limit)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn delete_login_attempts_before(&mut self, before: DateTime<Utc>, up_to_id: Option<i64>) -> Result<u64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM login_logs WHERE created_at < $1 AND ($2 IS NULL OR id <= $2)")
+                .bind(before)
+                .bind(up_to_id)
+                .execute(conn)
+                .await
+                .map(|result| result.rows_affected())
+        })?)
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::Utc;
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::menu::*;
+use crate::with_conn;
+
+pub struct MenuRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> MenuRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, id: i64) -> Result<Menu, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Menu>("SELECT * FROM menus WHERE id = $1")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("菜单不存在".to_string()))
+    }
+
+    /// 全部菜单，按排序号排列
+    pub async fn list(&mut self) -> Result<Vec<Menu>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Menu>("SELECT * FROM menus ORDER BY sort_order, id")
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn update(&mut self, id: i64, request: &UpdateMenuRequest) -> Result<Menu, AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "UPDATE menus SET parent_id = $1, title = $2, path = $3, icon = $4, permission = $5, sort_order = $6, visible = $7, updated_at = $8 WHERE id = $9"
+            )
+            .bind(request.parent_id)
+            .bind(&request.title)
+            .bind(&request.path)
+            .bind(&request.icon)
+            .bind(&request.permission)
+            .bind(request.sort_order)
+            .bind(request.visible)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("菜单不存在".to_string()));
+        }
+
+        self.find(id).await
+    }
+
+    /// 只更新层级和排序
+    pub async fn update_position(&mut self, item: &MenuSortItem) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE menus SET parent_id = $1, sort_order = $2, updated_at = $3 WHERE id = $4")
+                .bind(item.parent_id)
+                .bind(item.sort_order)
+                .bind(Utc::now())
+                .bind(item.id)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+//! 数据访问层：每个实体一个仓储，只负责读写数据库，业务规则放在 `services` 中。
+//!
+//! 仓储通过 `DbConn` 执行 SQL，既可以直接使用连接池，也可以加入调用方开启的事务：
+//!
+//! ```ignore
+//! let user = UserRepository::new(&db.pool).find(id).await?;
+//!
+//! // 多条语句放进 `transaction!`，要么全部提交，要么全部回滚
+//! let product = transaction!(db.pool, |tx| ProductRepository::new(&mut tx).update(id, &request).await)?;
+//! ```
+
+pub mod category;
+pub mod log;
+pub mod menu;
+pub mod order;
+pub mod product;
+pub mod role;
+pub mod search;
+pub mod setting;
+pub mod stock;
+pub mod sync;
+pub mod tenant;
+pub mod user;
+pub mod variant;
+
+#[cfg(test)]
+mod postgres_tests;
+#[cfg(test)]
+mod sqlite_tests;
+
+pub use category::CategoryRepository;
+pub use log::LogRepository;
+pub use menu::MenuRepository;
+pub use order::OrderRepository;
+pub use product::ProductRepository;
+pub use role::RoleRepository;
+pub use setting::SettingRepository;
+pub use stock::StockRepository;
+pub use sync::SyncRepository;
+pub use tenant::TenantRepository;
+pub use user::UserRepository;
+pub use variant::VariantRepository;
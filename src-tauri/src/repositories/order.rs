@@ -0,0 +1,159 @@
+use chrono::Utc;
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::money::Money;
+use crate::models::order::*;
+use crate::repositories::search::ORDER_SEARCH;
+use crate::utils::search::SearchQuery;
+use crate::with_conn;
+
+/// 已取消的订单（status 列为文本）不计入营业额，占用的库存退回
+pub const STATUS_CANCELLED: &str = "5";
+
+pub struct OrderRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> OrderRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, tenant_id: i64, id: i64) -> Result<Order, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE id = $1 AND tenant_id = $2")
+                .bind(id)
+                .bind(tenant_id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("订单不存在".to_string()))
+    }
+
+    pub async fn list(&mut self, tenant_id: i64, search: Option<&SearchQuery>, limit: i32, offset: i32) -> Result<Vec<OrderWithUser>, AppError> {
+        let filter = ORDER_SEARCH.filter(self.conn.backend(), search);
+        let sql = format!(
+            r#"
+            SELECT o.id, o.order_no, o.user_id, COALESCE(u.username, '') as username,
+                   o.total_amount, o.currency, o.status, o.created_at, o.updated_at, o.version
+            FROM orders o
+            LEFT JOIN users u ON o.user_id = u.id
+            WHERE o.tenant_id = ${}{}
+            ORDER BY o.created_at DESC
+            LIMIT ${} OFFSET ${}
+            "#,
+            filter.next_param(),
+            filter.sql,
+            filter.next_param() + 1,
+            filter.next_param() + 2
+        );
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            let mut query = sqlx::query_as::<_, OrderWithUser>(&sql);
+            for param in &filter.params {
+                query = query.bind(param);
+            }
+            query.bind(tenant_id).bind(limit).bind(offset).fetch_all(conn).await
+        })?)
+    }
+
+    pub async fn count(&mut self, tenant_id: i64, search: Option<&SearchQuery>) -> Result<i64, AppError> {
+        let filter = ORDER_SEARCH.filter(self.conn.backend(), search);
+        let sql = format!("SELECT COUNT(*) FROM orders o WHERE o.tenant_id = ${}{}", filter.next_param(), filter.sql);
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for param in &filter.params {
+                query = query.bind(param);
+            }
+            query.bind(tenant_id).fetch_one(conn).await
+        })?)
+    }
+
+    pub async fn create(&mut self, tenant_id: i64, order_no: &str, user_id: i64, total_amount: &Money, status: &str) -> Result<Order, AppError> {
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO orders (tenant_id, order_no, user_id, total_amount, currency, status, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id"
+            )
+            .bind(tenant_id)
+            .bind(order_no)
+            .bind(user_id)
+            .bind(total_amount.amount)
+            .bind(&total_amount.currency)
+            .bind(status)
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        self.find(tenant_id, id).await
+    }
+
+    /// 写入一条订单明细，`price` 为下单时的单价，币种与订单相同
+    pub async fn create_item(&mut self, order_id: i64, item: &NewOrderItem) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "INSERT INTO order_items (order_id, product_id, variant_id, sku, product_name, price, quantity, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+            )
+            .bind(order_id)
+            .bind(item.product_id)
+            .bind(item.variant_id)
+            .bind(&item.sku)
+            .bind(&item.product_name)
+            .bind(item.price)
+            .bind(item.quantity)
+            .bind(Utc::now())
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    pub async fn items(&mut self, order_id: i64) -> Result<Vec<OrderItem>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, OrderItem>("SELECT * FROM order_items WHERE order_id = $1 ORDER BY id")
+                .bind(order_id)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    /// 店铺未取消订单的金额合计，每个币种一项；SUM 在两种后端上都是精确的整数运算
+    pub async fn revenue(&mut self, tenant_id: i64) -> Result<Vec<Money>, AppError> {
+        let rows = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, (String, i64)>(
+                "SELECT currency, CAST(SUM(total_amount) AS BIGINT) FROM orders WHERE tenant_id = $1 AND status <> $2 GROUP BY currency ORDER BY currency"
+            )
+            .bind(tenant_id)
+            .bind(STATUS_CANCELLED)
+            .fetch_all(conn)
+            .await
+        })?;
+        Ok(rows.into_iter().map(|(currency, amount)| Money::new(amount, currency)).collect())
+    }
+
+    /// 更新订单状态，`version` 为读取订单时的版本号
+    pub async fn update_status(&mut self, tenant_id: i64, id: i64, status: &str, version: i64) -> Result<Order, AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE orders SET status = $1, updated_at = $2, version = version + 1 WHERE id = $3 AND tenant_id = $4 AND version = $5")
+                .bind(status)
+                .bind(Utc::now())
+                .bind(id)
+                .bind(tenant_id)
+                .bind(version)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            // 记录仍然存在，说明提交的版本已过期
+            let current = self.find(tenant_id, id).await?;
+            return Err(AppError::version_conflict(&current));
+        }
+
+        self.find(tenant_id, id).await
+    }
+}
@@ -0,0 +1,351 @@
+use chrono::{DateTime, Utc};
+
+use crate::database::{Backend, DbConn};
+use crate::error::AppError;
+use crate::models::money::Money;
+use crate::models::product::*;
+use crate::models::recycle_bin::RecycleBinItem;
+use crate::repositories::search::{SearchFilter, PRODUCT_SEARCH};
+use crate::utils::search::SearchQuery;
+use crate::with_conn;
+
+/// 商品的库存：有规格时为未删除规格的库存之和，否则为商品本身的库存
+pub const STOCK_SQL: &str = "COALESCE((SELECT CAST(SUM(v.stock) AS INTEGER) FROM product_variants v WHERE v.product_id = p.id AND v.deleted_at IS NULL), p.stock)";
+
+pub struct ProductRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> ProductRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, tenant_id: i64, id: i64) -> Result<Product, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Product>("SELECT * FROM products WHERE id = $1 AND tenant_id = $2 AND deleted_at IS NULL")
+                .bind(id)
+                .bind(tenant_id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("商品不存在".to_string()))
+    }
+
+    pub async fn list(&mut self, tenant_id: i64, search: Option<&SearchQuery>, query: &ProductQuery, limit: i32, offset: i32) -> Result<Vec<ProductWithCategory>, AppError> {
+        let backend = self.conn.backend();
+        let filter = ProductFilter::new(backend, search, query);
+        let sort_column = match (backend, query.sort_by) {
+            (Backend::Sqlite, ProductSortField::CreatedAt | ProductSortField::UpdatedAt) => format!("datetime({})", query.sort_by.column()),
+            (_, ProductSortField::Stock) => STOCK_SQL.to_string(),
+            _ => query.sort_by.column().to_string(),
+        };
+        let sql = format!(
+            r#"
+            SELECT p.id, p.name, p.price, p.currency, p.description, p.category_id, c.name as category_name,
+                   {STOCK_SQL} as stock, p.images, p.status, p.created_at, p.updated_at, p.version, p.reorder_threshold,
+                   (SELECT COUNT(*) FROM product_variants v WHERE v.product_id = p.id AND v.deleted_at IS NULL) as variant_count
+            FROM products p
+            LEFT JOIN categories c ON p.category_id = c.id
+            WHERE p.tenant_id = ${} AND p.deleted_at IS NULL{}
+            ORDER BY {sort_column} {order}, p.id {order}
+            LIMIT ${} OFFSET ${}
+            "#,
+            filter.next_param(),
+            filter.sql(),
+            filter.next_param() + 1,
+            filter.next_param() + 2,
+            order = query.sort_order.sql()
+        );
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            let mut sql_query = sqlx::query_as::<_, ProductWithCategory>(&sql);
+            for param in &filter.search.params {
+                sql_query = sql_query.bind(param);
+            }
+            for value in &filter.values {
+                sql_query = match value {
+                    FilterValue::Int(value) => sql_query.bind(*value),
+                    FilterValue::Time(value) => sql_query.bind(*value),
+                };
+            }
+            sql_query.bind(tenant_id).bind(limit).bind(offset).fetch_all(conn).await
+        })?)
+    }
+
+    /// 符合筛选条件的商品总数，`query` 为 `None` 时不筛选
+    pub async fn count(&mut self, tenant_id: i64, search: Option<&SearchQuery>, query: Option<&ProductQuery>) -> Result<i64, AppError> {
+        let default_query = ProductQuery::default();
+        let filter = ProductFilter::new(self.conn.backend(), search, query.unwrap_or(&default_query));
+        let sql = format!(
+            "SELECT COUNT(*) FROM products p LEFT JOIN categories c ON p.category_id = c.id WHERE p.tenant_id = ${} AND p.deleted_at IS NULL{}",
+            filter.next_param(),
+            filter.sql()
+        );
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            let mut sql_query = sqlx::query_scalar::<_, i64>(&sql);
+            for param in &filter.search.params {
+                sql_query = sql_query.bind(param);
+            }
+            for value in &filter.values {
+                sql_query = match value {
+                    FilterValue::Int(value) => sql_query.bind(*value),
+                    FilterValue::Time(value) => sql_query.bind(*value),
+                };
+            }
+            sql_query.bind(tenant_id).fetch_one(conn).await
+        })?)
+    }
+
+    /// `price` 为按店铺币种换算后的价格，请求中的十进制价格不直接写入
+    pub async fn create(&mut self, tenant_id: i64, request: &CreateProductRequest, price: &Money) -> Result<Product, AppError> {
+        let images_json = images_json(&request.images);
+
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO products (tenant_id, name, price, currency, description, category_id, images, status, reorder_threshold, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11) RETURNING id"
+            )
+            .bind(tenant_id)
+            .bind(&request.name)
+            .bind(price.amount)
+            .bind(&price.currency)
+            .bind(&request.description)
+            .bind(request.category_id)
+            .bind(&images_json)
+            .bind(1) // 默认状态为启用
+            .bind(request.reorder_threshold)
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        self.find(tenant_id, id).await
+    }
+
+    pub async fn update(&mut self, tenant_id: i64, id: i64, request: &UpdateProductRequest, price: &Money) -> Result<Product, AppError> {
+        let images_json = images_json(&request.images);
+
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "UPDATE products SET name = $1, price = $2, currency = $3, description = $4, category_id = $5, images = $6, status = $7, reorder_threshold = $8, updated_at = $9, version = version + 1 WHERE id = $10 AND tenant_id = $11 AND deleted_at IS NULL AND version = $12"
+            )
+            .bind(&request.name)
+            .bind(price.amount)
+            .bind(&price.currency)
+            .bind(&request.description)
+            .bind(request.category_id)
+            .bind(&images_json)
+            .bind(request.status)
+            .bind(request.reorder_threshold)
+            .bind(Utc::now())
+            .bind(id)
+            .bind(tenant_id)
+            .bind(request.version)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            // 记录仍然存在，说明提交的版本已过期
+            let current = self.find(tenant_id, id).await?;
+            return Err(AppError::version_conflict(&current));
+        }
+
+        self.find(tenant_id, id).await
+    }
+
+    /// 保存规格项定义，`options` 为 JSON 字符串，为空表示商品没有规格
+    pub async fn set_variant_options(&mut self, tenant_id: i64, id: i64, options: Option<&str>) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE products SET variant_options = $1 WHERE id = $2 AND tenant_id = $3")
+                .bind(options)
+                .bind(id)
+                .bind(tenant_id)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 软删除：移入回收站，`deleted_by` 为操作人
+    pub async fn delete(&mut self, tenant_id: i64, id: i64, deleted_by: Option<i64>) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE products SET deleted_at = $1, deleted_by = $2 WHERE id = $3 AND tenant_id = $4 AND deleted_at IS NULL")
+                .bind(Utc::now())
+                .bind(deleted_by)
+                .bind(id)
+                .bind(tenant_id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("商品不存在".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 回收站中的商品，最近删除的在前
+    pub async fn list_deleted(&mut self, tenant_id: i64) -> Result<Vec<RecycleBinItem>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, RecycleBinItem>(
+                r#"
+                SELECT t.id, t.name as name, t.deleted_at, t.deleted_by, d.username as deleted_by_name
+                FROM products t
+                LEFT JOIN users d ON t.deleted_by = d.id
+                WHERE t.tenant_id = $1 AND t.deleted_at IS NOT NULL
+                ORDER BY t.deleted_at DESC
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn restore(&mut self, tenant_id: i64, id: i64) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE products SET deleted_at = NULL, deleted_by = NULL, updated_at = $1 WHERE id = $2 AND tenant_id = $3 AND deleted_at IS NOT NULL")
+                .bind(Utc::now())
+                .bind(id)
+                .bind(tenant_id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("回收站中没有该商品".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 永久删除在 `before` 之前移入回收站的商品，仍被订单明细引用的商品保留在回收站中
+    pub async fn purge_deleted(&mut self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                r#"
+                DELETE FROM products
+                WHERE deleted_at IS NOT NULL AND deleted_at < $1
+                  AND NOT EXISTS (SELECT 1 FROM order_items oi WHERE oi.product_id = products.id)
+                "#
+            )
+            .bind(before)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?)
+    }
+
+    /// 所有店铺中商品的图片数组（JSON 字符串）和规格图片。回收站中的商品还可能恢复，一并计入
+    pub async fn image_references(&mut self) -> Result<Vec<String>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>(
+                r#"
+                SELECT images FROM products WHERE images IS NOT NULL
+                UNION ALL
+                SELECT image FROM product_variants WHERE image IS NOT NULL AND deleted_at IS NULL
+                "#
+            )
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+}
+
+// 辅助函数：图片数组以 JSON 字符串存储
+fn images_json(images: &[String]) -> String {
+    serde_json::to_string(images).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// 筛选条件的参数，按各自的类型绑定
+enum FilterValue {
+    Int(i64),
+    Time(DateTime<Utc>),
+}
+
+/// 商品列表的关键词条件加上筛选条件，筛选参数的编号接在关键词参数之后
+struct ProductFilter {
+    search: SearchFilter,
+    conditions: String,
+    values: Vec<FilterValue>,
+}
+
+impl ProductFilter {
+    fn new(backend: Backend, search: Option<&SearchQuery>, query: &ProductQuery) -> Self {
+        let mut filter = ProductFilter {
+            search: PRODUCT_SEARCH.filter(backend, search),
+            conditions: String::new(),
+            values: Vec::new(),
+        };
+
+        if let Some(category_id) = query.category_id {
+            let param = filter.push(FilterValue::Int(category_id));
+            if query.include_subcategories {
+                // UNION 会去重，即使分类的上下级关系出现环，递归也能结束
+                filter.conditions.push_str(&format!(
+                    " AND p.category_id IN (WITH RECURSIVE tree(id) AS (SELECT ${} UNION SELECT sub.id FROM categories sub JOIN tree ON sub.parent_id = tree.id) SELECT id FROM tree)",
+                    param
+                ));
+            } else {
+                filter.conditions.push_str(&format!(" AND p.category_id = ${}", param));
+            }
+        }
+        if let Some(status) = query.status {
+            filter.compare("p.status", "=", FilterValue::Int(status.into()));
+        }
+
+        let ranges = [
+            ("p.price", query.min_price.map(FilterValue::Int), query.max_price.map(FilterValue::Int)),
+            (STOCK_SQL, query.min_stock.map(|v| FilterValue::Int(v.into())), query.max_stock.map(|v| FilterValue::Int(v.into()))),
+        ];
+        for (column, min, max) in ranges {
+            if let Some(min) = min {
+                filter.compare(column, ">=", min);
+            }
+            if let Some(max) = max {
+                filter.compare(column, "<=", max);
+            }
+        }
+
+        // SQLite 中的时间有 `2024-01-01 08:00:00` 和 RFC 3339 两种写法，统一用 datetime() 转换后再比较
+        let time_ranges = [
+            ("p.created_at", query.created_from, query.created_to),
+            ("p.updated_at", query.updated_from, query.updated_to),
+        ];
+        for (column, from, to) in time_ranges {
+            for (operator, value) in [(">=", from), ("<=", to)] {
+                let Some(value) = value else { continue };
+                let param = filter.push(FilterValue::Time(value));
+                filter.conditions.push_str(&match backend {
+                    Backend::Sqlite => format!(" AND datetime({}) {} datetime(${})", column, operator, param),
+                    Backend::Postgres => format!(" AND {} {} ${}", column, operator, param),
+                });
+            }
+        }
+
+        filter
+    }
+
+    /// 添加一个参数，返回它的编号
+    fn push(&mut self, value: FilterValue) -> usize {
+        self.values.push(value);
+        self.search.params.len() + self.values.len()
+    }
+
+    fn compare(&mut self, column: &str, operator: &str, value: FilterValue) {
+        let param = self.push(value);
+        self.conditions.push_str(&format!(" AND {} {} ${}", column, operator, param));
+    }
+
+    fn sql(&self) -> String {
+        format!("{}{}", self.search.sql, self.conditions)
+    }
+
+    /// 条件之后的下一个参数编号
+    fn next_param(&self) -> usize {
+        self.search.params.len() + self.values.len() + 1
+    }
+}
@@ -0,0 +1,61 @@
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::role::*;
+use crate::with_conn;
+
+pub struct RoleRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> RoleRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn list(&mut self) -> Result<Vec<Role>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Role>("SELECT * FROM roles ORDER BY id")
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn find(&mut self, id: i64) -> Result<Role, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Role>("SELECT * FROM roles WHERE id = $1")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("角色不存在".to_string()))
+    }
+
+    /// 角色的权限 JSON，角色不存在时返回 None
+    pub async fn permissions(&mut self, id: i64) -> Result<Option<String>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>("SELECT permissions FROM roles WHERE id = $1")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+
+    pub async fn update(&mut self, id: i64, request: &UpdateRoleRequest) -> Result<Role, AppError> {
+        let permissions_json = serde_json::to_string(&request.permissions)?;
+
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE roles SET name = $1, permissions = $2 WHERE id = $3")
+                .bind(&request.name)
+                .bind(&permissions_json)
+                .bind(id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("角色不存在".to_string()));
+        }
+
+        self.find(id).await
+    }
+}
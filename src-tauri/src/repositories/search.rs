@@ -0,0 +1,107 @@
+use crate::database::Backend;
+use crate::utils::search::SearchQuery;
+
+// trigram 分词至少需要三个字符，更短的关键词（例如两个汉字）改用 LIKE 匹配索引表
+const MIN_MATCH_CHARS: usize = 3;
+
+/// 一个可搜索的列表：SQLite 下使用的 FTS5 索引表，PostgreSQL 下直接匹配的原表列
+pub struct SearchTarget {
+    /// 原表主键列，与索引表的 rowid 对应
+    pub id_column: &'static str,
+    pub fts_table: &'static str,
+    pub fts_columns: &'static [&'static str],
+    /// 查询中原表列的写法（带表别名），列顺序与 `fts_columns` 一致
+    pub columns: &'static [&'static str],
+}
+
+pub const PRODUCT_SEARCH: SearchTarget = SearchTarget {
+    id_column: "p.id",
+    fts_table: "products_fts",
+    fts_columns: &["name", "description", "category_name"],
+    columns: &["p.name", "p.description", "c.name"],
+};
+
+pub const USER_SEARCH: SearchTarget = SearchTarget {
+    id_column: "u.id",
+    fts_table: "users_fts",
+    fts_columns: &["username", "email"],
+    columns: &["u.username", "u.email"],
+};
+
+pub const ORDER_SEARCH: SearchTarget = SearchTarget {
+    id_column: "o.id",
+    fts_table: "orders_fts",
+    fts_columns: &["order_no"],
+    columns: &["o.order_no"],
+};
+
+/// 拼接到 WHERE 之后的搜索条件及其参数，参数占位符从 `$1` 开始编号
+#[derive(Debug, Default)]
+pub struct SearchFilter {
+    /// 以 ` AND ` 开头，没有搜索条件时为空
+    pub sql: String,
+    pub params: Vec<String>,
+}
+
+impl SearchFilter {
+    /// 条件之后的下一个参数编号，供 LIMIT / OFFSET 使用
+    pub fn next_param(&self) -> usize {
+        self.params.len() + 1
+    }
+}
+
+impl SearchTarget {
+    pub fn filter(&self, backend: Backend, search: Option<&SearchQuery>) -> SearchFilter {
+        let mut filter = SearchFilter::default();
+
+        for term in search.map(SearchQuery::terms).unwrap_or_default() {
+            let condition = match backend {
+                Backend::Sqlite if term.chars().count() >= MIN_MATCH_CHARS => {
+                    filter.params.push(match_phrase(term));
+                    format!(
+                        "{} IN (SELECT rowid FROM {table} WHERE {table} MATCH ${})",
+                        self.id_column,
+                        filter.params.len(),
+                        table = self.fts_table
+                    )
+                }
+                Backend::Sqlite => {
+                    filter.params.push(like_pattern(term));
+                    format!(
+                        "{} IN (SELECT rowid FROM {} WHERE {})",
+                        self.id_column,
+                        self.fts_table,
+                        like_any(self.fts_columns, "LIKE", filter.params.len())
+                    )
+                }
+                Backend::Postgres => {
+                    filter.params.push(like_pattern(term));
+                    format!("({})", like_any(self.columns, "ILIKE", filter.params.len()))
+                }
+            };
+
+            filter.sql.push_str(" AND ");
+            filter.sql.push_str(&condition);
+        }
+
+        filter
+    }
+}
+
+// 关键词作为短语查询，trigram 索引按子串匹配，前缀自然也能命中
+fn match_phrase(term: &str) -> String {
+    format!("\"{}\"", term.replace('"', "\"\""))
+}
+
+fn like_pattern(term: &str) -> String {
+    let escaped = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+fn like_any(columns: &[&str], operator: &str, param: usize) -> String {
+    columns
+        .iter()
+        .map(|column| format!("{} {} ${} ESCAPE '\\'", column, operator, param))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+use chrono::Utc;
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::money::{MoneySettings, RoundingMode};
+use crate::models::settings::SystemSetting;
+use crate::with_conn;
+
+/// 全局设置的 tenant_id，店铺没有单独设置时使用全局值
+const GLOBAL: i64 = 0;
+
+pub struct SettingRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> SettingRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn get(&mut self, key: &str) -> Result<Option<String>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>("SELECT setting_value FROM system_settings WHERE tenant_id = $1 AND setting_key = $2")
+                .bind(GLOBAL)
+                .bind(key)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+
+    pub async fn set(&mut self, key: &str, value: &str) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE system_settings SET setting_value = $1, updated_at = $2 WHERE tenant_id = $3 AND setting_key = $4")
+                .bind(value)
+                .bind(Utc::now())
+                .bind(GLOBAL)
+                .bind(key)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 店铺生效的设置：店铺单独设置的值覆盖全局值
+    pub async fn list_for_tenant(&mut self, tenant_id: i64) -> Result<Vec<SystemSetting>, AppError> {
+        let rows = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, SystemSetting>(
+                "SELECT * FROM system_settings WHERE tenant_id IN ($1, $2) ORDER BY setting_key, tenant_id"
+            )
+            .bind(GLOBAL)
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?;
+
+        // 同一个键按 tenant_id 升序排列，排在后面的店铺值覆盖全局值
+        let mut settings = BTreeMap::new();
+        for setting in rows {
+            settings.insert(setting.setting_key.clone(), setting);
+        }
+        Ok(settings.into_values().collect())
+    }
+
+    /// 只包含店铺单独设置的值
+    pub async fn list_overrides(&mut self, tenant_id: i64) -> Result<Vec<SystemSetting>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, SystemSetting>("SELECT * FROM system_settings WHERE tenant_id = $1 ORDER BY setting_key")
+                .bind(tenant_id)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn get_for_tenant(&mut self, tenant_id: i64, key: &str) -> Result<Option<String>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>(
+                "SELECT setting_value FROM system_settings WHERE tenant_id IN ($1, $2) AND setting_key = $3 ORDER BY tenant_id DESC LIMIT 1"
+            )
+            .bind(GLOBAL)
+            .bind(tenant_id)
+            .bind(key)
+            .fetch_optional(conn)
+            .await
+        })?)
+    }
+
+    /// 店铺的币种和金额舍入方式，没有设置或设置值无效时使用默认值
+    pub async fn money_settings(&mut self, tenant_id: i64) -> Result<MoneySettings, AppError> {
+        let mut settings = MoneySettings::default();
+        if let Some(currency) = self.get_for_tenant(tenant_id, "currency").await? {
+            settings.currency = currency;
+        }
+        if let Some(rounding) = self.get_for_tenant(tenant_id, "money_rounding").await? {
+            settings.rounding = rounding.parse().unwrap_or(RoundingMode::default());
+        }
+        Ok(settings)
+    }
+
+    /// 写入店铺单独的设置值，类型和说明沿用同名的全局设置；没有对应全局设置的键会被忽略
+    pub async fn set_for_tenant(&mut self, tenant_id: i64, key: &str, value: &str) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                r#"
+                INSERT INTO system_settings (tenant_id, setting_key, setting_value, setting_type, description, created_at, updated_at)
+                SELECT $1, setting_key, $2, setting_type, description, $3, $3
+                FROM system_settings
+                WHERE tenant_id = $4 AND setting_key = $5
+                ON CONFLICT (tenant_id, setting_key) DO UPDATE SET setting_value = excluded.setting_value, updated_at = excluded.updated_at
+                "#
+            )
+            .bind(tenant_id)
+            .bind(value)
+            .bind(Utc::now())
+            .bind(GLOBAL)
+            .bind(key)
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+}
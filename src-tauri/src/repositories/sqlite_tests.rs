@@ -0,0 +1,78 @@
+//! SQLite 后端的并发测试：数据库建在临时目录中，不需要外部环境
+
+use std::time::Duration;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+
+use super::CategoryRepository;
+use crate::database::{migrations, DbPool};
+use crate::error::AppError;
+use crate::models::category::{CreateCategoryRequest, UpdateCategoryRequest};
+use crate::transaction;
+
+const DEFAULT_TENANT: i64 = 1;
+
+async fn open_pool(dir: &std::path::Path) -> DbPool {
+    let options = SqliteConnectOptions::new()
+        .filename(dir.join("test.db"))
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .busy_timeout(Duration::from_secs(5))
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new().max_connections(4).connect_with(options).await.unwrap();
+    let pool = DbPool::Sqlite(pool);
+    migrations::run_migrations(&pool).await.unwrap();
+    pool
+}
+
+// 先读出当前版本，等一会儿再写入：模拟订单状态更新这类先读后写的工作单元
+async fn rename(pool: DbPool, id: i64, name: String) -> Result<(), AppError> {
+    transaction!(pool, |tx| {
+        let mut categories = CategoryRepository::new(&mut tx);
+        let current = categories.find(DEFAULT_TENANT, id).await?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        categories
+            .update(DEFAULT_TENANT, id, &UpdateCategoryRequest {
+                name: name.clone(),
+                parent_id: current.parent_id,
+                sort_order: current.sort_order,
+                reorder_threshold: current.reorder_threshold,
+                version: current.version,
+            })
+            .await?;
+        Ok(())
+    })
+}
+
+#[tokio::test]
+async fn concurrent_read_then_write_units_do_not_fail() {
+    let dir = std::env::temp_dir().join(format!("rust-admin-test-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pool = open_pool(&dir).await;
+
+    let category = CategoryRepository::new(&pool)
+        .create(DEFAULT_TENANT, &CreateCategoryRequest {
+            name: "并发".to_string(),
+            parent_id: None,
+            sort_order: 0,
+            reorder_threshold: None,
+        })
+        .await
+        .unwrap();
+
+    // 延迟的 BEGIN 下两个事务会读到同一个快照，后写入的一方得到 SQLITE_BUSY_SNAPSHOT
+    let tasks: Vec<_> = (0..4)
+        .map(|i| tokio::spawn(rename(pool.clone(), category.id, format!("并发 {}", i))))
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    let latest = CategoryRepository::new(&pool).find(DEFAULT_TENANT, category.id).await.unwrap();
+    assert_eq!(latest.version, category.version + 4);
+
+    if let DbPool::Sqlite(pool) = pool {
+        pool.close().await;
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,277 @@
+use chrono::Utc;
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::stock::*;
+use crate::repositories::product::STOCK_SQL;
+use crate::with_conn;
+
+const MOVEMENT_COLUMNS: &str = r#"
+    m.id, m.tenant_id, m.product_id, m.variant_id, m.movement_type, m.quantity, m.stock_after,
+    m.reference, m.order_id, m.actor_id, u.username as actor_name, m.note, m.created_at
+"#;
+
+/// 生效的库存预警值：商品自己的优先，其次是所属分类的
+const THRESHOLD_SQL: &str = "COALESCE(p.reorder_threshold, c.reorder_threshold)";
+
+/// 需要预警的商品：上架、未删除且库存不高于预警值，没有预警值时比较结果为 NULL；`p` 为商品，`c` 为分类
+fn low_stock_condition() -> String {
+    format!("p.deleted_at IS NULL AND p.status = 1 AND {STOCK_SQL} <= {THRESHOLD_SQL}")
+}
+
+/// 库存流水。库存只通过 `record` 修改，同时写入一条流水，两者在同一个事务中
+pub struct StockRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> StockRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    /// 修改商品或规格的库存并记录流水；出库后库存不能小于 0
+    pub async fn record(&mut self, tenant_id: i64, movement: &NewStockMovement) -> Result<StockMovement, AppError> {
+        // 在同一条 UPDATE 中检查库存，并发出库时也不会扣成负数
+        let (sql, row_id) = match movement.variant_id {
+            Some(variant_id) => (
+                "UPDATE product_variants SET stock = stock + $1 WHERE id = $2 AND tenant_id = $3 AND product_id = $4 AND stock + $1 >= 0 RETURNING stock",
+                variant_id,
+            ),
+            None => (
+                "UPDATE products SET stock = stock + $1 WHERE id = $2 AND tenant_id = $3 AND id = $4 AND stock + $1 >= 0 RETURNING stock",
+                movement.product_id,
+            ),
+        };
+        let stock_after = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i32>(sql)
+                .bind(movement.quantity)
+                .bind(row_id)
+                .bind(tenant_id)
+                .bind(movement.product_id)
+                .fetch_optional(conn)
+                .await
+        })?;
+        let Some(stock_after) = stock_after else {
+            return Err(match self.current_stock(tenant_id, movement.product_id, movement.variant_id).await? {
+                Some(stock) => AppError::Invalid(format!("库存不足，当前库存 {}", stock)),
+                None => AppError::NotFound("商品或规格不存在".to_string()),
+            });
+        };
+
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO stock_movements (tenant_id, product_id, variant_id, movement_type, quantity, stock_after, reference, order_id, actor_id, note, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                RETURNING id
+                "#
+            )
+            .bind(tenant_id)
+            .bind(movement.product_id)
+            .bind(movement.variant_id)
+            .bind(movement.movement_type.as_str())
+            .bind(movement.quantity)
+            .bind(stock_after)
+            .bind(&movement.reference)
+            .bind(movement.order_id)
+            .bind(movement.actor_id)
+            .bind(&movement.note)
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        let sql = format!("SELECT {} FROM stock_movements m LEFT JOIN users u ON m.actor_id = u.id WHERE m.id = $1", MOVEMENT_COLUMNS);
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, StockMovement>(&sql)
+                .bind(id)
+                .fetch_one(conn)
+                .await
+        })?)
+    }
+
+    async fn current_stock(&mut self, tenant_id: i64, product_id: i64, variant_id: Option<i64>) -> Result<Option<i32>, AppError> {
+        let (sql, row_id) = match variant_id {
+            Some(variant_id) => ("SELECT stock FROM product_variants WHERE id = $1 AND tenant_id = $2 AND product_id = $3", variant_id),
+            None => ("SELECT stock FROM products WHERE id = $1 AND tenant_id = $2 AND id = $3", product_id),
+        };
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i32>(sql)
+                .bind(row_id)
+                .bind(tenant_id)
+                .bind(product_id)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+
+    /// 商品的流水，最新的在前；`variant_id` 为空时包括商品本身和所有规格的流水
+    pub async fn list(&mut self, tenant_id: i64, product_id: i64, variant_id: Option<i64>, limit: i32, offset: i32) -> Result<Vec<StockMovement>, AppError> {
+        let sql = format!(
+            r#"
+            SELECT {}
+            FROM stock_movements m
+            LEFT JOIN users u ON m.actor_id = u.id
+            WHERE m.tenant_id = $1 AND m.product_id = $2 AND ($3 IS NULL OR m.variant_id = $3)
+            ORDER BY m.id DESC
+            LIMIT $4 OFFSET $5
+            "#,
+            MOVEMENT_COLUMNS
+        );
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, StockMovement>(&sql)
+                .bind(tenant_id)
+                .bind(product_id)
+                .bind(variant_id)
+                .bind(limit)
+                .bind(offset)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn count(&mut self, tenant_id: i64, product_id: i64, variant_id: Option<i64>) -> Result<i64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "SELECT COUNT(*) FROM stock_movements m WHERE m.tenant_id = $1 AND m.product_id = $2 AND ($3 IS NULL OR m.variant_id = $3)"
+            )
+            .bind(tenant_id)
+            .bind(product_id)
+            .bind(variant_id)
+            .fetch_one(conn)
+            .await
+        })?)
+    }
+
+    /// 一个订单在各商品、规格上的净出入库数量，出库为负
+    pub async fn order_totals(&mut self, tenant_id: i64, order_id: i64) -> Result<Vec<(i64, Option<i64>, i64)>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, (i64, Option<i64>, i64)>(
+                r#"
+                SELECT product_id, variant_id, CAST(SUM(quantity) AS BIGINT)
+                FROM stock_movements
+                WHERE tenant_id = $1 AND order_id = $2
+                GROUP BY product_id, variant_id
+                ORDER BY product_id, variant_id
+                "#
+            )
+            .bind(tenant_id)
+            .bind(order_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    /// 当前库存与流水合计不相等的商品和规格，包括回收站中的商品和已删除的规格
+    pub async fn discrepancies(&mut self, tenant_id: i64) -> Result<Vec<StockDiscrepancy>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, StockDiscrepancy>(
+                r#"
+                SELECT p.id as product_id, NULL as variant_id, p.name as product_name, NULL as sku,
+                       CAST(p.stock AS BIGINT) as stock, CAST(COALESCE(SUM(m.quantity), 0) AS BIGINT) as ledger_total
+                FROM products p
+                LEFT JOIN stock_movements m ON m.product_id = p.id AND m.variant_id IS NULL
+                WHERE p.tenant_id = $1
+                GROUP BY p.id, p.name, p.stock
+                HAVING p.stock <> COALESCE(SUM(m.quantity), 0)
+                UNION ALL
+                SELECT v.product_id, v.id, p.name, v.sku,
+                       CAST(v.stock AS BIGINT), CAST(COALESCE(SUM(m.quantity), 0) AS BIGINT)
+                FROM product_variants v
+                JOIN products p ON v.product_id = p.id
+                LEFT JOIN stock_movements m ON m.variant_id = v.id
+                WHERE v.tenant_id = $1
+                GROUP BY v.id, v.product_id, p.name, v.sku, v.stock
+                HAVING v.stock <> COALESCE(SUM(m.quantity), 0)
+                ORDER BY product_id, variant_id
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    /// 上架商品中库存不高于预警值的，缺口最大的在前；`tenant_id` 为空时查询所有店铺
+    pub async fn low_stock(&mut self, tenant_id: Option<i64>, limit: i32) -> Result<Vec<LowStockProduct>, AppError> {
+        let sql = format!(
+            r#"
+            SELECT p.id as product_id, p.tenant_id, p.name as product_name, c.name as category_name,
+                   CAST({STOCK_SQL} AS BIGINT) as stock, CAST({THRESHOLD_SQL} AS BIGINT) as threshold
+            FROM products p
+            LEFT JOIN categories c ON p.category_id = c.id AND c.deleted_at IS NULL
+            WHERE ($1 IS NULL OR p.tenant_id = $1) AND {condition}
+            ORDER BY {STOCK_SQL} - {THRESHOLD_SQL}, p.id
+            LIMIT $2
+            "#,
+            condition = low_stock_condition()
+        );
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, LowStockProduct>(&sql)
+                .bind(tenant_id)
+                .bind(limit)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    /// 删除库存已经恢复（或不再需要预警）的商品的预警记录，下次跌破预警值时会再次提醒
+    pub async fn clear_recovered_alerts(&mut self) -> Result<u64, AppError> {
+        let sql = format!(
+            r#"
+            DELETE FROM low_stock_alerts
+            WHERE NOT EXISTS (
+                SELECT 1
+                FROM products p
+                LEFT JOIN categories c ON p.category_id = c.id AND c.deleted_at IS NULL
+                WHERE p.id = low_stock_alerts.product_id AND {condition}
+            )
+            "#,
+            condition = low_stock_condition()
+        );
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query(&sql)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?)
+    }
+
+    /// 所有店铺中新跌破预警值、还没有提醒过的商品
+    pub async fn unalerted_low_stock(&mut self) -> Result<Vec<LowStockProduct>, AppError> {
+        let sql = format!(
+            r#"
+            SELECT p.id as product_id, p.tenant_id, p.name as product_name, c.name as category_name,
+                   CAST({STOCK_SQL} AS BIGINT) as stock, CAST({THRESHOLD_SQL} AS BIGINT) as threshold
+            FROM products p
+            LEFT JOIN categories c ON p.category_id = c.id AND c.deleted_at IS NULL
+            WHERE {condition}
+              AND NOT EXISTS (SELECT 1 FROM low_stock_alerts a WHERE a.product_id = p.id)
+            ORDER BY p.tenant_id, p.id
+            "#,
+            condition = low_stock_condition()
+        );
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, LowStockProduct>(&sql)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn mark_alerted(&mut self, product: &LowStockProduct) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "INSERT INTO low_stock_alerts (product_id, tenant_id, stock, threshold, alerted_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (product_id) DO NOTHING"
+            )
+            .bind(product.product_id)
+            .bind(product.tenant_id)
+            .bind(product.stock)
+            .bind(product.threshold)
+            .bind(Utc::now())
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,605 @@
+use chrono::{DateTime, Utc};
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::sync::*;
+use crate::with_conn;
+
+/// 远端订单的下单用户在本地不存在时，记到默认管理员名下
+const FALLBACK_USER_ID: i64 = 1;
+
+/// 商品本身的库存与流水合计的差额记为一笔调整，$1 为商品 ID
+const SYNC_PRODUCT_STOCK_SQL: &str = r#"
+    INSERT INTO stock_movements (tenant_id, product_id, movement_type, quantity, stock_after, note, created_at)
+    SELECT tenant_id, id, 'adjustment', stock - ledger, stock, '同步其他设备的库存', $2
+    FROM (
+        SELECT p.tenant_id, p.id, p.stock,
+               COALESCE((SELECT SUM(m.quantity) FROM stock_movements m WHERE m.product_id = p.id AND m.variant_id IS NULL), 0) AS ledger
+        FROM products p
+        WHERE p.id = $1
+    )
+    WHERE stock <> ledger
+"#;
+
+/// 商品各规格（包括已删除的）的库存与流水合计的差额
+const SYNC_VARIANT_STOCK_SQL: &str = r#"
+    INSERT INTO stock_movements (tenant_id, product_id, variant_id, movement_type, quantity, stock_after, note, created_at)
+    SELECT tenant_id, product_id, id, 'adjustment', stock - ledger, stock, '同步其他设备的库存', $2
+    FROM (
+        SELECT v.tenant_id, v.product_id, v.id, v.stock,
+               COALESCE((SELECT SUM(m.quantity) FROM stock_movements m WHERE m.variant_id = v.id), 0) AS ledger
+        FROM product_variants v
+        WHERE v.product_id = $1
+    )
+    WHERE stock <> ledger
+"#;
+
+/// 同步用到的表只在 SQLite 上存在
+pub struct SyncRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> SyncRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    /// 给本地新增、还没有 sync_id 的行分配 sync_id，并补到待推送的修改上；
+    /// 推送前已经被物理删除的行从未同步过，它的修改直接丢弃
+    pub async fn assign_sync_ids(&mut self) -> Result<(), AppError> {
+        for entity in SyncEntity::ALL {
+            let table = entity.table();
+            let assign = format!("UPDATE {} SET sync_id = lower(hex(randomblob(16))) WHERE sync_id IS NULL", table);
+            let fill = format!(
+                "UPDATE sync_outbox SET sync_id = (SELECT sync_id FROM {} WHERE id = sync_outbox.row_id) WHERE entity = $1 AND sync_id IS NULL",
+                table
+            );
+            with_conn!(&mut self.conn, |conn| sqlx::query(&assign).execute(conn).await.map(|_| ()))?;
+            with_conn!(&mut self.conn, |conn| {
+                sqlx::query(&fill).bind(table).execute(conn).await.map(|_| ())
+            })?;
+        }
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM sync_outbox WHERE sync_id IS NULL").execute(conn).await.map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 有未推送修改的行，每行只取最近的一次修改；按每行第一次修改的顺序排列，
+    /// 被引用的行（例如订单中的商品）总是排在引用它的行之前。
+    /// 分配 sync_id 之后才产生的新增留到下一次推送
+    pub async fn pending(&mut self, limit: i64) -> Result<Vec<OutboxEntry>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, OutboxEntry>(
+                r#"
+                SELECT MAX(seq) as seq, entity, sync_id, MAX(changed_at) as changed_at
+                FROM sync_outbox
+                WHERE sync_id IS NOT NULL
+                GROUP BY entity, sync_id
+                ORDER BY MIN(seq)
+                LIMIT $1
+                "#
+            )
+            .bind(limit)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    /// 推送成功后删除已推送的修改，推送期间新产生的修改保留到下一次
+    pub async fn remove_pushed(&mut self, entry: &OutboxEntry) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM sync_outbox WHERE entity = $1 AND sync_id = $2 AND seq <= $3")
+                .bind(&entry.entity)
+                .bind(&entry.sync_id)
+                .bind(entry.seq)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    pub async fn has_pending(&mut self, entity: SyncEntity, sync_id: &str) -> Result<bool, AppError> {
+        let count = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM sync_outbox WHERE entity = $1 AND sync_id = $2")
+                .bind(entity.table())
+                .bind(sync_id)
+                .fetch_one(conn)
+                .await
+        })?;
+        Ok(count > 0)
+    }
+
+    /// 各类数据有未推送修改的行数
+    pub async fn pending_counts(&mut self) -> Result<Vec<(String, i64)>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, (String, i64)>("SELECT entity, COUNT(DISTINCT row_id) FROM sync_outbox GROUP BY entity")
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    /// 各类数据已经同步过的行数
+    pub async fn synced_counts(&mut self) -> Result<Vec<(String, i64)>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, (String, i64)>("SELECT entity, COUNT(*) FROM sync_rows GROUP BY entity")
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn row_hlc(&mut self, entity: SyncEntity, sync_id: &str) -> Result<Option<String>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>("SELECT hlc FROM sync_rows WHERE entity = $1 AND sync_id = $2")
+                .bind(entity.table())
+                .bind(sync_id)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+
+    pub async fn set_row_hlc(&mut self, entity: SyncEntity, sync_id: &str, hlc: &str) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "INSERT INTO sync_rows (entity, sync_id, hlc) VALUES ($1, $2, $3) ON CONFLICT (entity, sync_id) DO UPDATE SET hlc = excluded.hlc"
+            )
+            .bind(entity.table())
+            .bind(sync_id)
+            .bind(hlc)
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 之后同一个事务中的写入不再记入发件箱，必须与 `end_apply` 成对在事务中调用
+    pub async fn begin_apply(&mut self) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("INSERT INTO sync_applying (active) VALUES (1)")
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    pub async fn end_apply(&mut self) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM sync_applying")
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    pub async fn record_conflict(&mut self, change: &SyncChange, local_hlc: Option<&str>, reason: &str) -> Result<(), AppError> {
+        let payload = change.data.as_ref().map(|data| data.to_string());
+
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "INSERT INTO sync_conflicts (entity, sync_id, local_hlc, remote_hlc, remote_node, reason, payload, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+            )
+            .bind(change.entity.table())
+            .bind(&change.sync_id)
+            .bind(local_hlc)
+            .bind(&change.hlc)
+            .bind(&change.node_id)
+            .bind(reason)
+            .bind(&payload)
+            .bind(Utc::now())
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 各类数据的冲突数和最近一次冲突的时间
+    pub async fn conflict_stats(&mut self) -> Result<Vec<(String, i64, Option<DateTime<Utc>>)>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, (String, i64, Option<DateTime<Utc>>)>(
+                "SELECT entity, COUNT(*), MAX(created_at) FROM sync_conflicts GROUP BY entity"
+            )
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    /// 最近的冲突在前
+    pub async fn list_conflicts(&mut self, entity: Option<SyncEntity>, limit: i64) -> Result<Vec<SyncConflict>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, SyncConflict>(
+                "SELECT * FROM sync_conflicts WHERE ($1 IS NULL OR entity = $1) ORDER BY id DESC LIMIT $2"
+            )
+            .bind(entity.map(SyncEntity::table))
+            .bind(limit)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn category_data(&mut self, sync_id: &str) -> Result<Option<CategorySyncData>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, CategorySyncData>(
+                r#"
+                SELECT t.code as tenant_code, t.name as tenant_name, c.name, p.sync_id as parent_sync_id,
+                       COALESCE(c.sort_order, 0) as sort_order, c.status, c.created_at, c.updated_at,
+                       c.deleted_at, d.username as deleted_by_name, c.reorder_threshold
+                FROM categories c
+                JOIN tenants t ON c.tenant_id = t.id
+                LEFT JOIN categories p ON c.parent_id = p.id
+                LEFT JOIN users d ON c.deleted_by = d.id
+                WHERE c.sync_id = $1
+                "#
+            )
+            .bind(sync_id)
+            .fetch_optional(conn)
+            .await
+        })?)
+    }
+
+    pub async fn product_data(&mut self, sync_id: &str) -> Result<Option<ProductSyncData>, AppError> {
+        let product = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, ProductSyncData>(
+                r#"
+                SELECT t.code as tenant_code, t.name as tenant_name, p.name, p.price, p.currency, p.description,
+                       c.sync_id as category_sync_id, p.stock, p.images, p.status, p.created_at, p.updated_at,
+                       p.deleted_at, d.username as deleted_by_name, p.variant_options, p.reorder_threshold
+                FROM products p
+                JOIN tenants t ON p.tenant_id = t.id
+                LEFT JOIN categories c ON p.category_id = c.id
+                LEFT JOIN users d ON p.deleted_by = d.id
+                WHERE p.sync_id = $1
+                "#
+            )
+            .bind(sync_id)
+            .fetch_optional(conn)
+            .await
+        })?;
+        let Some(mut product) = product else {
+            return Ok(None);
+        };
+
+        product.variants = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, VariantSyncData>(
+                r#"
+                SELECT v.sku, v.barcode, v.options, v.price, v.stock, v.image, v.sort_order, v.created_at, v.updated_at
+                FROM product_variants v
+                JOIN products p ON v.product_id = p.id
+                WHERE p.sync_id = $1 AND v.deleted_at IS NULL
+                ORDER BY v.sort_order, v.id
+                "#
+            )
+            .bind(sync_id)
+            .fetch_all(conn)
+            .await
+        })?;
+        Ok(Some(product))
+    }
+
+    pub async fn order_data(&mut self, sync_id: &str) -> Result<Option<OrderSyncData>, AppError> {
+        let order = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, OrderSyncData>(
+                r#"
+                SELECT t.code as tenant_code, t.name as tenant_name, o.order_no, u.username,
+                       o.total_amount, o.currency, o.status, o.created_at, o.updated_at
+                FROM orders o
+                JOIN tenants t ON o.tenant_id = t.id
+                LEFT JOIN users u ON o.user_id = u.id
+                WHERE o.sync_id = $1
+                "#
+            )
+            .bind(sync_id)
+            .fetch_optional(conn)
+            .await
+        })?;
+        let Some(mut order) = order else {
+            return Ok(None);
+        };
+
+        order.items = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, OrderItemSyncData>(
+                r#"
+                SELECT p.sync_id as product_sync_id, oi.sku, oi.product_name, oi.price, oi.quantity, oi.created_at
+                FROM order_items oi
+                JOIN orders o ON oi.order_id = o.id
+                LEFT JOIN products p ON oi.product_id = p.id
+                WHERE o.sync_id = $1
+                ORDER BY oi.id
+                "#
+            )
+            .bind(sync_id)
+            .fetch_all(conn)
+            .await
+        })?;
+        Ok(Some(order))
+    }
+
+    /// 按编码找到本地的店铺，没有时新建
+    pub async fn ensure_tenant(&mut self, code: &str, name: &str) -> Result<i64, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("INSERT INTO tenants (name, code) VALUES ($1, $2) ON CONFLICT (code) DO NOTHING")
+                .bind(name)
+                .bind(code)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT id FROM tenants WHERE code = $1")
+                .bind(code)
+                .fetch_one(conn)
+                .await
+        })?)
+    }
+
+    /// 写入远端的分类，上级分类还没有同步过来时暂时作为顶级分类
+    pub async fn apply_category(&mut self, sync_id: &str, tenant_id: i64, data: &CategorySyncData) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                r#"
+                INSERT INTO categories (sync_id, tenant_id, name, parent_id, sort_order, status, created_at, updated_at, deleted_at, deleted_by, reorder_threshold)
+                VALUES ($1, $2, $3, (SELECT id FROM categories WHERE sync_id = $4), $5, $6, $7, $8, $9, (SELECT id FROM users WHERE username = $10), $11)
+                ON CONFLICT (sync_id) DO UPDATE SET
+                    tenant_id = excluded.tenant_id, name = excluded.name, parent_id = excluded.parent_id,
+                    sort_order = excluded.sort_order, status = excluded.status, created_at = excluded.created_at,
+                    updated_at = excluded.updated_at, deleted_at = excluded.deleted_at, deleted_by = excluded.deleted_by,
+                    reorder_threshold = excluded.reorder_threshold, version = categories.version + 1
+                "#
+            )
+            .bind(sync_id)
+            .bind(tenant_id)
+            .bind(&data.name)
+            .bind(&data.parent_sync_id)
+            .bind(data.sort_order)
+            .bind(data.status)
+            .bind(data.created_at)
+            .bind(data.updated_at)
+            .bind(data.deleted_at)
+            .bind(&data.deleted_by_name)
+            .bind(data.reorder_threshold)
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 写入远端的商品并整体替换规格：按 SKU 对应已有的规格，远端没有的规格软删除
+    pub async fn apply_product(&mut self, sync_id: &str, tenant_id: i64, data: &ProductSyncData) -> Result<(), AppError> {
+        // SKU 和条码在店铺内唯一，先检查是否被本地的其他商品占用，避免商品写入了而规格没有
+        for variant in &data.variants {
+            let taken = with_conn!(&mut self.conn, |conn| {
+                sqlx::query_scalar::<_, i64>(
+                    r#"
+                    SELECT COUNT(*) FROM product_variants v JOIN products p ON v.product_id = p.id
+                    WHERE v.tenant_id = $1 AND (v.sku = $2 OR v.barcode = $3) AND (p.sync_id IS NULL OR p.sync_id <> $4) AND v.deleted_at IS NULL
+                    "#
+                )
+                .bind(tenant_id)
+                .bind(&variant.sku)
+                .bind(&variant.barcode)
+                .bind(sync_id)
+                .fetch_one(conn)
+                .await
+            })?;
+            if taken > 0 {
+                return Err(AppError::Conflict(format!("SKU {} 或其条码已被本地的其他商品使用", variant.sku)));
+            }
+        }
+
+        let product_id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO products (sync_id, tenant_id, name, price, currency, description, category_id, stock, images, status, created_at, updated_at, deleted_at, deleted_by, variant_options, reorder_threshold)
+                VALUES ($1, $2, $3, $4, $5, $6, (SELECT id FROM categories WHERE sync_id = $7), $8, $9, $10, $11, $12, $13, (SELECT id FROM users WHERE username = $14), $15, $16)
+                ON CONFLICT (sync_id) DO UPDATE SET
+                    tenant_id = excluded.tenant_id, name = excluded.name, price = excluded.price, currency = excluded.currency,
+                    description = excluded.description, category_id = excluded.category_id, stock = excluded.stock,
+                    images = excluded.images, status = excluded.status, created_at = excluded.created_at,
+                    updated_at = excluded.updated_at, deleted_at = excluded.deleted_at, deleted_by = excluded.deleted_by,
+                    variant_options = excluded.variant_options, reorder_threshold = excluded.reorder_threshold, version = products.version + 1
+                RETURNING id
+                "#
+            )
+            .bind(sync_id)
+            .bind(tenant_id)
+            .bind(&data.name)
+            .bind(data.price)
+            .bind(&data.currency)
+            .bind(&data.description)
+            .bind(&data.category_sync_id)
+            .bind(data.stock)
+            .bind(&data.images)
+            .bind(data.status)
+            .bind(data.created_at)
+            .bind(data.updated_at)
+            .bind(data.deleted_at)
+            .bind(&data.deleted_by_name)
+            .bind(&data.variant_options)
+            .bind(data.reorder_threshold)
+            .fetch_one(conn)
+            .await
+        })?;
+
+        let local_skus = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>("SELECT sku FROM product_variants WHERE product_id = $1 AND deleted_at IS NULL")
+                .bind(product_id)
+                .fetch_all(conn)
+                .await
+        })?;
+        for sku in local_skus.iter().filter(|sku| !data.variants.iter().any(|variant| &variant.sku == *sku)) {
+            with_conn!(&mut self.conn, |conn| {
+                sqlx::query("UPDATE product_variants SET deleted_at = $1 WHERE product_id = $2 AND sku = $3 AND deleted_at IS NULL")
+                    .bind(Utc::now())
+                    .bind(product_id)
+                    .bind(sku)
+                    .execute(conn)
+                    .await
+                    .map(|_| ())
+            })?;
+        }
+
+        for variant in &data.variants {
+            let sql = if local_skus.contains(&variant.sku) {
+                "UPDATE product_variants SET barcode = $3, options = $4, price = $5, stock = $6, image = $7, sort_order = $8, created_at = $9, updated_at = $10 WHERE product_id = $1 AND sku = $2 AND deleted_at IS NULL AND tenant_id = $11"
+            } else {
+                "INSERT INTO product_variants (product_id, sku, barcode, options, price, stock, image, sort_order, created_at, updated_at, tenant_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"
+            };
+            with_conn!(&mut self.conn, |conn| {
+                sqlx::query(sql)
+                    .bind(product_id)
+                    .bind(&variant.sku)
+                    .bind(&variant.barcode)
+                    .bind(&variant.options)
+                    .bind(variant.price)
+                    .bind(variant.stock)
+                    .bind(&variant.image)
+                    .bind(variant.sort_order)
+                    .bind(variant.created_at)
+                    .bind(variant.updated_at)
+                    .bind(tenant_id)
+                    .execute(conn)
+                    .await
+                    .map(|_| ())
+            })?;
+        }
+
+        // 库存流水不同步，远端带来的库存变化在本地记为一笔调整，对账才能平衡
+        for sql in [SYNC_PRODUCT_STOCK_SQL, SYNC_VARIANT_STOCK_SQL] {
+            with_conn!(&mut self.conn, |conn| {
+                sqlx::query(sql)
+                    .bind(product_id)
+                    .bind(Utc::now())
+                    .execute(conn)
+                    .await
+                    .map(|_| ())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// 写入远端的订单并整体替换订单明细
+    pub async fn apply_order(&mut self, sync_id: &str, tenant_id: i64, data: &OrderSyncData) -> Result<(), AppError> {
+        // 先检查会导致写到一半失败的情况，避免订单写入了而明细没有
+        let duplicate = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM orders WHERE order_no = $1 AND sync_id <> $2")
+                .bind(&data.order_no)
+                .bind(sync_id)
+                .fetch_one(conn)
+                .await
+        })?;
+        if duplicate > 0 {
+            return Err(AppError::Conflict(format!("订单号 {} 已被本地的其他订单使用", data.order_no)));
+        }
+
+        let mut item_refs = Vec::with_capacity(data.items.len());
+        for item in &data.items {
+            let product_id = match &item.product_sync_id {
+                Some(product_sync_id) => with_conn!(&mut self.conn, |conn| {
+                    sqlx::query_scalar::<_, i64>("SELECT id FROM products WHERE sync_id = $1")
+                        .bind(product_sync_id)
+                        .fetch_optional(conn)
+                        .await
+                })?,
+                None => None,
+            };
+            let product_id = product_id
+                .ok_or_else(|| AppError::NotFound(format!("订单明细引用的商品“{}”不存在", item.product_name)))?;
+            // 规格还没有同步过来时只记录 SKU，不指向规格
+            let variant_id = match &item.sku {
+                Some(sku) => with_conn!(&mut self.conn, |conn| {
+                    sqlx::query_scalar::<_, i64>("SELECT id FROM product_variants WHERE product_id = $1 AND sku = $2 ORDER BY id DESC LIMIT 1")
+                        .bind(product_id)
+                        .bind(sku)
+                        .fetch_optional(conn)
+                        .await
+                })?,
+                None => None,
+            };
+            item_refs.push((product_id, variant_id));
+        }
+
+        let order_id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                r#"
+                INSERT INTO orders (sync_id, tenant_id, order_no, user_id, total_amount, currency, status, created_at, updated_at)
+                VALUES ($1, $2, $3, COALESCE((SELECT id FROM users WHERE username = $4), $5), $6, $7, $8, $9, $10)
+                ON CONFLICT (sync_id) DO UPDATE SET
+                    tenant_id = excluded.tenant_id, order_no = excluded.order_no, user_id = excluded.user_id,
+                    total_amount = excluded.total_amount, currency = excluded.currency, status = excluded.status, created_at = excluded.created_at,
+                    updated_at = excluded.updated_at, version = orders.version + 1
+                RETURNING id
+                "#
+            )
+            .bind(sync_id)
+            .bind(tenant_id)
+            .bind(&data.order_no)
+            .bind(&data.username)
+            .bind(FALLBACK_USER_ID)
+            .bind(data.total_amount)
+            .bind(&data.currency)
+            .bind(&data.status)
+            .bind(data.created_at)
+            .bind(data.updated_at)
+            .fetch_one(conn)
+            .await
+        })?;
+
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM order_items WHERE order_id = $1")
+                .bind(order_id)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+
+        for (item, (product_id, variant_id)) in data.items.iter().zip(item_refs) {
+            with_conn!(&mut self.conn, |conn| {
+                sqlx::query(
+                    "INSERT INTO order_items (order_id, product_id, variant_id, sku, product_name, price, quantity, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+                )
+                .bind(order_id)
+                .bind(product_id)
+                .bind(variant_id)
+                .bind(&item.sku)
+                .bind(&item.product_name)
+                .bind(item.price)
+                .bind(item.quantity)
+                .bind(item.created_at)
+                .execute(conn)
+                .await
+                .map(|_| ())
+            })?;
+        }
+        Ok(())
+    }
+
+    /// 删除远端已经永久删除的行
+    pub async fn delete_row(&mut self, entity: SyncEntity, sync_id: &str) -> Result<(), AppError> {
+        if entity == SyncEntity::Orders {
+            with_conn!(&mut self.conn, |conn| {
+                sqlx::query("DELETE FROM order_items WHERE order_id IN (SELECT id FROM orders WHERE sync_id = $1)")
+                    .bind(sync_id)
+                    .execute(conn)
+                    .await
+                    .map(|_| ())
+            })?;
+        }
+
+        // 表名来自固定的枚举值
+        let sql = format!("DELETE FROM {} WHERE sync_id = $1", entity.table());
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(&sql)
+                .bind(sync_id)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use chrono::Utc;
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::category::Category;
+use crate::models::order::{Order, OrderItem};
+use crate::models::product::{Product, ProductVariant};
+use crate::models::stock::StockMovement;
+use crate::models::tenant::*;
+use crate::with_conn;
+
+pub struct TenantRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> TenantRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, id: i64) -> Result<Tenant, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Tenant>("SELECT * FROM tenants WHERE id = $1")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("店铺不存在".to_string()))
+    }
+
+    pub async fn list(&mut self) -> Result<Vec<Tenant>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Tenant>("SELECT * FROM tenants ORDER BY id")
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn code_exists(&mut self, code: &str) -> Result<bool, AppError> {
+        let count = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tenants WHERE code = $1")
+                .bind(code)
+                .fetch_one(conn)
+                .await
+        })?;
+        Ok(count > 0)
+    }
+
+    pub async fn create(&mut self, request: &CreateTenantRequest) -> Result<Tenant, AppError> {
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO tenants (name, code, status, created_at, updated_at) VALUES ($1, $2, $3, $4, $5) RETURNING id"
+            )
+            .bind(&request.name)
+            .bind(&request.code)
+            .bind(1) // 默认状态为启用
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        self.find(id).await
+    }
+
+    pub async fn update(&mut self, id: i64, request: &UpdateTenantRequest) -> Result<Tenant, AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "UPDATE tenants SET name = $1, status = $2, updated_at = $3, version = version + 1 WHERE id = $4 AND version = $5"
+            )
+            .bind(&request.name)
+            .bind(request.status)
+            .bind(Utc::now())
+            .bind(id)
+            .bind(request.version)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            // 记录仍然存在，说明提交的版本已过期
+            let current = self.find(id).await?;
+            return Err(AppError::version_conflict(&current));
+        }
+
+        self.find(id).await
+    }
+
+    /// 用户所属的启用状态店铺
+    pub async fn list_for_user(&mut self, user_id: i64) -> Result<Vec<UserTenant>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, UserTenant>(
+                r#"
+                SELECT t.id, t.name, t.code
+                FROM tenant_users tu
+                JOIN tenants t ON tu.tenant_id = t.id
+                WHERE tu.user_id = $1 AND t.status = 1
+                ORDER BY t.id
+                "#
+            )
+            .bind(user_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn is_member(&mut self, tenant_id: i64, user_id: i64) -> Result<bool, AppError> {
+        let count = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tenant_users WHERE tenant_id = $1 AND user_id = $2")
+                .bind(tenant_id)
+                .bind(user_id)
+                .fetch_one(conn)
+                .await
+        })?;
+        Ok(count > 0)
+    }
+
+    pub async fn list_members(&mut self, tenant_id: i64) -> Result<Vec<TenantMember>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, TenantMember>(
+                r#"
+                SELECT u.id as user_id, u.username, COALESCE(r.name, '未知角色') as role_name, tu.created_at as joined_at
+                FROM tenant_users tu
+                JOIN users u ON tu.user_id = u.id
+                LEFT JOIN roles r ON u.role_id = r.id
+                WHERE tu.tenant_id = $1 AND u.deleted_at IS NULL
+                ORDER BY tu.created_at, u.id
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    /// 把用户加入店铺，已经是成员时不做任何修改
+    pub async fn add_member(&mut self, tenant_id: i64, user_id: i64) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("INSERT INTO tenant_users (tenant_id, user_id, created_at) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING")
+                .bind(tenant_id)
+                .bind(user_id)
+                .bind(Utc::now())
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    pub async fn remove_member(&mut self, tenant_id: i64, user_id: i64) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("DELETE FROM tenant_users WHERE tenant_id = $1 AND user_id = $2")
+                .bind(tenant_id)
+                .bind(user_id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("该用户不是店铺成员".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 用户记录的当前店铺，可能已经不再是成员或已停用，由调用方校验
+    pub async fn current_tenant_id(&mut self, user_id: i64) -> Result<Option<i64>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, Option<i64>>("SELECT current_tenant_id FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .flatten())
+    }
+
+    pub async fn set_current_tenant_id(&mut self, user_id: i64, tenant_id: i64) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE users SET current_tenant_id = $1 WHERE id = $2")
+                .bind(tenant_id)
+                .bind(user_id)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 导出用：店铺中未删除的分类
+    pub async fn export_categories(&mut self, tenant_id: i64) -> Result<Vec<Category>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE tenant_id = $1 AND deleted_at IS NULL ORDER BY id")
+                .bind(tenant_id)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn export_products(&mut self, tenant_id: i64) -> Result<Vec<Product>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Product>("SELECT * FROM products WHERE tenant_id = $1 AND deleted_at IS NULL ORDER BY id")
+                .bind(tenant_id)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn export_product_variants(&mut self, tenant_id: i64) -> Result<Vec<ProductVariant>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, ProductVariant>("SELECT * FROM product_variants WHERE tenant_id = $1 ORDER BY id")
+                .bind(tenant_id)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn export_stock_movements(&mut self, tenant_id: i64) -> Result<Vec<StockMovement>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, StockMovement>(
+                "SELECT m.*, u.username as actor_name FROM stock_movements m LEFT JOIN users u ON m.actor_id = u.id WHERE m.tenant_id = $1 ORDER BY m.id"
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn export_orders(&mut self, tenant_id: i64) -> Result<Vec<Order>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, Order>("SELECT * FROM orders WHERE tenant_id = $1 ORDER BY id")
+                .bind(tenant_id)
+                .fetch_all(conn)
+                .await
+        })?)
+    }
+
+    pub async fn export_order_items(&mut self, tenant_id: i64) -> Result<Vec<OrderItem>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, OrderItem>(
+                r#"
+                SELECT oi.*
+                FROM order_items oi
+                JOIN orders o ON oi.order_id = o.id
+                WHERE o.tenant_id = $1
+                ORDER BY oi.id
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+}
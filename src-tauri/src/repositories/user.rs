@@ -0,0 +1,276 @@
+use chrono::{DateTime, Utc};
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::recycle_bin::RecycleBinItem;
+use crate::models::user::*;
+use crate::repositories::search::USER_SEARCH;
+use crate::utils::search::SearchQuery;
+use crate::with_conn;
+
+const USER_WITH_ROLE_COLUMNS: &str = r#"
+    SELECT u.id, u.username, u.email, u.phone, u.address, u.avatar,
+           u.role_id, COALESCE(r.name, '未知角色') as role_name, COALESCE(r.permissions, '[]') as permissions,
+           u.status, u.created_at, u.updated_at, u.version
+    FROM users u
+    LEFT JOIN roles r ON u.role_id = r.id
+"#;
+
+pub struct UserRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> UserRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, id: i64) -> Result<User, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("用户不存在".to_string()))
+    }
+
+    /// 按用户名或邮箱查找，用于登录
+    pub async fn find_by_login(&mut self, login: &str) -> Result<Option<User>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, User>("SELECT * FROM users WHERE (username = $1 OR email = $1) AND deleted_at IS NULL")
+                .bind(login)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+
+    /// 启用状态用户的角色，禁用或不存在时返回 None
+    pub async fn active_role_id(&mut self, id: i64) -> Result<Option<i64>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT role_id FROM users WHERE id = $1 AND status = 1 AND deleted_at IS NULL")
+                .bind(id)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+
+    /// 回收站中的用户仍然占用用户名和邮箱（唯一约束），这里不排除已删除的用户
+    pub async fn exists(&mut self, username: &str, email: &str) -> Result<bool, AppError> {
+        let count = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users WHERE username = $1 OR email = $2")
+                .bind(username)
+                .bind(email)
+                .fetch_one(conn)
+                .await
+        })?;
+        Ok(count > 0)
+    }
+
+    /// 店铺成员列表，用户账号本身不属于某个店铺
+    pub async fn list(&mut self, tenant_id: i64, search: Option<&SearchQuery>, limit: i32, offset: i32) -> Result<Vec<UserWithRole>, AppError> {
+        let filter = USER_SEARCH.filter(self.conn.backend(), search);
+        let sql = format!(
+            "{} WHERE {} AND u.deleted_at IS NULL{} ORDER BY u.created_at DESC LIMIT ${} OFFSET ${}",
+            USER_WITH_ROLE_COLUMNS,
+            member_condition(filter.next_param()),
+            filter.sql,
+            filter.next_param() + 1,
+            filter.next_param() + 2
+        );
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            let mut query = sqlx::query_as::<_, UserWithRole>(&sql);
+            for param in &filter.params {
+                query = query.bind(param);
+            }
+            query.bind(tenant_id).bind(limit).bind(offset).fetch_all(conn).await
+        })?)
+    }
+
+    pub async fn count(&mut self, tenant_id: i64, search: Option<&SearchQuery>) -> Result<i64, AppError> {
+        let filter = USER_SEARCH.filter(self.conn.backend(), search);
+        let sql = format!(
+            "SELECT COUNT(*) FROM users u WHERE {} AND u.deleted_at IS NULL{}",
+            member_condition(filter.next_param()),
+            filter.sql
+        );
+
+        Ok(with_conn!(&mut self.conn, |conn| {
+            let mut query = sqlx::query_scalar::<_, i64>(&sql);
+            for param in &filter.params {
+                query = query.bind(param);
+            }
+            query.bind(tenant_id).fetch_one(conn).await
+        })?)
+    }
+
+    pub async fn create(&mut self, request: &CreateUserRequest, password_hash: &str) -> Result<User, AppError> {
+        let id = with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                r#"INSERT INTO users (username, email, password_hash, phone, address, avatar, role_id, status, created_at, updated_at)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                   RETURNING id"#
+            )
+            .bind(&request.username)
+            .bind(&request.email)
+            .bind(password_hash)
+            .bind(&request.phone)
+            .bind(&request.address)
+            .bind(&request.avatar)
+            .bind(request.role_id)
+            .bind(1) // 默认状态为启用
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?;
+
+        self.find(id).await
+    }
+
+    pub async fn update(&mut self, id: i64, request: &UpdateUserRequest) -> Result<User, AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "UPDATE users SET username = $1, email = $2, phone = $3, address = $4, avatar = $5, role_id = $6, updated_at = $7, version = version + 1 WHERE id = $8 AND deleted_at IS NULL AND version = $9"
+            )
+            .bind(&request.username)
+            .bind(&request.email)
+            .bind(&request.phone)
+            .bind(&request.address)
+            .bind(&request.avatar)
+            .bind(request.role_id)
+            .bind(Utc::now())
+            .bind(id)
+            .bind(request.version)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            // 记录仍然存在，说明提交的版本已过期
+            let current = self.find(id).await?;
+            return Err(AppError::version_conflict(&current));
+        }
+
+        self.find(id).await
+    }
+
+    /// 只更新联系方式和头像，不涉及用户名、邮箱和角色
+    pub async fn update_profile(&mut self, id: i64, request: &UpdateUserRequest) -> Result<User, AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE users SET phone = $1, address = $2, avatar = $3, updated_at = $4, version = version + 1 WHERE id = $5 AND deleted_at IS NULL AND version = $6")
+                .bind(&request.phone)
+                .bind(&request.address)
+                .bind(&request.avatar)
+                .bind(Utc::now())
+                .bind(id)
+                .bind(request.version)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            // 记录仍然存在，说明提交的版本已过期
+            let current = self.find(id).await?;
+            return Err(AppError::version_conflict(&current));
+        }
+
+        self.find(id).await
+    }
+
+    /// 保存登录失败次数、锁定时间和最后登录时间
+    pub async fn update_login_state(&mut self, user: &User) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                r#"
+                UPDATE users
+                SET failed_login_attempts = $1, last_failed_login = $2, locked_until = $3, last_login = $4
+                WHERE id = $5
+                "#
+            )
+            .bind(user.failed_login_attempts)
+            .bind(user.last_failed_login)
+            .bind(user.locked_until)
+            .bind(user.last_login)
+            .bind(user.id)
+            .execute(conn)
+            .await
+            .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 软删除：移入回收站，`deleted_by` 为操作人
+    pub async fn delete(&mut self, id: i64, deleted_by: Option<i64>) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE users SET deleted_at = $1, deleted_by = $2 WHERE id = $3 AND deleted_at IS NULL")
+                .bind(Utc::now())
+                .bind(deleted_by)
+                .bind(id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("用户不存在".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 回收站中属于该店铺的用户，最近删除的在前
+    pub async fn list_deleted(&mut self, tenant_id: i64) -> Result<Vec<RecycleBinItem>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, RecycleBinItem>(
+                r#"
+                SELECT u.id, u.username as name, u.deleted_at, u.deleted_by, d.username as deleted_by_name
+                FROM users u
+                LEFT JOIN users d ON u.deleted_by = d.id
+                WHERE u.deleted_at IS NOT NULL
+                  AND EXISTS (SELECT 1 FROM tenant_users tu WHERE tu.user_id = u.id AND tu.tenant_id = $1)
+                ORDER BY u.deleted_at DESC
+                "#
+            )
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    pub async fn restore(&mut self, id: i64) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE users SET deleted_at = NULL, deleted_by = NULL, updated_at = $1 WHERE id = $2 AND deleted_at IS NOT NULL")
+                .bind(Utc::now())
+                .bind(id)
+                .execute(conn)
+                .await
+                .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("回收站中没有该用户".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 永久删除在 `before` 之前移入回收站的用户，仍被订单引用的用户保留在回收站中
+    pub async fn purge_deleted(&mut self, before: DateTime<Utc>) -> Result<u64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                r#"
+                DELETE FROM users
+                WHERE deleted_at IS NOT NULL AND deleted_at < $1
+                  AND NOT EXISTS (SELECT 1 FROM orders o WHERE o.user_id = users.id)
+                "#
+            )
+            .bind(before)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?)
+    }
+}
+
+// 用户是 `$param` 店铺的成员
+fn member_condition(param: usize) -> String {
+    format!("EXISTS (SELECT 1 FROM tenant_users tu WHERE tu.user_id = u.id AND tu.tenant_id = ${})", param)
+}
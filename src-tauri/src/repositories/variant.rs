@@ -0,0 +1,126 @@
+use chrono::Utc;
+
+use crate::database::DbConn;
+use crate::error::AppError;
+use crate::models::product::{ProductVariant, VariantFields};
+use crate::with_conn;
+
+/// 商品规格，查询都限定在店铺内；删除的规格保留给已有的订单明细引用
+pub struct VariantRepository<'a> {
+    conn: DbConn<'a>,
+}
+
+impl<'a> VariantRepository<'a> {
+    pub fn new(conn: impl Into<DbConn<'a>>) -> Self {
+        Self { conn: conn.into() }
+    }
+
+    pub async fn find(&mut self, tenant_id: i64, id: i64) -> Result<ProductVariant, AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, ProductVariant>("SELECT * FROM product_variants WHERE id = $1 AND tenant_id = $2 AND deleted_at IS NULL")
+                .bind(id)
+                .bind(tenant_id)
+                .fetch_optional(conn)
+                .await
+        })?
+        .ok_or_else(|| AppError::NotFound("商品规格不存在".to_string()))
+    }
+
+    pub async fn list(&mut self, tenant_id: i64, product_id: i64) -> Result<Vec<ProductVariant>, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_as::<_, ProductVariant>(
+                "SELECT * FROM product_variants WHERE product_id = $1 AND tenant_id = $2 AND deleted_at IS NULL ORDER BY sort_order, id"
+            )
+            .bind(product_id)
+            .bind(tenant_id)
+            .fetch_all(conn)
+            .await
+        })?)
+    }
+
+    /// 新规格的库存为 0，初始库存由调用方记为入库流水
+    pub async fn create(&mut self, tenant_id: i64, product_id: i64, variant: &VariantFields) -> Result<i64, AppError> {
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO product_variants (tenant_id, product_id, sku, barcode, options, price, image, sort_order, created_at, updated_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id"
+            )
+            .bind(tenant_id)
+            .bind(product_id)
+            .bind(&variant.sku)
+            .bind(&variant.barcode)
+            .bind(&variant.options)
+            .bind(variant.price)
+            .bind(&variant.image)
+            .bind(variant.sort_order)
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .fetch_one(conn)
+            .await
+        })?)
+    }
+
+    pub async fn update(&mut self, tenant_id: i64, product_id: i64, id: i64, variant: &VariantFields) -> Result<(), AppError> {
+        let affected = with_conn!(&mut self.conn, |conn| {
+            sqlx::query(
+                "UPDATE product_variants SET sku = $1, barcode = $2, options = $3, price = $4, image = $5, sort_order = $6, updated_at = $7 WHERE id = $8 AND product_id = $9 AND tenant_id = $10 AND deleted_at IS NULL"
+            )
+            .bind(&variant.sku)
+            .bind(&variant.barcode)
+            .bind(&variant.options)
+            .bind(variant.price)
+            .bind(&variant.image)
+            .bind(variant.sort_order)
+            .bind(Utc::now())
+            .bind(id)
+            .bind(product_id)
+            .bind(tenant_id)
+            .execute(conn)
+            .await
+            .map(|r| r.rows_affected())
+        })?;
+        if affected == 0 {
+            return Err(AppError::NotFound("商品规格不存在".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 软删除，已下单的明细仍然可以查到规格
+    pub async fn delete(&mut self, tenant_id: i64, id: i64) -> Result<(), AppError> {
+        with_conn!(&mut self.conn, |conn| {
+            sqlx::query("UPDATE product_variants SET deleted_at = $1 WHERE id = $2 AND tenant_id = $3 AND deleted_at IS NULL")
+                .bind(Utc::now())
+                .bind(id)
+                .bind(tenant_id)
+                .execute(conn)
+                .await
+                .map(|_| ())
+        })?;
+        Ok(())
+    }
+
+    /// 店铺内其他商品正在使用该 SKU 时返回该商品的名称
+    pub async fn sku_owner(&mut self, tenant_id: i64, product_id: i64, sku: &str) -> Result<Option<String>, AppError> {
+        self.owner("sku", tenant_id, product_id, sku).await
+    }
+
+    /// 店铺内其他商品正在使用该条码时返回该商品的名称
+    pub async fn barcode_owner(&mut self, tenant_id: i64, product_id: i64, barcode: &str) -> Result<Option<String>, AppError> {
+        self.owner("barcode", tenant_id, product_id, barcode).await
+    }
+
+    // 列名来自上面两个固定的调用
+    async fn owner(&mut self, column: &str, tenant_id: i64, product_id: i64, value: &str) -> Result<Option<String>, AppError> {
+        let sql = format!(
+            "SELECT p.name FROM product_variants v JOIN products p ON v.product_id = p.id WHERE v.tenant_id = $1 AND v.{} = $2 AND v.product_id <> $3 AND v.deleted_at IS NULL",
+            column
+        );
+        Ok(with_conn!(&mut self.conn, |conn| {
+            sqlx::query_scalar::<_, String>(&sql)
+                .bind(tenant_id)
+                .bind(value)
+                .bind(product_id)
+                .fetch_optional(conn)
+                .await
+        })?)
+    }
+}
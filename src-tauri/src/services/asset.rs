@@ -0,0 +1,339 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{Cursor, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageDecoder, ImageError, ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::asset::{Asset, AssetCleanup};
+use crate::repositories::ProductRepository;
+
+/// 保存在商品中的图片引用的前缀，后面是文件名
+pub const REFERENCE_PREFIX: &str = "media:";
+/// 缩略图的长边像素，分别保存在 assets/<尺寸>/ 目录下
+pub const THUMBNAIL_SIZES: [u32; 3] = [128, 256, 512];
+/// 导入文件的大小上限
+const MAX_FILE_SIZE: u64 = 20 * 1024 * 1024;
+/// 宽高上限，避免解码尺寸异常的图片耗尽内存
+const MAX_DIMENSION: u32 = 12_000;
+const JPEG_QUALITY: u8 = 90;
+const THUMBNAIL_QUALITY: u8 = 80;
+/// 刚导入、还没来得及保存到商品中的图片，在这段时间内不会被清理
+const GC_GRACE: Duration = Duration::from_secs(24 * 60 * 60);
+/// 自动清理的间隔
+const GC_TICK: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 商品图片的存储：文件名为原始文件的 SHA-256，相同的图片只保存一份
+pub struct AssetService<'a> {
+    pool: &'a DbPool,
+    dir: &'a Path,
+}
+
+impl<'a> AssetService<'a> {
+    pub fn new(pool: &'a DbPool, dir: &'a Path) -> Self {
+        Self { pool, dir }
+    }
+
+    /// 导入本地的图片文件：去掉 EXIF 等元数据（方向先应用到像素上）并生成各尺寸的缩略图。
+    /// GIF 只保留第一帧
+    pub async fn import(&self, path: &Path) -> Result<Asset, AppError> {
+        let dir = self.dir.to_path_buf();
+        let path = path.to_path_buf();
+        // 解码和编码很耗 CPU，放到阻塞线程池中执行
+        tokio::task::spawn_blocking(move || import_file(&dir, &path))
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+    }
+
+    /// 删除没有被任何商品或规格引用的图片及其缩略图
+    pub async fn collect_garbage(&self) -> Result<AssetCleanup, AppError> {
+        let mut referenced = HashSet::new();
+        for value in ProductRepository::new(self.pool).image_references().await? {
+            // 商品的 images 是 JSON 数组，规格的 image 是单个字符串
+            let references = serde_json::from_str::<Vec<String>>(&value).unwrap_or_else(|_| vec![value]);
+            referenced.extend(references.iter().filter_map(|reference| asset_name(reference)).map(str::to_string));
+        }
+
+        let dir = self.dir.to_path_buf();
+        tokio::task::spawn_blocking(move || remove_unreferenced(&dir, &referenced))
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+    }
+}
+
+/// 引用对应的文件名，不是本地图片的引用（例如网络地址）时为 None
+pub fn asset_name(reference: &str) -> Option<&str> {
+    reference
+        .strip_prefix(REFERENCE_PREFIX)
+        .filter(|name| mime_type(name).is_some())
+}
+
+/// media 协议的地址对应的文件和 MIME 类型。路径 `/<文件名>` 为原图，`/<尺寸>/<文件名>` 为缩略图；
+/// 各平台的地址形式不同（`media://localhost/...` 或 `https://media.localhost/...`），只看路径部分
+pub fn resolve(dir: &Path, uri: &str) -> Option<(PathBuf, &'static str)> {
+    let without_scheme = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+    let without_query = without_scheme.split(['?', '#']).next().unwrap_or_default();
+    let path = without_query.split_once('/').map_or("", |(_, path)| path);
+
+    match path.split_once('/') {
+        Some((size, name)) => {
+            let size = size.parse::<u32>().ok().filter(|size| THUMBNAIL_SIZES.contains(size))?;
+            Some((dir.join(size.to_string()).join(name), mime_type(name)?))
+        }
+        None => Some((dir.join(path), mime_type(path)?)),
+    }
+}
+
+/// 文件名必须是 64 位小写十六进制的哈希加上支持的扩展名，也保证了路径不会跳出图片目录
+fn mime_type(name: &str) -> Option<&'static str> {
+    let (hash, extension) = name.split_once('.')?;
+    if hash.len() != 64 || !hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+        return None;
+    }
+    match extension {
+        "jpg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        _ => None,
+    }
+}
+
+fn import_file(dir: &Path, path: &Path) -> Result<Asset, AppError> {
+    let metadata = fs::metadata(path).map_err(|_| AppError::NotFound(format!("文件 {} 不存在", path.display())))?;
+    if metadata.len() > MAX_FILE_SIZE {
+        return Err(AppError::Invalid(format!("图片不能超过 {} MB", MAX_FILE_SIZE / 1024 / 1024)));
+    }
+    let bytes = fs::read(path)?;
+    let hash: String = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect();
+
+    let mut reader = ImageReader::new(Cursor::new(&bytes)).with_guessed_format()?;
+    let format = match reader.format() {
+        Some(format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP | ImageFormat::Gif)) => format,
+        _ => return Err(AppError::Invalid("只支持 JPEG、PNG、WebP 和 GIF 格式的图片".to_string())),
+    };
+    // 照片保持 JPEG；其他格式可能有透明通道，统一保存为 PNG
+    let extension = if format == ImageFormat::Jpeg { "jpg" } else { "png" };
+    let name = format!("{}.{}", hash, extension);
+    let original = dir.join(&name);
+
+    if original.exists() {
+        // 刷新修改时间，避免这次导入还没保存到商品中就被清理
+        fs::File::options().write(true).open(&original)?.set_modified(SystemTime::now())?;
+        let (width, height) = image::image_dimensions(&original).map_err(invalid_image)?;
+        let size = fs::metadata(&original)?.len();
+        return Ok(asset(hash, extension, width, height, size, true));
+    }
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder().map_err(invalid_image)?;
+    let orientation = decoder.orientation().map_err(invalid_image)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(invalid_image)?;
+    image.apply_orientation(orientation);
+
+    // 先写缩略图、最后写原图，原图存在就说明这张图片已经完整导入
+    for size in THUMBNAIL_SIZES {
+        let thumbnail = if image.width() > size || image.height() > size {
+            image.thumbnail(size, size)
+        } else {
+            image.clone()
+        };
+        let encoded = encode(&thumbnail, extension, THUMBNAIL_QUALITY)?;
+        write_file(dir, &dir.join(size.to_string()).join(&name), &encoded)?;
+    }
+    let encoded = encode(&image, extension, JPEG_QUALITY)?;
+    write_file(dir, &original, &encoded)?;
+
+    Ok(asset(hash, extension, image.width(), image.height(), encoded.len() as u64, false))
+}
+
+fn asset(hash: String, extension: &str, width: u32, height: u32, size: u64, deduplicated: bool) -> Asset {
+    Asset {
+        reference: format!("{}{}.{}", REFERENCE_PREFIX, hash, extension),
+        mime_type: mime_type(&format!("{}.{}", hash, extension)).unwrap_or_default().to_string(),
+        hash,
+        width,
+        height,
+        size,
+        deduplicated,
+    }
+}
+
+fn invalid_image(e: ImageError) -> AppError {
+    match e {
+        ImageError::Limits(_) => AppError::Invalid(format!("图片的宽高不能超过 {} 像素", MAX_DIMENSION)),
+        ImageError::IoError(e) => e.into(),
+        e => AppError::Invalid(format!("无法读取图片: {}", e)),
+    }
+}
+
+// 重新编码时不写入任何元数据
+fn encode(image: &DynamicImage, extension: &str, quality: u8) -> Result<Vec<u8>, AppError> {
+    let mut buffer = Vec::new();
+    let result = if extension == "jpg" {
+        // JPEG 不支持透明通道和 16 位色深
+        image.to_rgb8().write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, quality))
+    } else {
+        image.write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+    };
+    result.map_err(|e| AppError::Internal(format!("图片编码失败: {}", e)))?;
+    Ok(buffer)
+}
+
+// 先写到图片目录下的临时文件再改名，读取方不会看到写了一半的文件
+fn write_file(dir: &Path, path: &Path, bytes: &[u8]) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let temp = dir.join(format!("{}.tmp", uuid::Uuid::new_v4()));
+    fs::write(&temp, bytes)?;
+    if let Err(e) = fs::rename(&temp, path) {
+        let _ = fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+fn remove_unreferenced(dir: &Path, referenced: &HashSet<String>) -> Result<AssetCleanup, AppError> {
+    let mut cleanup = AssetCleanup::default();
+    let expired = SystemTime::now().checked_sub(GC_GRACE).unwrap_or(SystemTime::UNIX_EPOCH);
+
+    // 原图和中断的导入留下的临时文件
+    for (path, name, metadata) in old_files(dir, expired)? {
+        let orphan = name.ends_with(".tmp") || (mime_type(&name).is_some() && !referenced.contains(&name));
+        if orphan {
+            fs::remove_file(&path)?;
+            cleanup.files_removed += 1;
+            cleanup.bytes_freed += metadata.len();
+        }
+    }
+    // 原图已经删除或者没有写完的缩略图
+    for size in THUMBNAIL_SIZES {
+        for (path, name, metadata) in old_files(&dir.join(size.to_string()), expired)? {
+            if !dir.join(&name).exists() {
+                fs::remove_file(&path)?;
+                cleanup.files_removed += 1;
+                cleanup.bytes_freed += metadata.len();
+            }
+        }
+    }
+    Ok(cleanup)
+}
+
+// 目录中在 `before` 之前修改过的文件，目录不存在时为空
+fn old_files(dir: &Path, before: SystemTime) -> Result<Vec<(PathBuf, String, fs::Metadata)>, AppError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() && metadata.modified()? < before {
+            files.push((entry.path(), entry.file_name().to_string_lossy().into_owned(), metadata));
+        }
+    }
+    Ok(files)
+}
+
+/// 启动图片自动清理任务
+pub fn spawn_cleanup_task(pool: DbPool, dir: PathBuf) {
+    tokio::spawn(async move {
+        loop {
+            // 恢复备份期间连接池会被关闭
+            if !pool.is_closed() {
+                match AssetService::new(&pool, &dir).collect_garbage().await {
+                    Ok(cleanup) if cleanup.files_removed > 0 => println!(
+                        "Removed {} unused asset files ({} bytes)",
+                        cleanup.files_removed, cleanup.bytes_freed
+                    ),
+                    Ok(_) => {}
+                    Err(e) => println!("Asset cleanup failed: {}", e),
+                }
+            }
+            tokio::time::sleep(GC_TICK).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash() -> String {
+        "0123456789abcdef".repeat(4)
+    }
+
+    #[test]
+    fn resolves_originals_and_thumbnails() {
+        let dir = Path::new("/data/assets");
+        let hash = hash();
+
+        assert_eq!(
+            resolve(dir, &format!("media://localhost/{}.jpg", hash)),
+            Some((dir.join(format!("{}.jpg", hash)), "image/jpeg"))
+        );
+        assert_eq!(
+            resolve(dir, &format!("https://media.localhost/256/{}.png", hash)),
+            Some((dir.join("256").join(format!("{}.png", hash)), "image/png"))
+        );
+        // 查询参数和片段不影响文件
+        assert_eq!(
+            resolve(dir, &format!("media://localhost/128/{}.jpg?v=2#top", hash)),
+            Some((dir.join("128").join(format!("{}.jpg", hash)), "image/jpeg"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_sizes_and_paths_outside_the_directory() {
+        let dir = Path::new("/data/assets");
+        let hash = hash();
+
+        for uri in [
+            format!("media://localhost/999/{}.png", hash),
+            format!("media://localhost/0/{}.png", hash),
+            format!("media://localhost/256/256/{}.png", hash),
+            format!("media://localhost/../{}.png", hash),
+            format!("media://localhost/256/../{}.png", hash),
+            "media://localhost/../../etc/passwd".to_string(),
+            "media://localhost/".to_string(),
+            "media://localhost".to_string(),
+        ] {
+            assert_eq!(resolve(dir, &uri), None, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn accepts_only_lowercase_hashes_with_known_extensions() {
+        let hash = hash();
+
+        assert_eq!(mime_type(&format!("{}.jpg", hash)), Some("image/jpeg"));
+        assert_eq!(mime_type(&format!("{}.png", hash)), Some("image/png"));
+
+        assert_eq!(mime_type(&format!("{}.jpg", hash.to_uppercase())), None);
+        assert_eq!(mime_type(&format!("{}.jpg", &hash[1..])), None);
+        assert_eq!(mime_type(&format!("{}0.jpg", hash)), None);
+        assert_eq!(mime_type(&format!("{}.jpg", hash.replace('a', "g"))), None);
+        assert_eq!(mime_type(&format!("{}.gif", hash)), None);
+        assert_eq!(mime_type(&format!("{}.JPG", hash)), None);
+        assert_eq!(mime_type(&format!("{}.jpg.tmp", hash)), None);
+        assert_eq!(mime_type(&hash), None);
+    }
+
+    #[test]
+    fn extracts_asset_names_from_references() {
+        let name = format!("{}.png", hash());
+
+        assert_eq!(asset_name(&format!("media:{}", name)), Some(name.as_str()));
+        assert_eq!(asset_name(&name), None);
+        assert_eq!(asset_name("https://example.com/a.png"), None);
+    }
+}
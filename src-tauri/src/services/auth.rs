@@ -0,0 +1,122 @@
+use std::str::FromStr;
+use chrono::{Duration, Utc};
+
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::role::Role;
+use crate::models::user::*;
+use crate::repositories::{RoleRepository, SettingRepository, UserRepository};
+use crate::utils::masking::mask_text;
+
+pub struct AuthService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> AuthService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 校验用户名（或邮箱）和密码，按安全设置处理失败计数和账户锁定
+    pub async fn login(&self, request: &LoginRequest) -> Result<UserResponse, AppError> {
+        // 获取系统设置
+        let max_attempts: i32 = self.setting("max_login_attempts", 5).await;
+        let lockout_duration: i64 = self.setting("lockout_duration", 300).await;
+        let reset_attempts_after: i64 = self.setting("reset_attempts_after", 3600).await;
+
+        let mut users = UserRepository::new(self.pool);
+
+        // 查找用户
+        let mut user = match users.find_by_login(&request.username).await? {
+            Some(user) => user,
+            None => {
+                println!("User not found: {}", mask_text(&request.username));
+                return Err(AppError::Invalid("用户名或密码错误".to_string()));
+            }
+        };
+
+        // 检查用户是否被锁定
+        if let Some(locked_until) = user.locked_until {
+            if Utc::now() < locked_until {
+                let remaining_seconds = (locked_until - Utc::now()).num_seconds();
+                return Err(AppError::Invalid(format!("账户已被锁定，请在 {} 分钟后重试", remaining_seconds / 60)));
+            } else {
+                // 解锁用户
+                user.locked_until = None;
+                user.failed_login_attempts = 0;
+                users.update_login_state(&user).await?;
+            }
+        }
+
+        // 检查是否需要重置登录尝试次数
+        if let Some(last_failed) = user.last_failed_login {
+            if (Utc::now() - last_failed).num_seconds() > reset_attempts_after {
+                user.failed_login_attempts = 0;
+                users.update_login_state(&user).await?;
+            }
+        }
+
+        // 验证密码
+        if !bcrypt::verify(&request.password, &user.password_hash)? {
+            // 密码错误，增加失败次数
+            user.failed_login_attempts += 1;
+            user.last_failed_login = Some(Utc::now());
+
+            // 检查是否需要锁定账户
+            if user.failed_login_attempts >= max_attempts {
+                user.locked_until = Some(Utc::now() + Duration::seconds(lockout_duration));
+                users.update_login_state(&user).await?;
+                return Err(AppError::Invalid(format!("登录失败次数过多，账户已被锁定 {} 分钟", lockout_duration / 60)));
+            } else {
+                users.update_login_state(&user).await?;
+                let remaining_attempts = max_attempts - user.failed_login_attempts;
+                return Err(AppError::Invalid(format!("用户名或密码错误，还有 {} 次尝试机会", remaining_attempts)));
+            }
+        }
+
+        // 登录成功，重置失败次数
+        user.failed_login_attempts = 0;
+        user.last_failed_login = None;
+        user.locked_until = None;
+        user.last_login = Some(Utc::now());
+        users.update_login_state(&user).await?;
+
+        let role = RoleRepository::new(self.pool).find(user.role_id).await?;
+
+        Ok(user_response(user, role))
+    }
+
+    /// 用户信息及其角色
+    pub async fn profile(&self, user_id: i64) -> Result<UserResponse, AppError> {
+        let user = UserRepository::new(self.pool).find(user_id).await?;
+        let role = RoleRepository::new(self.pool).find(user.role_id).await?;
+        Ok(user_response(user, role))
+    }
+
+    // 辅助函数：读取安全设置，缺失或无法解析时使用默认值
+    async fn setting<T: FromStr>(&self, key: &str, default: T) -> T {
+        match SettingRepository::new(self.pool).get(key).await {
+            Ok(Some(value)) => value.parse().unwrap_or(default),
+            _ => default,
+        }
+    }
+}
+
+// 辅助函数：组合用户和角色信息
+fn user_response(user: User, role: Role) -> UserResponse {
+    UserResponse {
+        id: user.id,
+        username: user.username,
+        email: user.email,
+        phone: user.phone,
+        address: user.address,
+        avatar: user.avatar,
+        role_id: user.role_id,
+        role_name: role.name,
+        permissions: role.permissions,
+        status: user.status,
+        created_at: user.created_at,
+        updated_at: user.updated_at,
+        version: user.version,
+    }
+}
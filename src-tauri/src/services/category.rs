@@ -0,0 +1,52 @@
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::category::*;
+use crate::repositories::CategoryRepository;
+use crate::services::product::validate_threshold;
+use crate::services::stock::stock_changed;
+use crate::transaction;
+
+pub struct CategoryService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> CategoryService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, tenant_id: i64, request: &CreateCategoryRequest) -> Result<Category, AppError> {
+        validate_threshold(request.reorder_threshold)?;
+        transaction!(self.pool, |tx| {
+            let mut categories = CategoryRepository::new(&mut tx);
+
+            // 检查分类名是否已存在
+            if categories.name_exists(tenant_id, &request.name).await? {
+                return Err(AppError::Conflict("分类名已存在".to_string()));
+            }
+
+            // 上级分类必须属于同一个店铺
+            if let Some(parent_id) = request.parent_id {
+                categories.find(tenant_id, parent_id).await?;
+            }
+
+            categories.create(tenant_id, request).await
+        })
+    }
+
+    /// 分类的预警值可能改变了其下商品是否需要预警
+    pub async fn update(&self, tenant_id: i64, id: i64, request: &UpdateCategoryRequest) -> Result<Category, AppError> {
+        validate_threshold(request.reorder_threshold)?;
+        let category = transaction!(self.pool, |tx| {
+            let mut categories = CategoryRepository::new(&mut tx);
+
+            if let Some(parent_id) = request.parent_id {
+                categories.find(tenant_id, parent_id).await?;
+            }
+
+            categories.update(tenant_id, id, request).await
+        })?;
+        stock_changed();
+        Ok(category)
+    }
+}
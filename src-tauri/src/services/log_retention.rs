@@ -0,0 +1,210 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use chrono::{Local, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::log::{LogRetentionSettings, LogRetentionStatus};
+use crate::repositories::{LogRepository, SettingRepository};
+use crate::transaction;
+
+const LOG_DAYS_SETTING: &str = "log_retention_days";
+const LOGIN_LOG_DAYS_SETTING: &str = "login_log_retention_days";
+const ARCHIVE_SETTING: &str = "log_archive_enabled";
+const STATUS_SETTING: &str = "log_retention_status";
+/// 自动清除的检查间隔
+const RETENTION_TICK: Duration = Duration::from_secs(60 * 60);
+/// 归档时每次读取的行数
+const ARCHIVE_BATCH: i64 = 5_000;
+
+pub struct LogRetentionService<'a> {
+    pool: &'a DbPool,
+    archive_dir: &'a Path,
+}
+
+impl<'a> LogRetentionService<'a> {
+    pub fn new(pool: &'a DbPool, archive_dir: &'a Path) -> Self {
+        Self { pool, archive_dir }
+    }
+
+    pub async fn settings(&self) -> Result<LogRetentionSettings, AppError> {
+        let mut settings = SettingRepository::new(self.pool);
+        let defaults = LogRetentionSettings::default();
+
+        Ok(LogRetentionSettings {
+            log_days: settings
+                .get(LOG_DAYS_SETTING)
+                .await?
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(defaults.log_days),
+            login_log_days: settings
+                .get(LOGIN_LOG_DAYS_SETTING)
+                .await?
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(defaults.login_log_days),
+            archive: settings
+                .get(ARCHIVE_SETTING)
+                .await?
+                .map(|value| value == "true")
+                .unwrap_or(defaults.archive),
+        })
+    }
+
+    pub async fn save_settings(&self, request: &LogRetentionSettings) -> Result<(), AppError> {
+        if request.log_days < 0 || request.login_log_days < 0 {
+            return Err(AppError::Invalid("保留天数不能为负数".to_string()));
+        }
+
+        transaction!(self.pool, |tx| {
+            let mut settings = SettingRepository::new(&mut tx);
+            settings.set(LOG_DAYS_SETTING, &request.log_days.to_string()).await?;
+            settings.set(LOGIN_LOG_DAYS_SETTING, &request.login_log_days.to_string()).await?;
+            settings.set(ARCHIVE_SETTING, &request.archive.to_string()).await?;
+            Ok(())
+        })
+    }
+
+    pub async fn status(&self) -> Result<LogRetentionStatus, AppError> {
+        let status = SettingRepository::new(self.pool).get(STATUS_SETTING).await?;
+        Ok(status
+            .filter(|value| !value.is_empty())
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or_default())
+    }
+
+    /// 按保留期清除两类日志，并记录本次的结果；失败时同样记录错误
+    pub async fn run(&self) -> Result<LogRetentionStatus, AppError> {
+        let mut status = LogRetentionStatus {
+            last_run: Some(Utc::now()),
+            ..Default::default()
+        };
+
+        let result = self.purge(&mut status).await;
+        if let Err(e) = &result {
+            status.error = Some(e.to_string());
+        }
+
+        let value = serde_json::to_string(&status)?;
+        SettingRepository::new(self.pool).set(STATUS_SETTING, &value).await?;
+
+        result.map(|_| status)
+    }
+
+    async fn purge(&self, status: &mut LogRetentionStatus) -> Result<(), AppError> {
+        let settings = self.settings().await?;
+        let now = Utc::now();
+
+        if settings.log_days > 0 {
+            let before = now - chrono::Duration::days(settings.log_days);
+            let mut logs = LogRepository::new(self.pool);
+
+            let archived_up_to = if settings.archive {
+                let mut archive = Archive::new(self.archive_dir, "logs");
+                let mut last_id = 0;
+                loop {
+                    let batch = logs.list_before(before, last_id, ARCHIVE_BATCH).await?;
+                    let Some(last) = batch.last() else { break };
+                    last_id = last.id;
+                    archive.write(&batch)?;
+                }
+                status.archive_files.extend(archive.finish()?);
+                Some(last_id)
+            } else {
+                None
+            };
+
+            status.logs_purged = logs.delete_before(before, archived_up_to).await?;
+        }
+
+        if settings.login_log_days > 0 {
+            let before = now - chrono::Duration::days(settings.login_log_days);
+            let mut logs = LogRepository::new(self.pool);
+
+            let archived_up_to = if settings.archive {
+                let mut archive = Archive::new(self.archive_dir, "login_logs");
+                let mut last_id = 0;
+                loop {
+                    let batch = logs.list_login_attempts_before(before, last_id, ARCHIVE_BATCH).await?;
+                    let Some(last) = batch.last() else { break };
+                    last_id = last.id;
+                    archive.write(&batch)?;
+                }
+                status.archive_files.extend(archive.finish()?);
+                Some(last_id)
+            } else {
+                None
+            };
+
+            status.login_logs_purged = logs.delete_login_attempts_before(before, archived_up_to).await?;
+        }
+
+        Ok(())
+    }
+}
+
+// gzip 压缩的 JSON Lines 文件，写入第一行时才创建
+struct Archive {
+    path: PathBuf,
+    encoder: Option<GzEncoder<BufWriter<File>>>,
+}
+
+impl Archive {
+    fn new(dir: &Path, table: &str) -> Self {
+        let file_name = format!("{}-{}.jsonl.gz", table, Local::now().format("%Y%m%d-%H%M%S"));
+        Self { path: dir.join(file_name), encoder: None }
+    }
+
+    fn write<T: Serialize>(&mut self, rows: &[T]) -> Result<(), AppError> {
+        let encoder = match &mut self.encoder {
+            Some(encoder) => encoder,
+            None => {
+                if let Some(dir) = self.path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                // 同名文件已存在时报错，不能覆盖之前的归档
+                let file = File::options().write(true).create_new(true).open(&self.path)?;
+                self.encoder.insert(GzEncoder::new(BufWriter::new(file), Compression::default()))
+            }
+        };
+
+        for row in rows {
+            serde_json::to_writer(&mut *encoder, row)?;
+            encoder.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    // 写完并落盘后才返回，调用方随后才删除这些行
+    fn finish(self) -> Result<Option<String>, AppError> {
+        let Some(encoder) = self.encoder else {
+            return Ok(None);
+        };
+        encoder.finish()?.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok(Some(self.path.display().to_string()))
+    }
+}
+
+/// 启动日志自动清除任务
+pub fn spawn_retention_task(pool: DbPool, archive_dir: PathBuf) {
+    tokio::spawn(async move {
+        loop {
+            // 恢复备份期间连接池会被关闭
+            if !pool.is_closed() {
+                match LogRetentionService::new(&pool, &archive_dir).run().await {
+                    Ok(status) if status.logs_purged + status.login_logs_purged > 0 => println!(
+                        "Purged {} logs and {} login logs",
+                        status.logs_purged, status.login_logs_purged
+                    ),
+                    Ok(_) => {}
+                    Err(e) => println!("Log retention failed: {}", e),
+                }
+            }
+            tokio::time::sleep(RETENTION_TICK).await;
+        }
+    });
+}
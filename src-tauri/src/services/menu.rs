@@ -0,0 +1,115 @@
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::menu::*;
+use crate::repositories::MenuRepository;
+use crate::transaction;
+use crate::utils::permissions::CompiledPermissions;
+
+pub struct MenuService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> MenuService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 菜单树。`permissions` 为 None 时返回完整树（含隐藏菜单），否则只保留可见且有权限的菜单
+    pub async fn tree(&self, permissions: Option<&CompiledPermissions>) -> Result<Vec<MenuTreeNode>, AppError> {
+        let menus = MenuRepository::new(self.pool).list().await?;
+        Ok(build_menu_tree(&menus, None, permissions))
+    }
+
+    pub async fn update(&self, id: i64, request: &UpdateMenuRequest) -> Result<Menu, AppError> {
+        transaction!(self.pool, |tx| {
+            let mut repository = MenuRepository::new(&mut tx);
+            let menus = repository.list().await?;
+
+            if creates_cycle(&menus, id, request.parent_id) {
+                return Err(AppError::Invalid("不能将菜单移动到自身或其子菜单下".to_string()));
+            }
+
+            repository.update(id, request).await
+        })
+    }
+
+    /// 批量调整层级和排序，全部成功或全部不生效
+    pub async fn sort(&self, items: &[MenuSortItem]) -> Result<(), AppError> {
+        let mut menus = MenuRepository::new(self.pool).list().await?;
+
+        // 先在内存中应用新的层级关系，再整体检查是否产生循环
+        for item in items {
+            if let Some(menu) = menus.iter_mut().find(|m| m.id == item.id) {
+                menu.parent_id = item.parent_id;
+            }
+        }
+        if items.iter().any(|item| creates_cycle(&menus, item.id, item.parent_id)) {
+            return Err(AppError::Invalid("不能将菜单移动到自身或其子菜单下".to_string()));
+        }
+
+        transaction!(self.pool, |tx| {
+            let mut repository = MenuRepository::new(&mut tx);
+            for item in items {
+                repository.update_position(item).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+// 辅助函数：检查把 id 挂到 parent_id 下是否会形成环
+fn creates_cycle(menus: &[Menu], id: i64, parent_id: Option<i64>) -> bool {
+    let mut current = parent_id;
+    let mut steps = 0;
+    while let Some(pid) = current {
+        if pid == id || steps > menus.len() {
+            return true;
+        }
+        current = menus.iter().find(|m| m.id == pid).and_then(|m| m.parent_id);
+        steps += 1;
+    }
+    false
+}
+
+// 辅助函数：构建菜单树
+// permissions 为 None 时返回完整树（含隐藏菜单），否则只保留可见且有权限的菜单
+fn build_menu_tree(
+    menus: &[Menu],
+    parent_id: Option<i64>,
+    permissions: Option<&CompiledPermissions>,
+) -> Vec<MenuTreeNode> {
+    menus
+        .iter()
+        .filter(|m| m.parent_id == parent_id)
+        .filter(|m| match permissions {
+            None => true,
+            Some(perms) => {
+                m.visible == 1
+                    && m.permission
+                        .as_deref()
+                        .is_none_or(|p| perms.allows(p))
+            }
+        })
+        .filter_map(|m| {
+            let children = build_menu_tree(menus, Some(m.id), permissions);
+
+            // 没有路由的分组菜单，如果子菜单全部被过滤掉就不再显示
+            if permissions.is_some() && m.path.is_none() && children.is_empty() {
+                return None;
+            }
+
+            Some(MenuTreeNode {
+                id: m.id,
+                parent_id: m.parent_id,
+                name: m.name.clone(),
+                title: m.title.clone(),
+                path: m.path.clone(),
+                icon: m.icon.clone(),
+                permission: m.permission.clone(),
+                sort_order: m.sort_order,
+                visible: m.visible,
+                children,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,30 @@
+//! 业务逻辑层：组合仓储完成一个完整的业务操作。
+//!
+//! 服务只依赖连接池，不依赖 Tauri 的 `State`，可以在测试、命令行工具或 HTTP 服务中复用；
+//! Tauri 命令负责参数转换、权限检查和脱敏，然后调用这里的方法。
+
+pub mod asset;
+pub mod auth;
+pub mod category;
+pub mod log_retention;
+pub mod menu;
+pub mod order;
+pub mod product;
+pub mod recycle_bin;
+pub mod stock;
+pub mod sync;
+pub mod tenant;
+pub mod user;
+
+pub use asset::AssetService;
+pub use auth::AuthService;
+pub use category::CategoryService;
+pub use log_retention::LogRetentionService;
+pub use menu::MenuService;
+pub use order::OrderService;
+pub use product::ProductService;
+pub use recycle_bin::RecycleBinService;
+pub use stock::StockService;
+pub use sync::SyncService;
+pub use tenant::TenantService;
+pub use user::UserService;
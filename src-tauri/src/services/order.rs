@@ -0,0 +1,189 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::Utc;
+
+use crate::database::{DbPool, DbTransaction};
+use crate::error::AppError;
+use crate::models::money::Money;
+use crate::models::order::*;
+use crate::models::stock::{MovementType, NewStockMovement};
+use crate::repositories::order::STATUS_CANCELLED;
+use crate::repositories::{OrderRepository, ProductRepository, SettingRepository, StockRepository, TenantRepository, VariantRepository};
+use crate::services::product::variant_label;
+use crate::services::stock::stock_changed;
+use crate::transaction;
+use crate::utils::search::SearchQuery;
+
+/// 新订单的默认状态：待处理（status 列为文本）
+const STATUS_PENDING: &str = "1";
+
+pub struct OrderService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> OrderService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 分页查询订单，同时返回总数
+    pub async fn list(&self, tenant_id: i64, search: Option<&SearchQuery>, limit: i32, offset: i32) -> Result<(Vec<OrderWithUser>, i64), AppError> {
+        let mut orders = OrderRepository::new(self.pool);
+        let items = orders.list(tenant_id, search, limit, offset).await?;
+        let total = orders.count(tenant_id, search).await?;
+        Ok((items, total))
+    }
+
+    /// 按商品或规格当前的价格写入订单明细，总金额是各明细金额的整数和，不需要舍入。
+    /// 每条明细记一笔销售出库，库存不足时整个订单不创建
+    pub async fn create(&self, tenant_id: i64, actor_id: Option<i64>, request: &CreateOrderRequest) -> Result<Order, AppError> {
+        let order_no = format!("ORD{}", Utc::now().timestamp());
+        if request.items.is_empty() {
+            return Err(AppError::Invalid("订单中没有商品".to_string()));
+        }
+
+        let order = transaction!(self.pool, |tx| {
+            if !TenantRepository::new(&mut tx).is_member(tenant_id, request.user_id).await? {
+                return Err(AppError::Invalid("下单用户不属于当前店铺".to_string()));
+            }
+            let currency = SettingRepository::new(&mut tx).money_settings(tenant_id).await?.currency;
+
+            let mut lines = Vec::with_capacity(request.items.len());
+            let mut total = Money::zero(currency);
+            for item in &request.items {
+                if item.quantity <= 0 {
+                    return Err(AppError::Invalid("商品数量必须大于 0".to_string()));
+                }
+                let product = ProductRepository::new(&mut tx).find(tenant_id, item.product_id).await?;
+                let mut variants = VariantRepository::new(&mut tx);
+
+                // 有规格的商品按规格下单：规格单独设置了价格时以它为准，名称后面加上规格
+                let (variant_id, sku, product_name, price) = match item.variant_id {
+                    Some(variant_id) => {
+                        let variant = variants.find(tenant_id, variant_id).await?;
+                        if variant.product_id != product.id {
+                            return Err(AppError::Invalid(format!("规格 {} 不属于商品“{}”", variant.sku, product.name)));
+                        }
+                        let name = format!("{}（{}）", product.name, variant_label(&product, &variant));
+                        (Some(variant.id), Some(variant.sku), name, variant.price.unwrap_or(product.price))
+                    }
+                    None => {
+                        if !variants.list(tenant_id, product.id).await?.is_empty() {
+                            return Err(AppError::Invalid(format!("商品“{}”有多个规格，请选择其中一个", product.name)));
+                        }
+                        (None, None, product.name.clone(), product.price)
+                    }
+                };
+
+                let price = Money::new(price, product.currency);
+                let line = price.checked_mul(item.quantity.into()).and_then(|line| total.checked_add(&line));
+                total = line.ok_or_else(|| {
+                    if price.currency != total.currency {
+                        AppError::Invalid(format!("商品“{}”的币种 {} 与店铺币种 {} 不一致", product.name, price.currency, total.currency))
+                    } else {
+                        AppError::Invalid("订单金额超出范围".to_string())
+                    }
+                })?;
+                lines.push(NewOrderItem {
+                    product_id: product.id,
+                    variant_id,
+                    sku,
+                    product_name,
+                    price: price.amount,
+                    quantity: item.quantity,
+                });
+            }
+
+            let mut orders = OrderRepository::new(&mut tx);
+            let order = orders.create(tenant_id, &order_no, request.user_id, &total, STATUS_PENDING).await?;
+            for line in &lines {
+                orders.create_item(order.id, line).await?;
+            }
+
+            let mut stock = StockRepository::new(&mut tx);
+            for line in &lines {
+                stock
+                    .record(tenant_id, &NewStockMovement {
+                        product_id: line.product_id,
+                        variant_id: line.variant_id,
+                        movement_type: MovementType::Sale,
+                        quantity: -line.quantity,
+                        reference: Some(order.order_no.clone()),
+                        order_id: Some(order.id),
+                        actor_id,
+                        note: None,
+                    })
+                    .await
+                    .map_err(|e| match e {
+                        AppError::Invalid(message) => AppError::Invalid(format!("商品“{}”{}", line.product_name, message)),
+                        e => e,
+                    })?;
+            }
+            Ok(order)
+        })?;
+        stock_changed();
+        Ok(order)
+    }
+
+    /// 取消订单时退回已出库的商品，从已取消改回其他状态时重新出库
+    pub async fn update_status(&self, tenant_id: i64, actor_id: Option<i64>, id: i64, status: i32, version: i64) -> Result<Order, AppError> {
+        let status = status.to_string();
+        let order = transaction!(self.pool, |tx| {
+            let mut orders = OrderRepository::new(&mut tx);
+            let was_cancelled = orders.find(tenant_id, id).await?.status == STATUS_CANCELLED;
+            let order = orders.update_status(tenant_id, id, &status, version).await?;
+            if was_cancelled != (status == STATUS_CANCELLED) {
+                settle_stock(&mut tx, tenant_id, actor_id, &order).await?;
+            }
+            Ok(order)
+        })?;
+        stock_changed();
+        Ok(order)
+    }
+}
+
+/// 让订单的出入库流水与订单状态一致：未取消的订单按明细出库，已取消的订单净出库为 0。
+/// 按差额记账，升级前创建、没有出库流水的订单取消时不会多退库存
+async fn settle_stock(tx: &mut DbTransaction, tenant_id: i64, actor_id: Option<i64>, order: &Order) -> Result<(), AppError> {
+    let mut expected: BTreeMap<(i64, Option<i64>), i64> = BTreeMap::new();
+    let mut names = BTreeMap::new();
+    for item in OrderRepository::new(&mut *tx).items(order.id).await? {
+        names.insert((item.product_id, item.variant_id), item.product_name.clone());
+        if order.status != STATUS_CANCELLED {
+            *expected.entry((item.product_id, item.variant_id)).or_default() -= i64::from(item.quantity);
+        }
+    }
+
+    let mut stock = StockRepository::new(&mut *tx);
+    let mut current: BTreeMap<(i64, Option<i64>), i64> = BTreeMap::new();
+    for (product_id, variant_id, quantity) in stock.order_totals(tenant_id, order.id).await? {
+        current.insert((product_id, variant_id), quantity);
+    }
+
+    let keys: BTreeSet<_> = expected.keys().chain(current.keys()).copied().collect();
+    for key in keys {
+        let (product_id, variant_id) = key;
+        let difference = expected.get(&key).copied().unwrap_or_default() - current.get(&key).copied().unwrap_or_default();
+        if difference == 0 {
+            continue;
+        }
+        let quantity = i32::try_from(difference).map_err(|_| AppError::Invalid("库存数量超出范围".to_string()))?;
+        stock
+            .record(tenant_id, &NewStockMovement {
+                product_id,
+                variant_id,
+                movement_type: if quantity > 0 { MovementType::Return } else { MovementType::Sale },
+                quantity,
+                reference: Some(order.order_no.clone()),
+                order_id: Some(order.id),
+                actor_id,
+                note: None,
+            })
+            .await
+            .map_err(|e| match (e, names.get(&key)) {
+                (AppError::Invalid(message), Some(name)) => AppError::Invalid(format!("商品“{}”{}", name, message)),
+                (e, _) => e,
+            })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, HashSet};
+
+use crate::database::{DbPool, DbTransaction};
+use crate::error::AppError;
+use crate::models::money::MoneySettings;
+use crate::models::product::*;
+use crate::models::stock::{MovementType, NewStockMovement};
+use crate::repositories::{CategoryRepository, ProductRepository, SettingRepository, StockRepository, VariantRepository};
+use crate::services::stock::stock_changed;
+use crate::transaction;
+
+pub struct ProductService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> ProductService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 所属分类必须是当前店铺的；价格按店铺的币种换算，规格和商品在同一个事务中写入。
+    /// 初始库存记为入库流水，`actor_id` 为操作人
+    pub async fn create(&self, tenant_id: i64, actor_id: Option<i64>, request: &CreateProductRequest) -> Result<Product, AppError> {
+        if request.stock < 0 {
+            return Err(AppError::Invalid("库存不能为负数".to_string()));
+        }
+        validate_threshold(request.reorder_threshold)?;
+
+        let product = transaction!(self.pool, |tx| {
+            if let Some(category_id) = request.category_id {
+                CategoryRepository::new(&mut tx).find(tenant_id, category_id).await?;
+            }
+            let money = SettingRepository::new(&mut tx).money_settings(tenant_id).await?;
+            let price = request.price.to_price(&money).map_err(AppError::Invalid)?;
+            let product = ProductRepository::new(&mut tx).create(tenant_id, request, &price).await?;
+
+            if request.stock > 0 {
+                StockRepository::new(&mut tx)
+                    .record(tenant_id, &initial_stock(product.id, None, request.stock, actor_id))
+                    .await?;
+            }
+            if let Some(variants) = &request.variants {
+                save_variants(&mut tx, tenant_id, actor_id, product.id, variants, &money).await?;
+            }
+            ProductRepository::new(&mut tx).find(tenant_id, product.id).await
+        })?;
+        stock_changed();
+        Ok(product)
+    }
+
+    /// 修改商品资料，不改变库存；提交规格时新增的规格按初始库存入库
+    pub async fn update(&self, tenant_id: i64, actor_id: Option<i64>, id: i64, request: &UpdateProductRequest) -> Result<Product, AppError> {
+        validate_threshold(request.reorder_threshold)?;
+        let product = transaction!(self.pool, |tx| {
+            if let Some(category_id) = request.category_id {
+                CategoryRepository::new(&mut tx).find(tenant_id, category_id).await?;
+            }
+            let money = SettingRepository::new(&mut tx).money_settings(tenant_id).await?;
+            let price = request.price.to_price(&money).map_err(AppError::Invalid)?;
+            let product = ProductRepository::new(&mut tx).update(tenant_id, id, request, &price).await?;
+
+            match &request.variants {
+                Some(variants) => {
+                    save_variants(&mut tx, tenant_id, actor_id, id, variants, &money).await?;
+                    ProductRepository::new(&mut tx).find(tenant_id, id).await
+                }
+                None => Ok(product),
+            }
+        })?;
+        stock_changed();
+        Ok(product)
+    }
+
+    /// 商品的规格项定义和未删除的规格
+    pub async fn variants(&self, tenant_id: i64, product_id: i64) -> Result<ProductVariants, AppError> {
+        let product = ProductRepository::new(self.pool).find(tenant_id, product_id).await?;
+        let variants = VariantRepository::new(self.pool).list(tenant_id, product_id).await?;
+        Ok(ProductVariants { options: variant_options(&product), variants })
+    }
+}
+
+/// 商品的规格项定义，没有规格时为空
+pub fn variant_options(product: &Product) -> Vec<VariantOption> {
+    product
+        .variant_options
+        .as_deref()
+        .and_then(|json| serde_json::from_str(json).ok())
+        .unwrap_or_default()
+}
+
+/// 规格的显示名称：按规格项定义的顺序列出取值，例如 `M / 红`
+pub fn variant_label(product: &Product, variant: &ProductVariant) -> String {
+    let values: BTreeMap<String, String> = serde_json::from_str(&variant.options).unwrap_or_default();
+    variant_options(product)
+        .iter()
+        .filter_map(|option| values.get(&option.name).cloned())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// 整体替换商品的规格：请求中带 ID 的规格原地修改，没有 ID 的新建，其余的软删除。
+/// 已有规格的库存保持不变，新规格的库存记为入库流水
+async fn save_variants(tx: &mut DbTransaction, tenant_id: i64, actor_id: Option<i64>, product_id: i64, request: &VariantsRequest, money: &MoneySettings) -> Result<(), AppError> {
+    validate_variants(request)?;
+
+    let mut rows = Vec::with_capacity(request.variants.len());
+    for (sort_order, variant) in (0..).zip(&request.variants) {
+        let price = match &variant.price {
+            Some(price) => Some(price.to_price(money).map_err(AppError::Invalid)?.amount),
+            None => None,
+        };
+        rows.push(VariantFields {
+            sku: variant.sku.clone(),
+            barcode: variant.barcode.clone(),
+            options: serde_json::to_string(&variant.options)?,
+            price,
+            image: variant.image.clone(),
+            sort_order,
+        });
+    }
+
+    let mut variants = VariantRepository::new(&mut *tx);
+    for variant in &request.variants {
+        if let Some(owner) = variants.sku_owner(tenant_id, product_id, &variant.sku).await? {
+            return Err(AppError::Conflict(format!("SKU {} 已被商品“{}”使用", variant.sku, owner)));
+        }
+        if let Some(barcode) = &variant.barcode {
+            if let Some(owner) = variants.barcode_owner(tenant_id, product_id, barcode).await? {
+                return Err(AppError::Conflict(format!("条码 {} 已被商品“{}”使用", barcode, owner)));
+            }
+        }
+    }
+
+    // 先删除不再提交的规格，新规格才能沿用它们的 SKU 和条码
+    let kept: HashSet<i64> = request.variants.iter().filter_map(|variant| variant.id).collect();
+    for existing in variants.list(tenant_id, product_id).await? {
+        if !kept.contains(&existing.id) {
+            variants.delete(tenant_id, existing.id).await?;
+        }
+    }
+
+    for (variant, row) in request.variants.iter().zip(&rows) {
+        let mut variants = VariantRepository::new(&mut *tx);
+        match variant.id {
+            Some(id) => variants.update(tenant_id, product_id, id, row).await?,
+            None => {
+                let variant_id = variants.create(tenant_id, product_id, row).await?;
+                if variant.stock > 0 {
+                    StockRepository::new(&mut *tx)
+                        .record(tenant_id, &initial_stock(product_id, Some(variant_id), variant.stock, actor_id))
+                        .await?;
+                }
+            }
+        }
+    }
+
+    let options = if request.options.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_string(&request.options)?)
+    };
+    ProductRepository::new(&mut *tx).set_variant_options(tenant_id, product_id, options.as_deref()).await
+}
+
+/// 库存预警值不能为负数，为空表示使用分类的预警值
+pub fn validate_threshold(threshold: Option<i32>) -> Result<(), AppError> {
+    match threshold {
+        Some(threshold) if threshold < 0 => Err(AppError::Invalid("库存预警值不能为负数".to_string())),
+        _ => Ok(()),
+    }
+}
+
+fn initial_stock(product_id: i64, variant_id: Option<i64>, quantity: i32, actor_id: Option<i64>) -> NewStockMovement {
+    NewStockMovement {
+        product_id,
+        variant_id,
+        movement_type: MovementType::Receipt,
+        quantity,
+        reference: None,
+        order_id: None,
+        actor_id,
+        note: Some("初始库存".to_string()),
+    }
+}
+
+// 规格项和规格是否完整、一致：每个规格为每个规格项选一个已定义的值，组合、SKU 和条码都不能重复
+fn validate_variants(request: &VariantsRequest) -> Result<(), AppError> {
+    let invalid = |message: String| Err(AppError::Invalid(message));
+
+    let mut names = HashSet::new();
+    for option in &request.options {
+        if option.name.trim().is_empty() {
+            return invalid("规格项名称不能为空".to_string());
+        }
+        if !names.insert(option.name.as_str()) {
+            return invalid(format!("规格项“{}”重复", option.name));
+        }
+        if option.values.is_empty() {
+            return invalid(format!("规格项“{}”没有可选的值", option.name));
+        }
+        let mut values = HashSet::new();
+        for value in &option.values {
+            if value.trim().is_empty() {
+                return invalid(format!("规格项“{}”的值不能为空", option.name));
+            }
+            if !values.insert(value.as_str()) {
+                return invalid(format!("规格项“{}”的值“{}”重复", option.name, value));
+            }
+        }
+    }
+
+    match (request.options.is_empty(), request.variants.is_empty()) {
+        (true, false) => return invalid("没有规格项时不能添加规格".to_string()),
+        (false, true) => return invalid("定义了规格项时至少需要一个规格".to_string()),
+        _ => {}
+    }
+
+    let mut skus = HashSet::new();
+    let mut barcodes = HashSet::new();
+    let mut combinations = HashSet::new();
+    for variant in &request.variants {
+        if variant.sku.trim().is_empty() {
+            return invalid("SKU 不能为空".to_string());
+        }
+        if !skus.insert(variant.sku.as_str()) {
+            return invalid(format!("SKU {} 重复", variant.sku));
+        }
+        if let Some(barcode) = &variant.barcode {
+            if barcode.trim().is_empty() {
+                return invalid(format!("规格 {} 的条码为空，没有条码时不要填写", variant.sku));
+            }
+            if !barcodes.insert(barcode.as_str()) {
+                return invalid(format!("条码 {} 重复", barcode));
+            }
+        }
+        if variant.stock < 0 {
+            return invalid(format!("规格 {} 的库存不能为负数", variant.sku));
+        }
+
+        if variant.options.len() != request.options.len() {
+            return invalid(format!("规格 {} 需要为每个规格项选择一个值", variant.sku));
+        }
+        for option in &request.options {
+            match variant.options.get(&option.name) {
+                Some(value) if option.values.contains(value) => {}
+                Some(value) => return invalid(format!("规格 {} 的{}“{}”不在可选的值中", variant.sku, option.name, value)),
+                None => return invalid(format!("规格 {} 没有选择{}", variant.sku, option.name)),
+            }
+        }
+        if !combinations.insert(&variant.options) {
+            return invalid(format!("规格 {} 与其他规格的组合重复", variant.sku));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+use chrono::Utc;
+
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::recycle_bin::*;
+use crate::repositories::{CategoryRepository, ProductRepository, SettingRepository, TenantRepository, UserRepository};
+use crate::transaction;
+
+/// 回收站保留天数的设置项
+const RETENTION_SETTING: &str = "recycle_bin_retention_days";
+/// 自动清除的检查间隔
+const PURGE_TICK: Duration = Duration::from_secs(60 * 60);
+
+pub struct RecycleBinService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> RecycleBinService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 列出店铺中指定类型的已删除数据，最近删除的在前
+    pub async fn list(&self, tenant_id: i64, entities: &[RecycleBinEntity]) -> Result<Vec<RecycleBinItem>, AppError> {
+        let mut items = Vec::new();
+
+        for &entity in entities {
+            let deleted = match entity {
+                RecycleBinEntity::User => UserRepository::new(self.pool).list_deleted(tenant_id).await?,
+                RecycleBinEntity::Product => ProductRepository::new(self.pool).list_deleted(tenant_id).await?,
+                RecycleBinEntity::Category => CategoryRepository::new(self.pool).list_deleted(tenant_id).await?,
+            };
+            items.extend(deleted.into_iter().map(|item| RecycleBinItem { entity: Some(entity), ..item }));
+        }
+
+        items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+        Ok(items)
+    }
+
+    pub async fn restore(&self, tenant_id: i64, entity: RecycleBinEntity, id: i64) -> Result<(), AppError> {
+        match entity {
+            RecycleBinEntity::User => transaction!(self.pool, |tx| {
+                if !TenantRepository::new(&mut tx).is_member(tenant_id, id).await? {
+                    return Err(AppError::NotFound("回收站中没有该用户".to_string()));
+                }
+                UserRepository::new(&mut tx).restore(id).await
+            }),
+            RecycleBinEntity::Product => ProductRepository::new(self.pool).restore(tenant_id, id).await,
+            RecycleBinEntity::Category => transaction!(self.pool, |tx| {
+                let mut categories = CategoryRepository::new(&mut tx);
+
+                // 删除期间可能新建了同名分类
+                let deleted = categories.list_deleted(tenant_id).await?;
+                if let Some(item) = deleted.iter().find(|item| item.id == id) {
+                    if categories.name_exists(tenant_id, &item.name).await? {
+                        return Err(AppError::Conflict("已存在同名分类，无法恢复".to_string()));
+                    }
+                }
+
+                categories.restore(tenant_id, id).await
+            }),
+        }
+    }
+
+    /// 永久删除超过保留期的数据；保留天数为 0 时不自动清除
+    pub async fn purge_expired(&self) -> Result<PurgeResult, AppError> {
+        let settings = self.settings().await?;
+        if settings.retention_days <= 0 {
+            return Ok(PurgeResult::default());
+        }
+
+        let before = Utc::now() - chrono::Duration::days(settings.retention_days);
+
+        // 先清除商品，再清除分类，使只被过期商品引用的分类也能在同一次清除
+        transaction!(self.pool, |tx| {
+            Ok(PurgeResult {
+                products: ProductRepository::new(&mut tx).purge_deleted(before).await?,
+                categories: CategoryRepository::new(&mut tx).purge_deleted(before).await?,
+                users: UserRepository::new(&mut tx).purge_deleted(before).await?,
+            })
+        })
+    }
+
+    pub async fn settings(&self) -> Result<RecycleBinSettings, AppError> {
+        let retention_days = SettingRepository::new(self.pool)
+            .get(RETENTION_SETTING)
+            .await?
+            .and_then(|value| value.parse().ok())
+            .unwrap_or_else(|| RecycleBinSettings::default().retention_days);
+
+        Ok(RecycleBinSettings { retention_days })
+    }
+
+    pub async fn save_settings(&self, settings: &RecycleBinSettings) -> Result<(), AppError> {
+        if settings.retention_days < 0 {
+            return Err(AppError::Invalid("保留天数不能为负数".to_string()));
+        }
+
+        SettingRepository::new(self.pool)
+            .set(RETENTION_SETTING, &settings.retention_days.to_string())
+            .await
+    }
+}
+
+/// 启动回收站自动清除任务
+pub fn spawn_purge_task(pool: DbPool) {
+    tokio::spawn(async move {
+        loop {
+            // 恢复备份期间连接池会被关闭
+            if !pool.is_closed() {
+                match RecycleBinService::new(&pool).purge_expired().await {
+                    Ok(result) if result.users + result.products + result.categories > 0 => println!(
+                        "Purged recycle bin: {} users, {} products, {} categories",
+                        result.users, result.products, result.categories
+                    ),
+                    Ok(_) => {}
+                    Err(e) => println!("Recycle bin purge failed: {}", e),
+                }
+            }
+            tokio::time::sleep(PURGE_TICK).await;
+        }
+    });
+}
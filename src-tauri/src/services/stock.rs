@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::stock::*;
+use crate::repositories::{ProductRepository, StockRepository, VariantRepository};
+use crate::transaction;
+
+/// 没有收到库存变化时也定期检查一次，直接修改数据库、同步等带来的变化也能发现
+const LOW_STOCK_TICK: Duration = Duration::from_secs(10 * 60);
+
+// 库存变化的信号：多次变化在检查之前只保留一个，不会排队重复检查
+static STOCK_CHANGED: Notify = Notify::const_new();
+
+/// 库存或预警值变化后调用，唤醒库存预警检查
+pub fn stock_changed() {
+    STOCK_CHANGED.notify_one();
+}
+
+pub struct StockService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> StockService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 手工登记一笔库存变化。收货和退货只能入库；销售出库由订单产生，不能在这里登记
+    pub async fn adjust(&self, tenant_id: i64, actor_id: Option<i64>, request: &AdjustStockRequest) -> Result<StockMovement, AppError> {
+        let reason = request.reason.trim();
+        if reason.is_empty() {
+            return Err(AppError::Invalid("请填写调整原因".to_string()));
+        }
+        if request.quantity == 0 {
+            return Err(AppError::Invalid("调整数量不能为 0".to_string()));
+        }
+        match request.movement_type {
+            MovementType::Sale => return Err(AppError::Invalid("销售出库由订单自动记录，不能手工登记".to_string())),
+            MovementType::Receipt | MovementType::Return if request.quantity < 0 => {
+                return Err(AppError::Invalid("收货和退货的数量必须大于 0".to_string()));
+            }
+            _ => {}
+        }
+
+        let movement = transaction!(self.pool, |tx| {
+            let product = ProductRepository::new(&mut tx).find(tenant_id, request.product_id).await?;
+            let mut variants = VariantRepository::new(&mut tx);
+            match request.variant_id {
+                Some(variant_id) => {
+                    let variant = variants.find(tenant_id, variant_id).await?;
+                    if variant.product_id != product.id {
+                        return Err(AppError::Invalid(format!("规格 {} 不属于商品“{}”", variant.sku, product.name)));
+                    }
+                }
+                None => {
+                    // 有规格时商品的库存是各规格的合计，只能调整具体的规格
+                    if !variants.list(tenant_id, product.id).await?.is_empty() {
+                        return Err(AppError::Invalid(format!("商品“{}”有多个规格，请选择要调整的规格", product.name)));
+                    }
+                }
+            }
+
+            StockRepository::new(&mut tx)
+                .record(tenant_id, &NewStockMovement {
+                    product_id: product.id,
+                    variant_id: request.variant_id,
+                    movement_type: request.movement_type,
+                    quantity: request.quantity,
+                    reference: request.reference.clone().filter(|reference| !reference.trim().is_empty()),
+                    order_id: None,
+                    actor_id,
+                    note: Some(reason.to_string()),
+                })
+                .await
+        })?;
+        stock_changed();
+        Ok(movement)
+    }
+
+    /// 分页查询商品的库存流水，同时返回总数
+    pub async fn movements(&self, tenant_id: i64, product_id: i64, variant_id: Option<i64>, limit: i32, offset: i32) -> Result<(Vec<StockMovement>, i64), AppError> {
+        ProductRepository::new(self.pool).find(tenant_id, product_id).await?;
+        let mut stock = StockRepository::new(self.pool);
+        let items = stock.list(tenant_id, product_id, variant_id, limit, offset).await?;
+        let total = stock.count(tenant_id, product_id, variant_id).await?;
+        Ok((items, total))
+    }
+
+    /// 对账：列出当前库存与流水合计不一致的商品和规格，全部一致时为空
+    pub async fn reconcile(&self, tenant_id: i64) -> Result<Vec<StockDiscrepancy>, AppError> {
+        StockRepository::new(self.pool).discrepancies(tenant_id).await
+    }
+
+    /// 当前店铺需要补货的商品，缺口最大的在前
+    pub async fn low_stock(&self, tenant_id: i64, limit: i32) -> Result<Vec<LowStockProduct>, AppError> {
+        StockRepository::new(self.pool).low_stock(Some(tenant_id), limit).await
+    }
+
+    /// 找出所有店铺中新跌破预警值的商品并记下已提醒；库存恢复的商品清除记录，之后再跌破时重新提醒
+    pub async fn check_alerts(&self) -> Result<Vec<LowStockProduct>, AppError> {
+        transaction!(self.pool, |tx| {
+            let mut stock = StockRepository::new(&mut tx);
+            stock.clear_recovered_alerts().await?;
+            let products = stock.unalerted_low_stock().await?;
+            for product in &products {
+                stock.mark_alerted(product).await?;
+            }
+            Ok(products)
+        })
+    }
+}
+
+/// 启动库存预警任务：启动时检查一次，之后在库存变化时检查，`notify` 负责把新跌破预警值的商品告诉用户
+pub fn spawn_low_stock_task<F>(pool: DbPool, notify: F)
+where
+    F: Fn(&[LowStockProduct]) + Send + 'static,
+{
+    tokio::spawn(async move {
+        loop {
+            // 恢复备份期间连接池会被关闭
+            if !pool.is_closed() {
+                match StockService::new(&pool).check_alerts().await {
+                    Ok(products) if !products.is_empty() => notify(&products),
+                    Ok(_) => {}
+                    Err(e) => println!("Low stock check failed: {}", e),
+                }
+            }
+            let _ = tokio::time::timeout(LOW_STOCK_TICK, STOCK_CHANGED.notified()).await;
+        }
+    });
+}
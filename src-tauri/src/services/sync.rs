@@ -0,0 +1,458 @@
+use std::time::Duration;
+use chrono::Utc;
+use sqlx::error::ErrorKind;
+use tokio::sync::Mutex;
+
+use crate::database::{Backend, DbPool};
+use crate::error::AppError;
+use crate::models::sync::*;
+use crate::repositories::{SettingRepository, SyncRepository};
+use crate::services::stock::stock_changed;
+use crate::transaction;
+use crate::utils::hlc::{Hlc, HlcClock};
+
+const HUB_URL_SETTING: &str = "sync_hub_url";
+const HUB_TOKEN_SETTING: &str = "sync_hub_token";
+const INTERVAL_SETTING: &str = "sync_interval_minutes";
+const NODE_ID_SETTING: &str = "sync_node_id";
+const CLOCK_SETTING: &str = "sync_clock";
+const PULLED_SEQ_SETTING: &str = "sync_pulled_seq";
+const STATUS_SETTING: &str = "sync_status";
+/// 每次推送或拉取的修改条数
+const BATCH: i64 = 500;
+/// 自动同步检查是否到期的间隔
+const SYNC_TICK: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 手动同步和定时同步不能同时进行，否则同一批修改会被推送和写入两次
+static SYNC_LOCK: Mutex<()> = Mutex::const_new(());
+
+pub struct SyncService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> SyncService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn settings(&self) -> Result<SyncSettings, AppError> {
+        self.ensure_sqlite()?;
+        let mut settings = SettingRepository::new(self.pool);
+        let defaults = SyncSettings::default();
+
+        Ok(SyncSettings {
+            hub_url: settings.get(HUB_URL_SETTING).await?.unwrap_or(defaults.hub_url),
+            token: settings.get(HUB_TOKEN_SETTING).await?.unwrap_or(defaults.token),
+            interval_minutes: settings
+                .get(INTERVAL_SETTING)
+                .await?
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(defaults.interval_minutes),
+        })
+    }
+
+    pub async fn save_settings(&self, request: &SyncSettings) -> Result<(), AppError> {
+        self.ensure_sqlite()?;
+        let hub_url = request.hub_url.trim().trim_end_matches('/');
+        if !hub_url.is_empty() && !hub_url.starts_with("http://") && !hub_url.starts_with("https://") {
+            return Err(AppError::Invalid("中心节点地址必须以 http:// 或 https:// 开头".to_string()));
+        }
+        if request.interval_minutes < 0 {
+            return Err(AppError::Invalid("同步间隔不能为负数".to_string()));
+        }
+
+        transaction!(self.pool, |tx| {
+            let mut settings = SettingRepository::new(&mut tx);
+            settings.set(HUB_URL_SETTING, hub_url).await?;
+            settings.set(HUB_TOKEN_SETTING, request.token.trim()).await?;
+            settings.set(INTERVAL_SETTING, &request.interval_minutes.to_string()).await?;
+            Ok(())
+        })
+    }
+
+    /// 整体状态和每类数据的待推送、已同步、冲突数
+    pub async fn status(&self) -> Result<SyncStatus, AppError> {
+        let settings = self.settings().await?;
+        let mut sync = SyncRepository::new(self.pool);
+        let pending = sync.pending_counts().await?;
+        let synced = sync.synced_counts().await?;
+        let conflicts = sync.conflict_stats().await?;
+
+        let count = |rows: &[(String, i64)], entity: SyncEntity| {
+            rows.iter().find(|(table, _)| table == entity.table()).map_or(0, |(_, count)| *count)
+        };
+        let entities = SyncEntity::ALL
+            .into_iter()
+            .map(|entity| {
+                let conflict = conflicts.iter().find(|(table, _, _)| table == entity.table());
+                SyncEntityStatus {
+                    entity,
+                    pending: count(&pending, entity),
+                    synced: count(&synced, entity),
+                    conflicts: conflict.map_or(0, |(_, count, _)| *count),
+                    last_conflict_at: conflict.and_then(|(_, _, at)| *at),
+                }
+            })
+            .collect();
+
+        let mut repository = SettingRepository::new(self.pool);
+        Ok(SyncStatus {
+            enabled: !settings.hub_url.is_empty(),
+            hub_url: settings.hub_url,
+            node_id: repository.get(NODE_ID_SETTING).await?.unwrap_or_default(),
+            pulled_seq: repository
+                .get(PULLED_SEQ_SETTING)
+                .await?
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0),
+            last_result: self.last_result().await?,
+            entities,
+        })
+    }
+
+    pub async fn conflicts(&self, entity: Option<SyncEntity>, limit: i64) -> Result<Vec<SyncConflict>, AppError> {
+        self.ensure_sqlite()?;
+        SyncRepository::new(self.pool).list_conflicts(entity, limit).await
+    }
+
+    /// 推送本地修改、拉取其他安装的修改，并记录本次的结果；失败时同样记录错误
+    pub async fn run(&self) -> Result<SyncRunResult, AppError> {
+        let settings = self.settings().await?;
+        if settings.hub_url.is_empty() {
+            return Err(AppError::Invalid("还没有配置同步中心节点".to_string()));
+        }
+
+        let _guard = SYNC_LOCK.lock().await;
+        let started = Utc::now();
+        let mut result = SyncRunResult {
+            last_run: Some(started),
+            last_success: self.last_result().await?.last_success,
+            ..Default::default()
+        };
+
+        let outcome = self.sync(&settings, &mut result).await;
+        if result.applied > 0 {
+            stock_changed();
+        }
+        match &outcome {
+            Ok(()) => result.last_success = Some(started),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+
+        let value = serde_json::to_string(&result)?;
+        SettingRepository::new(self.pool).set(STATUS_SETTING, &value).await?;
+
+        outcome.map(|_| result)
+    }
+
+    /// 配置了中心节点和同步间隔，并且距离上次同步已经超过间隔
+    pub async fn is_due(&self) -> Result<bool, AppError> {
+        let settings = self.settings().await?;
+        if settings.hub_url.is_empty() || settings.interval_minutes == 0 {
+            return Ok(false);
+        }
+
+        Ok(match self.last_result().await?.last_run {
+            Some(last_run) => Utc::now() - last_run >= chrono::Duration::minutes(settings.interval_minutes),
+            None => true,
+        })
+    }
+
+    async fn sync(&self, settings: &SyncSettings, result: &mut SyncRunResult) -> Result<(), AppError> {
+        let hub = HubClient::new(settings)?;
+        let node_id = self.node_id().await?;
+        let last = SettingRepository::new(self.pool).get(CLOCK_SETTING).await?;
+        let mut clock = HlcClock::new(node_id.clone(), last.as_deref().and_then(Hlc::parse).as_ref());
+
+        // 先推送再拉取：拉取时本地已经推送过的修改都有了 HLC，可以和收到的修改比较
+        self.push(&hub, &node_id, &mut clock, result).await?;
+        self.pull(&hub, &node_id, &mut clock, result).await
+    }
+
+    async fn push(&self, hub: &HubClient, node_id: &str, clock: &mut HlcClock, result: &mut SyncRunResult) -> Result<(), AppError> {
+        transaction!(self.pool, |tx| SyncRepository::new(&mut tx).assign_sync_ids().await)?;
+
+        loop {
+            let entries = SyncRepository::new(self.pool).pending(BATCH).await?;
+            if entries.is_empty() {
+                return Ok(());
+            }
+
+            // 推送成功后才保存推进后的时钟
+            let mut next_clock = clock.clone();
+            let mut changes = Vec::with_capacity(entries.len());
+            for entry in &entries {
+                let entity = SyncEntity::from_table(&entry.entity)
+                    .ok_or_else(|| AppError::Internal(format!("未知的同步数据类型: {}", entry.entity)))?;
+                changes.push(SyncChange {
+                    entity,
+                    sync_id: entry.sync_id.clone(),
+                    hlc: next_clock.send(entry.changed_at).to_string(),
+                    node_id: node_id.to_string(),
+                    data: self.row_data(entity, &entry.sync_id).await?,
+                });
+            }
+
+            hub.push(node_id, &changes).await?;
+
+            transaction!(self.pool, |tx| {
+                let mut sync = SyncRepository::new(&mut tx);
+                for (entry, change) in entries.iter().zip(&changes) {
+                    sync.remove_pushed(entry).await?;
+                    sync.set_row_hlc(change.entity, &change.sync_id, &change.hlc).await?;
+                }
+                SettingRepository::new(&mut tx).set(CLOCK_SETTING, &next_clock.current().to_string()).await?;
+                Ok(())
+            })?;
+
+            *clock = next_clock;
+            result.pushed += changes.len();
+            if (entries.len() as i64) < BATCH {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn pull(&self, hub: &HubClient, node_id: &str, clock: &mut HlcClock, result: &mut SyncRunResult) -> Result<(), AppError> {
+        loop {
+            let since = SettingRepository::new(self.pool)
+                .get(PULLED_SEQ_SETTING)
+                .await?
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0);
+            let response = hub.pull(since, node_id, BATCH).await?;
+            let now = Utc::now().timestamp_millis();
+
+            // 一批修改在同一个事务中写入，连同拉取进度一起提交；
+            // 计数和时钟先记在本批，提交成功后才合并，回滚的批次下次会重新拉取
+            let mut next_clock = clock.clone();
+            let mut batch = SyncRunResult::default();
+            transaction!(self.pool, |tx| {
+                let mut sync = SyncRepository::new(&mut tx);
+                sync.begin_apply().await?;
+
+                for change in &response.changes {
+                    batch.pulled += 1;
+
+                    let Some(remote) = Hlc::parse(&change.hlc) else {
+                        sync.record_conflict(change, None, "无效的 HLC 时间戳").await?;
+                        batch.conflicts += 1;
+                        continue;
+                    };
+                    next_clock.receive(&remote, now);
+
+                    let local = sync.row_hlc(change.entity, &change.sync_id).await?;
+                    // 本地未推送的修改会在下次推送时得到更大的 HLC，最终以它为准
+                    if sync.has_pending(change.entity, &change.sync_id).await? {
+                        sync.record_conflict(change, local.as_deref(), "本地有尚未推送的修改").await?;
+                        batch.conflicts += 1;
+                        continue;
+                    }
+                    if let Some(local_hlc) = local.as_deref().and_then(Hlc::parse) {
+                        if local_hlc > remote {
+                            sync.record_conflict(change, local.as_deref(), "本地已有更新的修改").await?;
+                            batch.conflicts += 1;
+                        }
+                        if local_hlc >= remote {
+                            continue;
+                        }
+                    }
+
+                    match apply_change(&mut sync, change).await {
+                        Ok(()) => {
+                            sync.set_row_hlc(change.entity, &change.sync_id, &change.hlc).await?;
+                            batch.applied += 1;
+                        }
+                        // 违反约束的修改记为冲突，其余错误（例如数据库忙）中止本批，下次重新拉取
+                        Err(e) if is_rejected(&e) => {
+                            sync.record_conflict(change, local.as_deref(), &e.to_string()).await?;
+                            batch.conflicts += 1;
+                        }
+                        Err(e) => return Err(e),
+                    }
+                }
+
+                sync.end_apply().await?;
+
+                let mut settings = SettingRepository::new(&mut tx);
+                settings.set(PULLED_SEQ_SETTING, &response.last_seq.to_string()).await?;
+                settings.set(CLOCK_SETTING, &next_clock.current().to_string()).await?;
+                Ok(())
+            })?;
+
+            *clock = next_clock;
+            result.pulled += batch.pulled;
+            result.applied += batch.applied;
+            result.conflicts += batch.conflicts;
+            if !response.has_more {
+                return Ok(());
+            }
+        }
+    }
+
+    // 推送时读取行的当前数据，行已经被永久删除时为 None
+    async fn row_data(&self, entity: SyncEntity, sync_id: &str) -> Result<Option<serde_json::Value>, AppError> {
+        let mut sync = SyncRepository::new(self.pool);
+        let data = match entity {
+            SyncEntity::Categories => sync.category_data(sync_id).await?.map(serde_json::to_value),
+            SyncEntity::Products => sync.product_data(sync_id).await?.map(serde_json::to_value),
+            SyncEntity::Orders => sync.order_data(sync_id).await?.map(serde_json::to_value),
+        };
+        Ok(data.transpose()?)
+    }
+
+    // 第一次同步时生成本安装的节点 ID
+    async fn node_id(&self) -> Result<String, AppError> {
+        let mut settings = SettingRepository::new(self.pool);
+        if let Some(node_id) = settings.get(NODE_ID_SETTING).await?.filter(|value| !value.is_empty()) {
+            return Ok(node_id);
+        }
+
+        let node_id = uuid::Uuid::new_v4().simple().to_string();
+        settings.set(NODE_ID_SETTING, &node_id).await?;
+        Ok(node_id)
+    }
+
+    async fn last_result(&self) -> Result<SyncRunResult, AppError> {
+        let status = SettingRepository::new(self.pool).get(STATUS_SETTING).await?;
+        Ok(status
+            .filter(|value| !value.is_empty())
+            .and_then(|value| serde_json::from_str(&value).ok())
+            .unwrap_or_default())
+    }
+
+    fn ensure_sqlite(&self) -> Result<(), AppError> {
+        match self.pool.backend() {
+            Backend::Sqlite => Ok(()),
+            Backend::Postgres => Err(AppError::Invalid(
+                "同步用于多台使用 SQLite 的桌面安装，使用 PostgreSQL 时各门店直接共享同一个数据库".to_string(),
+            )),
+        }
+    }
+}
+
+async fn apply_change(sync: &mut SyncRepository<'_>, change: &SyncChange) -> Result<(), AppError> {
+    let Some(data) = &change.data else {
+        return sync.delete_row(change.entity, &change.sync_id).await;
+    };
+
+    match change.entity {
+        SyncEntity::Categories => {
+            let data: CategorySyncData = serde_json::from_value(data.clone())?;
+            let tenant_id = sync.ensure_tenant(&data.tenant_code, &data.tenant_name).await?;
+            sync.apply_category(&change.sync_id, tenant_id, &data).await
+        }
+        SyncEntity::Products => {
+            let data: ProductSyncData = serde_json::from_value(data.clone())?;
+            let tenant_id = sync.ensure_tenant(&data.tenant_code, &data.tenant_name).await?;
+            sync.apply_product(&change.sync_id, tenant_id, &data).await
+        }
+        SyncEntity::Orders => {
+            let data: OrderSyncData = serde_json::from_value(data.clone())?;
+            let tenant_id = sync.ensure_tenant(&data.tenant_code, &data.tenant_name).await?;
+            sync.apply_order(&change.sync_id, tenant_id, &data).await
+        }
+    }
+}
+
+// 无法写入的修改：数据不合法、引用的数据不存在或违反约束
+fn is_rejected(error: &AppError) -> bool {
+    match error {
+        AppError::Database(sqlx::Error::Database(e)) => !matches!(e.kind(), ErrorKind::Other),
+        AppError::Internal(_) | AppError::Database(_) => false,
+        _ => true,
+    }
+}
+
+/// 中心节点的 HTTP 接口，见仓库中的 `sync-hub`
+struct HubClient {
+    http: reqwest::Client,
+    base_url: String,
+    token: String,
+}
+
+impl HubClient {
+    fn new(settings: &SyncSettings) -> Result<Self, AppError> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(hub_error)?;
+
+        Ok(Self {
+            http,
+            base_url: settings.hub_url.clone(),
+            token: settings.token.clone(),
+        })
+    }
+
+    async fn push(&self, node_id: &str, changes: &[SyncChange]) -> Result<usize, AppError> {
+        let response: PushResponse = self
+            .authorize(self.http.post(format!("{}/api/changes", self.base_url)))
+            .json(&PushRequest { node_id, changes })
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(hub_error)?
+            .json()
+            .await
+            .map_err(hub_error)?;
+        Ok(response.accepted)
+    }
+
+    /// 序号大于 `since` 的、其他安装推送的修改
+    async fn pull(&self, since: i64, node_id: &str, limit: i64) -> Result<PullResponse, AppError> {
+        self.authorize(self.http.get(format!("{}/api/changes", self.base_url)))
+            .query(&[("since", since.to_string()), ("limit", limit.to_string()), ("exclude_node", node_id.to_string())])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(hub_error)?
+            .json()
+            .await
+            .map_err(hub_error)
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if self.token.is_empty() {
+            request
+        } else {
+            request.bearer_auth(&self.token)
+        }
+    }
+}
+
+fn hub_error(e: reqwest::Error) -> AppError {
+    AppError::Internal(format!("无法连接同步中心节点: {}", e))
+}
+
+/// 启动自动同步任务，只在 SQLite 后端上运行
+pub fn spawn_sync_task(pool: DbPool) {
+    if pool.backend() != Backend::Sqlite {
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(SYNC_TICK).await;
+            // 恢复备份期间连接池会被关闭
+            if pool.is_closed() {
+                continue;
+            }
+
+            let service = SyncService::new(&pool);
+            match service.is_due().await {
+                Ok(true) => match service.run().await {
+                    Ok(result) if result.pushed + result.pulled > 0 => println!(
+                        "Synced: pushed {}, pulled {}, applied {}, conflicts {}",
+                        result.pushed, result.pulled, result.applied, result.conflicts
+                    ),
+                    Ok(_) => {}
+                    Err(e) => println!("Sync failed: {}", e),
+                },
+                Ok(false) => {}
+                Err(e) => println!("Sync failed: {}", e),
+            }
+        }
+    });
+}
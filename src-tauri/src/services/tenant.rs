@@ -0,0 +1,156 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use chrono::{Local, Utc};
+
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::tenant::*;
+use crate::repositories::{SettingRepository, TenantRepository, UserRepository};
+use crate::transaction;
+
+pub struct TenantService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> TenantService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 用户当前所在的店铺：优先使用上次切换到的店铺，
+    /// 该店铺已停用或用户已被移出时回退到用户所属的第一个店铺
+    pub async fn current(&self, user_id: i64) -> Result<i64, AppError> {
+        let mut tenants = TenantRepository::new(self.pool);
+        let available = tenants.list_for_user(user_id).await?;
+
+        let selected = tenants.current_tenant_id(user_id).await?;
+        if let Some(tenant) = available.iter().find(|t| Some(t.id) == selected) {
+            return Ok(tenant.id);
+        }
+
+        available
+            .first()
+            .map(|tenant| tenant.id)
+            .ok_or_else(|| AppError::Invalid("当前用户不属于任何店铺".to_string()))
+    }
+
+    /// 用户可以切换到的店铺
+    pub async fn list_for_user(&self, user_id: i64) -> Result<Vec<UserTenant>, AppError> {
+        let current = self.current(user_id).await.ok();
+        let mut tenants = TenantRepository::new(self.pool).list_for_user(user_id).await?;
+        for tenant in &mut tenants {
+            tenant.current = Some(tenant.id) == current;
+        }
+        Ok(tenants)
+    }
+
+    /// 切换当前店铺，之后的业务操作都在该店铺中进行
+    pub async fn switch(&self, user_id: i64, tenant_id: i64) -> Result<Tenant, AppError> {
+        transaction!(self.pool, |tx| {
+            let mut tenants = TenantRepository::new(&mut tx);
+
+            let tenant = tenants.find(tenant_id).await?;
+            if tenant.status != 1 {
+                return Err(AppError::Invalid("店铺已停用".to_string()));
+            }
+            if !tenants.is_member(tenant_id, user_id).await? {
+                return Err(AppError::Invalid("不是该店铺的成员".to_string()));
+            }
+
+            tenants.set_current_tenant_id(user_id, tenant_id).await?;
+            Ok(tenant)
+        })
+    }
+
+    pub async fn list(&self) -> Result<Vec<Tenant>, AppError> {
+        TenantRepository::new(self.pool).list().await
+    }
+
+    /// 新建店铺，创建者自动成为成员
+    pub async fn create(&self, request: &CreateTenantRequest, creator_id: i64) -> Result<Tenant, AppError> {
+        let code_valid = !request.code.is_empty()
+            && request.code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !code_valid {
+            return Err(AppError::Invalid("店铺编码只能包含字母、数字、- 和 _".to_string()));
+        }
+        if request.name.trim().is_empty() {
+            return Err(AppError::Invalid("店铺名称不能为空".to_string()));
+        }
+
+        transaction!(self.pool, |tx| {
+            let mut tenants = TenantRepository::new(&mut tx);
+
+            if tenants.code_exists(&request.code).await? {
+                return Err(AppError::Conflict("店铺编码已存在".to_string()));
+            }
+
+            let tenant = tenants.create(request).await?;
+            tenants.add_member(tenant.id, creator_id).await?;
+            Ok(tenant)
+        })
+    }
+
+    pub async fn update(&self, id: i64, request: &UpdateTenantRequest) -> Result<Tenant, AppError> {
+        transaction!(self.pool, |tx| TenantRepository::new(&mut tx).update(id, request).await)
+    }
+
+    pub async fn members(&self, tenant_id: i64) -> Result<Vec<TenantMember>, AppError> {
+        let mut tenants = TenantRepository::new(self.pool);
+        tenants.find(tenant_id).await?;
+        tenants.list_members(tenant_id).await
+    }
+
+    pub async fn add_member(&self, tenant_id: i64, user_id: i64) -> Result<(), AppError> {
+        transaction!(self.pool, |tx| {
+            TenantRepository::new(&mut tx).find(tenant_id).await?;
+            UserRepository::new(&mut tx).find(user_id).await?;
+            TenantRepository::new(&mut tx).add_member(tenant_id, user_id).await
+        })
+    }
+
+    pub async fn remove_member(&self, tenant_id: i64, user_id: i64) -> Result<(), AppError> {
+        TenantRepository::new(self.pool).remove_member(tenant_id, user_id).await
+    }
+
+    /// 把一个店铺的设置、成员、分类、商品和订单导出到 `dir` 下的 JSON 文件
+    pub async fn export(&self, tenant_id: i64, dir: &Path) -> Result<TenantExportInfo, AppError> {
+        // 在同一个事务中读取，导出的是同一时刻的数据
+        let export = transaction!(self.pool, |tx| {
+            let tenant = TenantRepository::new(&mut tx).find(tenant_id).await?;
+            let settings = SettingRepository::new(&mut tx).list_overrides(tenant_id).await?;
+
+            let mut tenants = TenantRepository::new(&mut tx);
+            Ok(TenantExport {
+                exported_at: Utc::now(),
+                settings,
+                members: tenants.list_members(tenant_id).await?,
+                categories: tenants.export_categories(tenant_id).await?,
+                products: tenants.export_products(tenant_id).await?,
+                product_variants: tenants.export_product_variants(tenant_id).await?,
+                stock_movements: tenants.export_stock_movements(tenant_id).await?,
+                orders: tenants.export_orders(tenant_id).await?,
+                order_items: tenants.export_order_items(tenant_id).await?,
+                tenant,
+            })
+        })?;
+
+        std::fs::create_dir_all(dir)?;
+        let file_name = format!("tenant-{}-{}.json", export.tenant.code, Local::now().format("%Y%m%d-%H%M%S"));
+        let path = dir.join(file_name);
+
+        // 同名文件已存在时报错，不覆盖之前的导出
+        let file = File::options().write(true).create_new(true).open(&path)?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, &export)?;
+        writer.flush()?;
+
+        Ok(TenantExportInfo {
+            size: std::fs::metadata(&path)?.len(),
+            path: path.display().to_string(),
+            categories: export.categories.len(),
+            products: export.products.len(),
+            orders: export.orders.len(),
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use crate::database::DbPool;
+use crate::error::AppError;
+use crate::models::user::*;
+use crate::repositories::{TenantRepository, UserRepository};
+use crate::transaction;
+use crate::utils::search::SearchQuery;
+
+pub struct UserService<'a> {
+    pool: &'a DbPool,
+}
+
+impl<'a> UserService<'a> {
+    pub fn new(pool: &'a DbPool) -> Self {
+        Self { pool }
+    }
+
+    /// 查找店铺成员，不是该店铺成员的用户视为不存在
+    pub async fn find(&self, tenant_id: i64, id: i64) -> Result<User, AppError> {
+        if !TenantRepository::new(self.pool).is_member(tenant_id, id).await? {
+            return Err(AppError::NotFound("用户不存在".to_string()));
+        }
+        UserRepository::new(self.pool).find(id).await
+    }
+
+    /// 分页查询店铺成员，同时返回符合条件的总数
+    pub async fn list(&self, tenant_id: i64, search: Option<&SearchQuery>, limit: i32, offset: i32) -> Result<(Vec<UserWithRole>, i64), AppError> {
+        let mut users = UserRepository::new(self.pool);
+        let items = users.list(tenant_id, search, limit, offset).await?;
+        let total = users.count(tenant_id, search).await?;
+        Ok((items, total))
+    }
+
+    /// 新建用户并加入 `tenant_id` 店铺
+    pub async fn create(&self, tenant_id: i64, request: &CreateUserRequest) -> Result<User, AppError> {
+        // 哈希计算较慢，放在事务之外
+        let password_hash = bcrypt::hash(&request.password, bcrypt::DEFAULT_COST)?;
+
+        transaction!(self.pool, |tx| {
+            let mut users = UserRepository::new(&mut tx);
+
+            // 检查用户名和邮箱是否已存在
+            if users.exists(&request.username, &request.email).await? {
+                return Err(AppError::Conflict("用户名或邮箱已存在".to_string()));
+            }
+
+            let user = users.create(request, &password_hash).await?;
+            TenantRepository::new(&mut tx).add_member(tenant_id, user.id).await?;
+            Ok(user)
+        })
+    }
+
+    /// 更新用户信息，`current` 为更新前的记录
+    pub async fn update(&self, current: &User, request: &UpdateUserRequest) -> Result<User, AppError> {
+        transaction!(self.pool, |tx| {
+            let mut users = UserRepository::new(&mut tx);
+
+            if current.username == "admin" {
+                // 对于admin用户，只更新基本信息，不更新敏感信息
+                users.update_profile(current.id, request).await
+            } else {
+                users.update(current.id, request).await
+            }
+        })
+    }
+
+    /// 删除用户（移入回收站），`deleted_by` 为操作人；只能删除本店铺的成员
+    pub async fn delete(&self, tenant_id: i64, id: i64, deleted_by: Option<i64>) -> Result<(), AppError> {
+        transaction!(self.pool, |tx| {
+            if !TenantRepository::new(&mut tx).is_member(tenant_id, id).await? {
+                return Err(AppError::NotFound("用户不存在".to_string()));
+            }
+
+            let mut users = UserRepository::new(&mut tx);
+            let user = users.find(id).await?;
+
+            // 禁止删除admin用户
+            if user.username == "admin" || id == 1 {
+                return Err(AppError::Invalid("管理员账户不可删除".to_string()));
+            }
+
+            users.delete(id, deleted_by).await
+        })
+    }
+}
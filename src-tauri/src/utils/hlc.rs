@@ -0,0 +1,180 @@
+use std::fmt;
+
+/// 混合逻辑时钟（HLC）时间戳：物理时间（毫秒）、同一毫秒内的逻辑计数和产生它的节点 ID。
+/// 按字段顺序比较得到一个全序，节点 ID 保证不同安装产生的时间戳不会相等；
+/// 字符串形式定长补零，按字典序比较与按字段比较的结果一致
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Hlc {
+    pub physical: i64,
+    pub logical: u32,
+    pub node: String,
+}
+
+impl Hlc {
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, '-');
+        let physical = parts.next()?.parse().ok()?;
+        let logical = parts.next()?.parse().ok()?;
+        let node = parts.next().filter(|node| !node.is_empty())?.to_string();
+        Some(Self { physical, logical, node })
+    }
+}
+
+impl fmt::Display for Hlc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:015}-{:010}-{}", self.physical, self.logical, self.node)
+    }
+}
+
+/// 本节点的时钟，保证产生的时间戳严格递增，并且大于所有已经收到的时间戳
+#[derive(Debug, Clone)]
+pub struct HlcClock {
+    physical: i64,
+    logical: u32,
+    node: String,
+}
+
+impl HlcClock {
+    /// `last` 为上次保存的时钟值，重启后从它继续，避免本地时间回拨时产生更小的时间戳
+    pub fn new(node: String, last: Option<&Hlc>) -> Self {
+        let (physical, logical) = last.map(|hlc| (hlc.physical, hlc.logical)).unwrap_or((0, 0));
+        Self { physical, logical, node }
+    }
+
+    /// 为一次本地修改生成时间戳，`now` 为修改发生时的毫秒时间
+    pub fn send(&mut self, now: i64) -> Hlc {
+        if now > self.physical {
+            self.physical = now;
+            self.logical = 0;
+        } else {
+            self.logical += 1;
+        }
+        self.current()
+    }
+
+    /// 收到其他节点的时间戳后推进本地时钟
+    pub fn receive(&mut self, remote: &Hlc, now: i64) {
+        let physical = now.max(self.physical).max(remote.physical);
+        self.logical = if physical == self.physical && physical == remote.physical {
+            self.logical.max(remote.logical) + 1
+        } else if physical == self.physical {
+            self.logical + 1
+        } else if physical == remote.physical {
+            remote.logical + 1
+        } else {
+            0
+        };
+        self.physical = physical;
+    }
+
+    pub fn current(&self) -> Hlc {
+        Hlc {
+            physical: self.physical,
+            logical: self.logical,
+            node: self.node.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hlc(physical: i64, logical: u32, node: &str) -> Hlc {
+        Hlc { physical, logical, node: node.to_string() }
+    }
+
+    #[test]
+    fn parses_its_own_text_form() {
+        let value = hlc(1_700_000_000_123, 7, "node-a");
+        assert_eq!(value.to_string(), "001700000000123-0000000007-node-a");
+        // 节点 ID 本身可以包含 `-`
+        assert_eq!(Hlc::parse(&value.to_string()), Some(value));
+        assert_eq!(Hlc::parse("1-2"), None);
+        assert_eq!(Hlc::parse("1-2-"), None);
+        assert_eq!(Hlc::parse("x-2-node"), None);
+    }
+
+    #[test]
+    fn breaks_ties_on_node_id() {
+        let a = hlc(1000, 3, "node-a");
+        let b = hlc(1000, 3, "node-b");
+        assert!(a < b);
+        assert_ne!(a, b);
+        assert!(a.to_string() < b.to_string());
+
+        // 节点 ID 只在物理时间和逻辑计数都相同时起作用
+        assert!(hlc(1000, 4, "node-a") > b);
+        assert!(hlc(1001, 0, "node-a") > hlc(1000, 9, "node-b"));
+    }
+
+    #[test]
+    fn text_order_matches_field_order() {
+        let mut values = [
+            hlc(999, 10, "b"),
+            hlc(1000, 2, "a"),
+            hlc(1000, 10, "a"),
+            hlc(1000, 2, "b"),
+            hlc(10_000, 0, "a"),
+        ];
+        let mut texts: Vec<String> = values.iter().map(Hlc::to_string).collect();
+        values.sort();
+        texts.sort();
+        assert_eq!(texts, values.iter().map(Hlc::to_string).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn send_is_strictly_increasing() {
+        let mut clock = HlcClock::new("a".to_string(), None);
+        let first = clock.send(1000);
+        let second = clock.send(1000);
+        let third = clock.send(1001);
+        assert_eq!(first, hlc(1000, 0, "a"));
+        assert_eq!(second, hlc(1000, 1, "a"));
+        assert_eq!(third, hlc(1001, 0, "a"));
+    }
+
+    #[test]
+    fn local_clock_moving_backwards_does_not_go_back() {
+        // 重启后本地时间比上次保存的时钟早
+        let last = hlc(5000, 2, "a");
+        let mut clock = HlcClock::new("a".to_string(), Some(&last));
+        let next = clock.send(4000);
+        assert!(next > last);
+        assert_eq!(next, hlc(5000, 3, "a"));
+    }
+
+    #[test]
+    fn remote_clock_ahead_is_adopted() {
+        // 对方的时钟比本地快：之后的本地修改仍然排在收到的修改之后
+        let mut clock = HlcClock::new("a".to_string(), None);
+        clock.send(1000);
+        let remote = hlc(9000, 4, "b");
+        clock.receive(&remote, 1000);
+        assert_eq!(clock.current(), hlc(9000, 5, "a"));
+
+        let local = clock.send(1001);
+        assert!(local > remote);
+        assert_eq!(local, hlc(9000, 6, "a"));
+    }
+
+    #[test]
+    fn remote_clock_behind_keeps_local_time() {
+        let mut clock = HlcClock::new("a".to_string(), None);
+        clock.send(5000);
+        clock.receive(&hlc(1000, 8, "b"), 5000);
+        assert_eq!(clock.current(), hlc(5000, 1, "a"));
+
+        // 本地物理时间已经前进，逻辑计数归零
+        clock.receive(&hlc(1000, 8, "b"), 6000);
+        assert_eq!(clock.current(), hlc(6000, 0, "a"));
+    }
+
+    #[test]
+    fn same_physical_time_takes_larger_logical_count() {
+        let mut clock = HlcClock::new("a".to_string(), None);
+        clock.send(1000);
+        clock.receive(&hlc(1000, 7, "b"), 900);
+        assert_eq!(clock.current(), hlc(1000, 8, "a"));
+    }
+}
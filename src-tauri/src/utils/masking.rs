@@ -0,0 +1,254 @@
+use crate::models::{log::Log, user::{LoginAttempt, User, UserResponse, UserWithRole}};
+use crate::utils::permissions::CompiledPermissions;
+
+/// 查看完整个人信息（邮箱、手机号、地址）所需的权限
+pub const PII_PERMISSION: &str = "user:pii";
+
+/// 个人信息脱敏策略，所有需要脱敏的响应、导出和日志都通过这里处理
+#[derive(Debug, Clone, Copy)]
+pub struct PiiPolicy {
+    can_view: bool,
+}
+
+impl PiiPolicy {
+    /// 根据用户权限生成策略
+    pub fn from_permissions(permissions: &CompiledPermissions) -> Self {
+        Self {
+            can_view: permissions.allows(PII_PERMISSION),
+        }
+    }
+
+    /// 无法确认调用者身份时使用，所有个人信息都会被脱敏
+    pub fn masked() -> Self {
+        Self { can_view: false }
+    }
+
+    pub fn apply<T: MaskPii>(&self, item: &mut T) {
+        if !self.can_view {
+            item.mask_pii();
+        }
+    }
+
+    pub fn apply_all<T: MaskPii>(&self, items: &mut [T]) {
+        if !self.can_view {
+            items.iter_mut().for_each(MaskPii::mask_pii);
+        }
+    }
+
+    /// 更新时还原被脱敏的字段：如果提交的值就是当前值的脱敏结果，说明前端没有修改，保留原值
+    pub fn restore(&self, submitted: &str, current: &str, mask: fn(&str) -> String) -> String {
+        if !self.can_view && submitted == mask(current) {
+            current.to_string()
+        } else {
+            submitted.to_string()
+        }
+    }
+
+    pub fn restore_opt(
+        &self,
+        submitted: &Option<String>,
+        current: &Option<String>,
+        mask: fn(&str) -> String,
+    ) -> Option<String> {
+        match (submitted, current) {
+            (Some(new), Some(old)) => Some(self.restore(new, old, mask)),
+            _ => submitted.clone(),
+        }
+    }
+}
+
+/// 含有个人信息的结构体实现此 trait，集中定义各字段的脱敏方式
+pub trait MaskPii {
+    fn mask_pii(&mut self);
+}
+
+impl MaskPii for User {
+    fn mask_pii(&mut self) {
+        self.email = mask_email(&self.email);
+        self.phone = self.phone.as_deref().map(mask_phone);
+        self.address = self.address.as_deref().map(mask_address);
+    }
+}
+
+impl MaskPii for UserWithRole {
+    fn mask_pii(&mut self) {
+        self.email = mask_email(&self.email);
+        self.phone = self.phone.as_deref().map(mask_phone);
+        self.address = self.address.as_deref().map(mask_address);
+    }
+}
+
+impl MaskPii for UserResponse {
+    fn mask_pii(&mut self) {
+        self.email = mask_email(&self.email);
+        self.phone = self.phone.as_deref().map(mask_phone);
+        self.address = self.address.as_deref().map(mask_address);
+    }
+}
+
+impl MaskPii for Log {
+    fn mask_pii(&mut self) {
+        self.description = mask_text(&self.description);
+    }
+}
+
+impl MaskPii for LoginAttempt {
+    fn mask_pii(&mut self) {
+        // 登录时可以使用邮箱作为用户名
+        self.username = mask_text(&self.username);
+    }
+}
+
+/// 手机号脱敏：13812345678 -> 138****5678
+pub fn mask_phone(phone: &str) -> String {
+    let chars: Vec<char> = phone.chars().collect();
+    let len = chars.len();
+    if len >= 7 {
+        let head: String = chars[..3].iter().collect();
+        let tail: String = chars[len - 4..].iter().collect();
+        format!("{}****{}", head, tail)
+    } else if len > 2 {
+        format!("{}{}{}", chars[0], "*".repeat(len - 2), chars[len - 1])
+    } else {
+        "*".repeat(len)
+    }
+}
+
+/// 邮箱脱敏：alice@example.com -> a***@example.com
+pub fn mask_email(email: &str) -> String {
+    match email.rsplit_once('@') {
+        Some((local, domain)) => {
+            let first = local.chars().next().map(String::from).unwrap_or_default();
+            format!("{}***@{}", first, domain)
+        }
+        None => mask_phone(email),
+    }
+}
+
+/// 地址脱敏：只保留前 6 个字符（通常是省市区）
+pub fn mask_address(address: &str) -> String {
+    let chars: Vec<char> = address.chars().collect();
+    if chars.len() > 6 {
+        format!("{}****", chars[..6].iter().collect::<String>())
+    } else {
+        format!("{}****", chars.first().map(|c| c.to_string()).unwrap_or_default())
+    }
+}
+
+/// 自由文本脱敏：识别其中的邮箱和手机号（用于日志描述、控制台输出等）
+pub fn mask_text(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() || "._%+-@".contains(c) {
+            word.push(c);
+        } else {
+            result.push_str(&mask_word(&word));
+            word.clear();
+            result.push(c);
+        }
+    }
+    result.push_str(&mask_word(&word));
+
+    result
+}
+
+fn mask_word(word: &str) -> String {
+    if let Some((local, domain)) = word.split_once('@') {
+        if !local.is_empty() && domain.contains('.') {
+            return mask_email(word);
+        }
+    }
+
+    let digits = word.strip_prefix('+').unwrap_or(word);
+    let is_number = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+    let is_mobile = digits.len() == 11 && digits.starts_with('1');
+    let is_international = word.starts_with('+') && (7..=15).contains(&digits.len());
+    if is_number && (is_mobile || is_international) {
+        return mask_phone(word);
+    }
+
+    word.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_phone_numbers_by_length() {
+        assert_eq!(mask_phone("13812345678"), "138****5678");
+        assert_eq!(mask_phone("+8613812345678"), "+86****5678");
+        assert_eq!(mask_phone("1234567"), "123****4567");
+        assert_eq!(mask_phone("12345"), "1***5");
+        assert_eq!(mask_phone("123"), "1*3");
+        assert_eq!(mask_phone("12"), "**");
+        assert_eq!(mask_phone("1"), "*");
+        assert_eq!(mask_phone(""), "");
+    }
+
+    #[test]
+    fn masks_email_local_part() {
+        assert_eq!(mask_email("alice@example.com"), "a***@example.com");
+        assert_eq!(mask_email("a@example.com"), "a***@example.com");
+        assert_eq!(mask_email("张三@example.cn"), "张***@example.cn");
+        assert_eq!(mask_email("@example.com"), "***@example.com");
+        // 按最后一个 @ 拆分，域名保持完整
+        assert_eq!(mask_email("a@b@example.com"), "a***@example.com");
+        // 不是邮箱时按短字符串处理，不会原样返回
+        assert_eq!(mask_email("abc"), "a*c");
+        assert_eq!(mask_email(""), "");
+    }
+
+    #[test]
+    fn keeps_only_the_start_of_an_address() {
+        assert_eq!(mask_address("北京市朝阳区建国路88号"), "北京市朝阳区****");
+        assert_eq!(mask_address("北京市朝阳区"), "北****");
+        assert_eq!(mask_address("上海"), "上****");
+        assert_eq!(mask_address(""), "****");
+    }
+
+    #[test]
+    fn masks_contacts_inside_free_text() {
+        assert_eq!(
+            mask_text("联系 alice@example.com 或 13812345678。"),
+            "联系 a***@example.com 或 138****5678。"
+        );
+        assert_eq!(mask_text("tel:+8613812345678"), "tel:+86****5678");
+        // 不像邮箱或手机号的内容保持不变
+        assert_eq!(mask_text("订单 20240101001 数量 100"), "订单 20240101001 数量 100");
+        assert_eq!(mask_text("admin@localhost"), "admin@localhost");
+        assert_eq!(mask_text("1381234567"), "1381234567");
+        assert_eq!(mask_text(""), "");
+    }
+
+    #[test]
+    fn policy_masks_unless_pii_is_granted() {
+        struct Contact(String);
+        impl MaskPii for Contact {
+            fn mask_pii(&mut self) {
+                self.0 = mask_email(&self.0);
+            }
+        }
+
+        let mut contacts = [Contact("alice@example.com".to_string())];
+        PiiPolicy::from_permissions(&CompiledPermissions::compile(r#"["user:*"]"#)).apply_all(&mut contacts);
+        assert_eq!(contacts[0].0, "alice@example.com");
+
+        PiiPolicy::from_permissions(&CompiledPermissions::compile(r#"["user:read"]"#)).apply_all(&mut contacts);
+        assert_eq!(contacts[0].0, "a***@example.com");
+    }
+
+    #[test]
+    fn restores_untouched_masked_values() {
+        let policy = PiiPolicy::masked();
+        assert_eq!(policy.restore("a***@example.com", "alice@example.com", mask_email), "alice@example.com");
+        assert_eq!(policy.restore("bob@example.com", "alice@example.com", mask_email), "bob@example.com");
+        assert_eq!(
+            policy.restore_opt(&Some("138****5678".to_string()), &Some("13812345678".to_string()), mask_phone),
+            Some("13812345678".to_string())
+        );
+        assert_eq!(policy.restore_opt(&None, &Some("13812345678".to_string()), mask_phone), None);
+    }
+}
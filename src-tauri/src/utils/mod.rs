@@ -1 +1,4 @@
-pub mod permissions; 
\ No newline at end of file
+pub mod permissions; 
+pub mod masking;
+pub mod search;
+pub mod hlc;
\ No newline at end of file
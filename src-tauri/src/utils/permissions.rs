@@ -1,62 +1,181 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, RwLock};
 
-pub struct Permission;
+/// 超级管理员（"*"）展开后拥有的全部权限
+const ALL_PERMISSIONS: &[&str] = &[
+    "dashboard:read",
+    "user:read",
+    "user:write",
+    "user:pii",
+    "product:read",
+    "product:write",
+    "order:read",
+    "order:write",
+    "category:read",
+    "category:write",
+    "settings:read",
+    "settings:write",
+    "logs:read",
+    "menu:read",
+    "menu:write",
+    "role:write",
+    "database:manage",
+    "tenant:manage",
+];
 
-impl Permission {
-    /// 检查用户是否有特定权限
-    pub fn has_permission(user_permissions: &str, required_permission: &str) -> bool {
-        println!("Checking permission: {} against {}", required_permission, user_permissions);
-        
-        // 如果用户有所有权限
-        if user_permissions.contains("*") {
-            return true;
-        }
-        
-        // 解析权限JSON
-        if let Ok(permissions) = serde_json::from_str::<Vec<String>>(user_permissions) {
-            // 检查是否有匹配的权限
-            permissions.contains(&required_permission.to_string())
-        } else {
-            false
-        }
-    }
-    
-    /// 检查用户是否有任意一个权限
-    pub fn has_any_permission(user_permissions: &str, required_permissions: &[&str]) -> bool {
-        for permission in required_permissions {
-            if Self::has_permission(user_permissions, permission) {
-                return true;
-            }
-        }
-        false
-    }
+/// 编译后的权限集合：角色权限 JSON 只解析一次，之后的检查只做哈希查找
+#[derive(Debug, Default, Clone)]
+pub struct CompiledPermissions {
+    all: bool,
+    exact: HashSet<String>,
+    prefixes: Vec<String>, // "user:*" 编译为前缀 "user:"
+}
+
+impl CompiledPermissions {
+    pub fn compile(permissions_json: &str) -> Self {
+        let mut compiled = Self::default();
 
-    /// 获取用户所有权限
-    pub fn get_permissions(permissions_json: &str) -> HashSet<String> {
-        let mut permissions = HashSet::new();
-        
         if let Ok(perms) = serde_json::from_str::<Vec<String>>(permissions_json) {
             for perm in perms {
                 if perm == "*" {
-                    // 超级管理员拥有所有权限
-                    permissions.insert("dashboard:read".to_string());
-                    permissions.insert("user:read".to_string());
-                    permissions.insert("user:write".to_string());
-                    permissions.insert("product:read".to_string());
-                    permissions.insert("product:write".to_string());
-                    permissions.insert("order:read".to_string());
-                    permissions.insert("order:write".to_string());
-                    permissions.insert("category:read".to_string());
-                    permissions.insert("category:write".to_string());
-                    permissions.insert("settings:read".to_string());
-                    permissions.insert("settings:write".to_string());
-                    permissions.insert("logs:read".to_string());
+                    compiled.all = true;
+                } else if let Some(prefix) = perm.strip_suffix('*') {
+                    compiled.prefixes.push(prefix.to_string());
                 } else {
-                    permissions.insert(perm);
+                    compiled.exact.insert(perm);
                 }
             }
         }
-        
+
+        compiled
+    }
+
+    /// 检查是否拥有特定权限
+    pub fn allows(&self, required_permission: &str) -> bool {
+        self.all
+            || self.exact.contains(required_permission)
+            || self.prefixes.iter().any(|p| required_permission.starts_with(p.as_str()))
+    }
+
+    /// 展开为具体的权限列表
+    pub fn expand(&self) -> HashSet<String> {
+        let mut permissions = self.exact.clone();
+        for perm in ALL_PERMISSIONS {
+            if self.allows(perm) {
+                permissions.insert(perm.to_string());
+            }
+        }
         permissions
     }
-} 
\ No newline at end of file
+}
+
+/// 按角色缓存编译后的权限，角色被修改后必须调用 invalidate
+#[derive(Debug, Default)]
+pub struct PermissionCache {
+    roles: RwLock<HashMap<i64, Arc<CompiledPermissions>>>,
+}
+
+impl PermissionCache {
+    pub fn get(&self, role_id: i64) -> Option<Arc<CompiledPermissions>> {
+        self.roles.read().ok()?.get(&role_id).cloned()
+    }
+
+    pub fn insert(&self, role_id: i64, permissions_json: &str) -> Arc<CompiledPermissions> {
+        let compiled = Arc::new(CompiledPermissions::compile(permissions_json));
+        if let Ok(mut roles) = self.roles.write() {
+            roles.insert(role_id, compiled.clone());
+        }
+        compiled
+    }
+
+    pub fn invalidate(&self, role_id: i64) {
+        if let Ok(mut roles) = self.roles.write() {
+            roles.remove(&role_id);
+        }
+    }
+}
+
+pub struct Permission;
+
+impl Permission {
+    /// 检查用户是否有特定权限（未缓存的一次性检查，频繁调用请使用 CompiledPermissions）
+    pub fn has_permission(user_permissions: &str, required_permission: &str) -> bool {
+        CompiledPermissions::compile(user_permissions).allows(required_permission)
+    }
+
+    /// 检查用户是否有任意一个权限
+    pub fn has_any_permission(user_permissions: &str, required_permissions: &[&str]) -> bool {
+        let compiled = CompiledPermissions::compile(user_permissions);
+        required_permissions.iter().any(|p| compiled.allows(p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wildcard_allows_everything() {
+        let permissions = CompiledPermissions::compile(r#"["*"]"#);
+        assert!(permissions.allows("user:write"));
+        assert!(permissions.allows("permission:not-in-catalogue"));
+        assert_eq!(permissions.expand().len(), ALL_PERMISSIONS.len());
+    }
+
+    #[test]
+    fn resource_wildcard_only_covers_that_resource() {
+        let permissions = CompiledPermissions::compile(r#"["user:*"]"#);
+        assert!(permissions.allows("user:read"));
+        assert!(permissions.allows("user:pii"));
+        assert!(!permissions.allows("product:read"));
+        // 前缀包含冒号，名字以 user 开头的其他资源不受影响
+        assert!(!permissions.allows("username:read"));
+        assert!(!permissions.allows("user"));
+
+        let expanded = permissions.expand();
+        assert!(expanded.contains("user:write"));
+        assert!(expanded.iter().all(|p| p.starts_with("user:")));
+    }
+
+    #[test]
+    fn exact_permissions_match_exactly() {
+        let permissions = CompiledPermissions::compile(r#"["product:read", "order:write"]"#);
+        assert!(permissions.allows("product:read"));
+        assert!(permissions.allows("order:write"));
+        assert!(!permissions.allows("product:write"));
+        assert!(!permissions.allows("product"));
+        assert!(!permissions.allows("product:read "));
+        assert!(!permissions.allows("PRODUCT:READ"));
+    }
+
+    #[test]
+    fn denies_when_nothing_is_granted() {
+        for json in ["[]", "", "not json", r#"{"user:read": true}"#, r#"[1, 2]"#] {
+            let permissions = CompiledPermissions::compile(json);
+            assert!(!permissions.allows("user:read"), "{}", json);
+            assert!(permissions.expand().is_empty(), "{}", json);
+        }
+        // "*" 只在单独作为一项时表示全部权限
+        assert!(!CompiledPermissions::compile(r#"["user:read*x"]"#).allows("user:write"));
+    }
+
+    #[test]
+    fn cache_returns_compiled_roles_until_invalidated() {
+        let cache = PermissionCache::default();
+        assert!(cache.get(1).is_none());
+
+        cache.insert(1, r#"["user:read"]"#);
+        assert!(cache.get(1).unwrap().allows("user:read"));
+
+        cache.invalidate(1);
+        assert!(cache.get(1).is_none());
+    }
+
+    #[test]
+    fn any_permission_requires_one_match() {
+        let json = r#"["order:read"]"#;
+        assert!(Permission::has_any_permission(json, &["user:read", "order:read"]));
+        assert!(!Permission::has_any_permission(json, &["user:read", "order:write"]));
+        assert!(!Permission::has_any_permission(json, &[]));
+    }
+}
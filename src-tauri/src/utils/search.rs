@@ -0,0 +1,107 @@
+use std::collections::BTreeMap;
+
+/// 列表搜索条件：按空白拆分的多个关键词，每个关键词都要命中（AND）
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    terms: Vec<String>,
+}
+
+impl SearchQuery {
+    /// 解析搜索框输入，没有有效关键词时返回 None
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut terms: Vec<String> = Vec::new();
+        for term in input.split_whitespace() {
+            if !terms.iter().any(|t| t.eq_ignore_ascii_case(term)) {
+                terms.push(term.to_string());
+            }
+        }
+
+        if terms.is_empty() {
+            None
+        } else {
+            Some(Self { terms })
+        }
+    }
+
+    pub fn terms(&self) -> &[String] {
+        &self.terms
+    }
+
+    /// 对命中关键词的字段生成高亮片段，字段名作为键；没有字段命中时返回 None
+    pub fn highlight_fields(&self, fields: &[(&str, Option<&str>)]) -> Option<BTreeMap<String, String>> {
+        let highlights: BTreeMap<String, String> = fields
+            .iter()
+            .filter_map(|(name, text)| Some((name.to_string(), self.highlight(text.as_ref()?)?)))
+            .collect();
+
+        if highlights.is_empty() {
+            None
+        } else {
+            Some(highlights)
+        }
+    }
+
+    /// 转义 HTML 后用 `<mark>` 包裹命中的部分，英文字母不区分大小写；没有命中时返回 None
+    pub fn highlight(&self, text: &str) -> Option<String> {
+        let ranges = self.match_ranges(text);
+        if ranges.is_empty() {
+            return None;
+        }
+
+        let mut html = String::with_capacity(text.len() + ranges.len() * 13);
+        let mut last = 0;
+        for (start, end) in ranges {
+            escape_html(&text[last..start], &mut html);
+            html.push_str("<mark>");
+            escape_html(&text[start..end], &mut html);
+            html.push_str("</mark>");
+            last = end;
+        }
+        escape_html(&text[last..], &mut html);
+
+        Some(html)
+    }
+
+    // 所有关键词的命中位置，重叠或相邻的区间合并为一个
+    fn match_ranges(&self, text: &str) -> Vec<(usize, usize)> {
+        let haystack = text.as_bytes();
+        let mut ranges = Vec::new();
+
+        for term in &self.terms {
+            let needle = term.as_bytes();
+            if needle.len() > haystack.len() {
+                continue;
+            }
+            for start in 0..=haystack.len() - needle.len() {
+                let end = start + needle.len();
+                // 关键词本身是合法 UTF-8，从字符边界开始命中时结束位置也一定在字符边界上
+                if text.is_char_boundary(start) && haystack[start..end].eq_ignore_ascii_case(needle) {
+                    ranges.push((start, end));
+                }
+            }
+        }
+
+        ranges.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
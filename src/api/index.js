@@ -10,8 +10,37 @@ export const api = {
     return await invoke('logout')
   },
   
-  async getCurrentUser(userId) {
-    return await invoke('get_current_user', { userId })
+  async getCurrentUser() {
+    const token = localStorage.getItem('token')
+    return await invoke('get_current_user', { token })
+  },
+
+  // 菜单（按当前用户权限过滤）
+  async getMenuTree() {
+    const token = localStorage.getItem('token')
+    return await invoke('get_menu_tree', { token })
+  },
+
+  async getAllMenus() {
+    const token = localStorage.getItem('token')
+    return await invoke('get_all_menus', { token })
+  },
+
+  async updateMenu(id, menuData) {
+    const token = localStorage.getItem('token')
+    return await invoke('update_menu', { 
+      token,
+      id, 
+      request: menuData 
+    })
+  },
+
+  async sortMenus(items) {
+    const token = localStorage.getItem('token')
+    return await invoke('sort_menus', { 
+      token,
+      items 
+    })
   },
 
   // 用户管理（需要token）
@@ -49,6 +78,7 @@ export const api = {
   },
 
   // 商品管理
+  // params.filter 为筛选和排序条件（ProductQuery），价格使用最小货币单位
   async getProducts(params = {}) {
     const token = localStorage.getItem('token')
     return await invoke('get_products', { 
@@ -65,15 +95,25 @@ export const api = {
     })
   },
   
+  // 更新类请求都需要带上读取时的 version，版本不一致时返回 current（服务端的最新数据）
   async updateProduct(id, productData) {
     const token = localStorage.getItem('token')
     return await invoke('update_product', { 
       token,
-      productId: id, 
+      id, 
       request: productData 
     })
   },
   
+  // 规格项定义和全部规格，修改规格随 updateProduct 一起提交
+  async getProductVariants(productId) {
+    const token = localStorage.getItem('token')
+    return await invoke('get_product_variants', {
+      token,
+      productId
+    })
+  },
+
   async deleteProduct(id) {
     const token = localStorage.getItem('token')
     return await invoke('delete_product', { 
@@ -82,6 +122,42 @@ export const api = {
     })
   },
 
+  // 商品图片：导入本地文件，返回保存到商品中的引用
+  async importProductImage(path) {
+    const token = localStorage.getItem('token')
+    return await invoke('import_product_image', { token, path })
+  },
+
+  // 库存：手工调整必须填写原因，库存只能通过流水变化
+  async adjustStock(request) {
+    const token = localStorage.getItem('token')
+    return await invoke('adjust_stock', {
+      token,
+      request
+    })
+  },
+
+  async getStockMovements(productId, params = {}) {
+    const token = localStorage.getItem('token')
+    return await invoke('get_stock_movements', {
+      token,
+      productId,
+      variantId: params.variant_id || null,
+      page: params.page || 1,
+      perPage: params.per_page || 10
+    })
+  },
+
+  async checkStockReconciliation() {
+    const token = localStorage.getItem('token')
+    return await invoke('check_stock_reconciliation', { token })
+  },
+
+  async getLowStockProducts(limit = 10) {
+    const token = localStorage.getItem('token')
+    return await invoke('get_low_stock_products', { token, limit })
+  },
+
   // 订单管理
   async getOrders(params = {}) {
     const token = localStorage.getItem('token')
@@ -99,12 +175,13 @@ export const api = {
     })
   },
   
-  async updateOrderStatus(id, status) {
+  async updateOrderStatus(id, status, version) {
     const token = localStorage.getItem('token')
     return await invoke('update_order_status', { 
       token,
-      orderId: id, 
-      status 
+      id, 
+      status,
+      version
     })
   },
 
@@ -126,7 +203,7 @@ export const api = {
     const token = localStorage.getItem('token')
     return await invoke('update_category', { 
       token,
-      categoryId: id, 
+      id, 
       request: categoryData 
     })
   },
@@ -139,6 +216,53 @@ export const api = {
     })
   },
 
+  // 店铺：业务数据都属于当前店铺，切换后服务端记住用户的选择
+  async getMyTenants() {
+    const token = localStorage.getItem('token')
+    return await invoke('get_my_tenants', { token })
+  },
+
+  async switchTenant(tenantId) {
+    const token = localStorage.getItem('token')
+    return await invoke('switch_tenant', { token, tenantId })
+  },
+
+  async getTenants() {
+    const token = localStorage.getItem('token')
+    return await invoke('get_tenants', { token })
+  },
+
+  async createTenant(tenantData) {
+    const token = localStorage.getItem('token')
+    return await invoke('create_tenant', { token, request: tenantData })
+  },
+
+  async updateTenant(id, tenantData) {
+    const token = localStorage.getItem('token')
+    return await invoke('update_tenant', { token, id, request: tenantData })
+  },
+
+  async getTenantMembers(tenantId) {
+    const token = localStorage.getItem('token')
+    return await invoke('get_tenant_members', { token, tenantId })
+  },
+
+  async addTenantMember(tenantId, userId) {
+    const token = localStorage.getItem('token')
+    return await invoke('add_tenant_member', { token, tenantId, userId })
+  },
+
+  async removeTenantMember(tenantId, userId) {
+    const token = localStorage.getItem('token')
+    return await invoke('remove_tenant_member', { token, tenantId, userId })
+  },
+
+  // 不指定店铺时导出当前店铺
+  async exportTenant(tenantId = null) {
+    const token = localStorage.getItem('token')
+    return await invoke('export_tenant', { token, tenantId })
+  },
+
   // 角色管理
   async getRoles() {
     const token = localStorage.getItem('token')
@@ -182,6 +306,14 @@ export const api = {
     return await invoke('save_security_settings', { settings })
   },
 
+  async saveMoneySettings(settings) {
+    const token = localStorage.getItem('token')
+    return await invoke('save_money_settings', { 
+      token,
+      settings 
+    })
+  },
+
   async updateSetting(request) {
     return await invoke('update_setting', { request })
   },
@@ -215,6 +347,7 @@ export const loginLogsApi = {
   // 获取登录日志列表
   getLoginLogs: async (params = {}) => {
     const { page = 1, per_page = 10 } = params
-    return await invoke('get_login_logs', { page, per_page })
+    const token = localStorage.getItem('token')
+    return await invoke('get_login_logs', { token, page, per_page })
   }
 } 
\ No newline at end of file
@@ -44,7 +44,12 @@ const zhCN = {
     description: '描述',
     name: '名称',
     total: '总计',
-    required: '是必填项'
+    required: '是必填项',
+    conflict_title: '数据已被其他人修改',
+    conflict_mine: '我的修改',
+    conflict_latest: '最新数据',
+    conflict_use_latest: '使用最新数据',
+    conflict_keep_mine: '保留我的修改'
   },
   menu: {
     dashboard: '仪表盘',
@@ -64,7 +69,11 @@ const zhCN = {
     total_revenue: '总收入',
     recent_orders: '最近订单',
     user_statistics: '用户统计',
-    sales_trend: '销售趋势'
+    sales_trend: '销售趋势',
+    low_stock: '库存预警',
+    no_low_stock: '暂无需要补货的商品',
+    reorder_threshold: '预警值',
+    manage_products: '去补货'
   },
   role: {
     super_admin: '超级管理员',
@@ -101,6 +110,8 @@ const zhCN = {
     category: '分类',
     images: '图片',
     no_image: '无图片',
+    add_image: '添加图片',
+    image_hint: '支持 JPEG、PNG、WebP 和 GIF，导入时自动去掉拍摄信息并生成缩略图',
     create_product: '新增商品',
     edit_product: '编辑商品',
     delete_product: '删除商品',
@@ -109,7 +120,47 @@ const zhCN = {
     product_updated: '商品更新成功',
     product_deleted: '商品删除成功',
     on_sale: '上架',
-    off_sale: '下架'
+    off_sale: '下架',
+    variants: '规格',
+    variant_count: '{count} 个规格',
+    option_name: '规格项',
+    option_values: '可选值',
+    add_option: '添加规格项',
+    add_variant: '添加规格',
+    sku: 'SKU',
+    barcode: '条码',
+    variant_price_placeholder: '留空使用商品价格',
+    variant_image: '图片地址',
+    variant_stock_hint: '有规格时，商品库存为各规格库存之和',
+    initial_stock_hint: '只在新建时填写，之后通过调整库存修改',
+    adjust_stock: '调整库存',
+    reconcile: '库存对账',
+    reconcile_ok: '所有商品的库存与流水一致',
+    reconcile_mismatch: '以下商品的库存与流水合计不一致',
+    ledger_total: '流水合计',
+    movement_type: '类型',
+    movement_types: {
+      sale: '销售出库',
+      return: '退货入库',
+      adjustment: '盘点调整',
+      receipt: '采购入库',
+      transfer: '调拨'
+    },
+    quantity: '数量',
+    quantity_hint: '入库填正数，出库填负数',
+    reference: '单据号',
+    reason: '原因',
+    stock_after: '变化后库存',
+    operator: '操作人',
+    stock_movements: '库存流水',
+    stock_adjusted: '库存已调整',
+    reorder_threshold: '库存预警值',
+    reorder_threshold_hint: '库存不高于此值时提醒补货，留空使用分类的预警值',
+    include_subcategories: '包含子分类',
+    min_price: '最低价',
+    max_price: '最高价',
+    min_stock: '最少库存',
+    max_stock: '最多库存'
   },
   order: {
     title: '订单管理',
@@ -131,6 +182,8 @@ const zhCN = {
     title: '分类管理',
     parent_category: '父级分类',
     sort_order: '排序',
+    reorder_threshold: '库存预警值',
+    reorder_threshold_hint: '分类下没有设置预警值的商品使用此值，库存不高于预警值时提醒补货',
     create_category: '新增分类',
     edit_category: '编辑分类',
     delete_category: '删除分类',
@@ -139,6 +192,10 @@ const zhCN = {
     category_updated: '分类更新成功',
     category_deleted: '分类删除成功'
   },
+  tenant: {
+    current: '当前店铺',
+    switched: '已切换到店铺“{name}”'
+  },
   settings: {
     title: '系统设置',
     basic: '基本设置',
@@ -155,6 +212,14 @@ const zhCN = {
     session_timeout: '会话超时(秒)',
     max_login_attempts: '最大登录尝试',
     maintenance_mode: '维护模式',
+    money: '金额设置',
+    currency: '币种',
+    currency_hint: '只影响之后新建的商品和订单',
+    rounding: '舍入方式',
+    rounding_half_up: '四舍五入',
+    rounding_half_even: '银行家舍入（恰好一半取偶数）',
+    rounding_down: '直接舍去',
+    rounding_up: '有余数就进位',
     settings_saved: '设置保存成功'
   }
 }
@@ -203,7 +268,12 @@ const enUS = {
     description: 'Description',
     name: 'Name',
     total: 'Total',
-    required: 'is required'
+    required: 'is required',
+    conflict_title: 'Modified by someone else',
+    conflict_mine: 'My changes',
+    conflict_latest: 'Latest',
+    conflict_use_latest: 'Use latest data',
+    conflict_keep_mine: 'Keep my changes'
   },
   menu: {
     dashboard: 'Dashboard',
@@ -223,7 +293,11 @@ const enUS = {
     total_revenue: 'Total Revenue',
     recent_orders: 'Recent Orders',
     user_statistics: 'User Statistics',
-    sales_trend: 'Sales Trend'
+    sales_trend: 'Sales Trend',
+    low_stock: 'Low Stock',
+    no_low_stock: 'No products need restocking',
+    reorder_threshold: 'Threshold',
+    manage_products: 'Restock'
   },
   role: {
     super_admin: 'Super Admin',
@@ -260,6 +334,8 @@ const enUS = {
     category: 'Category',
     images: 'Images',
     no_image: 'No Image',
+    add_image: 'Add Image',
+    image_hint: 'JPEG, PNG, WebP and GIF are supported; EXIF data is removed and thumbnails are generated on import',
     create_product: 'Create Product',
     edit_product: 'Edit Product',
     delete_product: 'Delete Product',
@@ -268,7 +344,47 @@ const enUS = {
     product_updated: 'Product updated successfully',
     product_deleted: 'Product deleted successfully',
     on_sale: 'On Sale',
-    off_sale: 'Off Sale'
+    off_sale: 'Off Sale',
+    variants: 'Variants',
+    variant_count: '{count} variants',
+    option_name: 'Option',
+    option_values: 'Values',
+    add_option: 'Add Option',
+    add_variant: 'Add Variant',
+    sku: 'SKU',
+    barcode: 'Barcode',
+    variant_price_placeholder: 'Leave empty to use the product price',
+    variant_image: 'Image URL',
+    variant_stock_hint: 'For products with variants, stock is the sum of all variants',
+    initial_stock_hint: 'Only set when creating; use stock adjustments afterwards',
+    adjust_stock: 'Adjust Stock',
+    reconcile: 'Reconcile Stock',
+    reconcile_ok: 'Stock matches the movement ledger for all products',
+    reconcile_mismatch: 'Stock does not match the movement ledger for these products',
+    ledger_total: 'Ledger Total',
+    movement_type: 'Type',
+    movement_types: {
+      sale: 'Sale',
+      return: 'Return',
+      adjustment: 'Adjustment',
+      receipt: 'Receipt',
+      transfer: 'Transfer'
+    },
+    quantity: 'Quantity',
+    quantity_hint: 'Positive for stock in, negative for stock out',
+    reference: 'Reference',
+    reason: 'Reason',
+    stock_after: 'Stock After',
+    operator: 'Operator',
+    stock_movements: 'Stock Movements',
+    stock_adjusted: 'Stock adjusted',
+    reorder_threshold: 'Reorder Threshold',
+    reorder_threshold_hint: 'Remind to restock when stock drops to this value; leave empty to use the category threshold',
+    include_subcategories: 'Include subcategories',
+    min_price: 'Min price',
+    max_price: 'Max price',
+    min_stock: 'Min stock',
+    max_stock: 'Max stock'
   },
   order: {
     title: 'Order Management',
@@ -290,6 +406,8 @@ const enUS = {
     title: 'Category Management',
     parent_category: 'Parent Category',
     sort_order: 'Sort Order',
+    reorder_threshold: 'Reorder Threshold',
+    reorder_threshold_hint: 'Used by products in this category without their own threshold; you are reminded to restock when stock drops to it',
     create_category: 'Create Category',
     edit_category: 'Edit Category',
     delete_category: 'Delete Category',
@@ -298,6 +416,10 @@ const enUS = {
     category_updated: 'Category updated successfully',
     category_deleted: 'Category deleted successfully'
   },
+  tenant: {
+    current: 'Current Store',
+    switched: 'Switched to store "{name}"'
+  },
   settings: {
     title: 'System Settings',
     basic: 'Basic Settings',
@@ -314,6 +436,14 @@ const enUS = {
     session_timeout: 'Session Timeout (seconds)',
     max_login_attempts: 'Max Login Attempts',
     maintenance_mode: 'Maintenance Mode',
+    money: 'Money Settings',
+    currency: 'Currency',
+    currency_hint: 'Only applies to products and orders created afterwards',
+    rounding: 'Rounding',
+    rounding_half_up: 'Half up',
+    rounding_half_even: 'Half even (banker\'s rounding)',
+    rounding_down: 'Round down',
+    rounding_up: 'Round up',
     settings_saved: 'Settings saved successfully'
   }
 }
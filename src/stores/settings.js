@@ -20,6 +20,10 @@ export const useSettingsStore = defineStore('settings', () => {
       session_timeout: 3600,
       max_login_attempts: 5,
       maintenance_mode: false
+    },
+    money: {
+      currency: 'CNY',
+      rounding: 'half_up'
     }
   })
 
@@ -168,6 +172,23 @@ export const useSettingsStore = defineStore('settings', () => {
     }
   }
 
+  // 保存金额设置，需要修改设置的权限，失败时显示后端返回的原因
+  const saveMoneySettings = async (moneySettings) => {
+    try {
+      const response = await api.saveMoneySettings(moneySettings)
+      if (response.success) {
+        settings.value.money = { ...moneySettings }
+        message.success('金额设置保存成功')
+        return true
+      }
+      message.error(response.message || '保存失败')
+      return false
+    } catch (error) {
+      message.error('保存失败')
+      return false
+    }
+  }
+
   return {
     settings,
     loading,
@@ -175,7 +196,8 @@ export const useSettingsStore = defineStore('settings', () => {
     applyUISettings,
     saveSystemSettings,
     saveUISettings,
-    saveSecuritySettings
+    saveSecuritySettings,
+    saveMoneySettings
   }
 })
 
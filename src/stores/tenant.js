@@ -0,0 +1,65 @@
+import { defineStore } from 'pinia'
+import { ref, computed } from 'vue'
+import { api } from '@/api'
+import { message } from 'ant-design-vue'
+
+// 当前店铺：服务端按用户记住最近一次切换到的店铺，这里在本机再记一份，
+// 登录后如果服务端的选择不同（例如在其他设备上切换过），以本机的选择为准
+export const useTenantStore = defineStore('tenant', () => {
+  const tenants = ref([])
+  const currentTenantId = ref(Number(localStorage.getItem('tenant_id')) || null)
+
+  const currentTenant = computed(() => tenants.value.find(tenant => tenant.id === currentTenantId.value) || null)
+
+  const remember = (tenantId) => {
+    currentTenantId.value = tenantId
+    localStorage.setItem('tenant_id', String(tenantId))
+  }
+
+  const loadTenants = async () => {
+    try {
+      const response = await api.getMyTenants()
+      if (!response.success) {
+        console.error('Failed to load tenants:', response.message)
+        return
+      }
+      tenants.value = response.data
+
+      const saved = tenants.value.find(tenant => tenant.id === currentTenantId.value)
+      const current = tenants.value.find(tenant => tenant.current)
+      if (saved && !saved.current) {
+        await switchTenant(saved.id)
+      } else if (current) {
+        remember(current.id)
+      }
+    } catch (error) {
+      console.error('Failed to load tenants:', error)
+    }
+  }
+
+  const switchTenant = async (tenantId) => {
+    const response = await api.switchTenant(tenantId)
+    if (!response.success) {
+      message.error(response.message)
+      return false
+    }
+    remember(tenantId)
+    tenants.value = tenants.value.map(tenant => ({ ...tenant, current: tenant.id === tenantId }))
+    return true
+  }
+
+  const clear = () => {
+    tenants.value = []
+    currentTenantId.value = null
+    localStorage.removeItem('tenant_id')
+  }
+
+  return {
+    tenants,
+    currentTenantId,
+    currentTenant,
+    loadTenants,
+    switchTenant,
+    clear
+  }
+})
@@ -0,0 +1,46 @@
+// 保存时的版本冲突：后端返回失败，并在 current 中带回服务端的最新数据
+import { h } from 'vue'
+import { Modal } from 'ant-design-vue'
+
+export function isConflict(response) {
+  return !!response && !response.success && response.current != null
+}
+
+// 用最新数据覆盖表单中已有的字段，表单之外的字段（例如密码哈希）不带入
+export function applyLatest(form, current) {
+  Object.keys(form).forEach((key) => {
+    if (key in current) {
+      form[key] = current[key]
+    }
+  })
+}
+
+// 对比提交的值和服务端的最新值，由用户决定采用哪一份。
+// rows 为 [字段名, 我的值, 最新值]；onUseLatest 用最新数据重新编辑，
+// onKeepMine 保留自己的修改并换成最新的版本号（省略时只能采用最新数据）
+export function showConflict(response, { t, rows, onUseLatest, onKeepMine }) {
+  const current = response.current
+  const cell = (value) => h('td', { style: 'padding: 4px 8px;' }, value == null || value === '' ? '-' : String(value))
+  const content = h('div', [
+    h('p', response.message),
+    h('table', { style: 'width: 100%; border-collapse: collapse;' }, [
+      h('tr', [cell(''), cell(t('common.conflict_mine')), cell(t('common.conflict_latest'))]),
+      ...rows(current).map(([label, mine, latest]) =>
+        h('tr', { style: String(mine) === String(latest) ? '' : 'color: #cf1322;' }, [cell(label), cell(mine), cell(latest)])
+      )
+    ])
+  ])
+
+  const options = {
+    title: t('common.conflict_title'),
+    content,
+    width: 560,
+    okText: t('common.conflict_use_latest'),
+    onOk: () => onUseLatest(current)
+  }
+  if (onKeepMine) {
+    Modal.confirm({ ...options, cancelText: t('common.conflict_keep_mine'), onCancel: () => onKeepMine(current) })
+  } else {
+    Modal.info(options)
+  }
+}
@@ -0,0 +1,35 @@
+// 商品图片的引用与显示地址的换算。导入的图片保存为 media:<哈希>.<扩展名>，
+// 通过后端注册的 media 协议读取；其他字符串（例如网络地址）原样使用
+
+const PREFIX = 'media:'
+
+// 与后端 services/asset.rs 中的缩略图尺寸一致
+export const THUMBNAIL_SIZES = [128, 256, 512]
+
+// Windows 上的 WebView2 只能通过 https://<协议>.localhost 访问自定义协议
+function protocolBase() {
+  return navigator.userAgent.includes('Windows') ? 'https://media.localhost/' : 'media://localhost/'
+}
+
+// 图片的显示地址，指定 size 时返回不小于该尺寸的缩略图
+export function imageUrl(reference, size) {
+  if (!reference || !reference.startsWith(PREFIX)) {
+    return reference
+  }
+  const name = reference.slice(PREFIX.length)
+  const thumbnail = size && THUMBNAIL_SIZES.find(s => s >= size)
+  return protocolBase() + (thumbnail ? `${thumbnail}/${name}` : name)
+}
+
+// 商品的 images 在后端以 JSON 字符串保存，列表和详情中返回的也是字符串
+export function parseImages(images) {
+  if (Array.isArray(images)) {
+    return images
+  }
+  try {
+    const parsed = JSON.parse(images || '[]')
+    return Array.isArray(parsed) ? parsed : []
+  } catch {
+    return []
+  }
+}
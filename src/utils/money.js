@@ -0,0 +1,39 @@
+// 后端的金额是最小货币单位（分）的整数，显示和编辑时按币种的小数位数换算
+
+// 与后端 models/money.rs 中支持的币种一致
+const currencyDigits = {
+  CNY: 2,
+  HKD: 2,
+  TWD: 2,
+  USD: 2,
+  EUR: 2,
+  GBP: 2,
+  SGD: 2,
+  JPY: 0,
+  KRW: 0
+}
+
+function digitsOf(currency) {
+  return currencyDigits[currency] ?? 2
+}
+
+// 最小单位换算为元，用于表单中的数字输入框
+export function toMajor(amount, currency = 'CNY') {
+  return amount / 10 ** digitsOf(currency)
+}
+
+// 元换算为最小单位，用于按价格筛选等需要整数金额的参数
+export function toMinor(amount, currency = 'CNY') {
+  return Math.round(amount * 10 ** digitsOf(currency))
+}
+
+// 带币种符号的金额文本，例如 ¥12.30
+export function formatMoney(amount, currency = 'CNY') {
+  const digits = digitsOf(currency)
+  return new Intl.NumberFormat(undefined, {
+    style: 'currency',
+    currency,
+    minimumFractionDigits: digits,
+    maximumFractionDigits: digits
+  }).format(toMajor(amount ?? 0, currency))
+}
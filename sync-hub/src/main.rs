@@ -0,0 +1,140 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use anyhow::Context;
+use axum::extract::{Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+mod store;
+
+use store::{ChangeStore, StoredChange};
+
+/// 单次拉取最多返回的修改条数
+const MAX_PULL_LIMIT: i64 = 1000;
+
+#[derive(Clone)]
+struct HubState {
+    store: ChangeStore,
+    /// 配置后所有请求都要带上 `Authorization: Bearer <token>`
+    token: Option<Arc<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PushRequest {
+    node_id: String,
+    changes: Vec<StoredChange>,
+}
+
+#[derive(Debug, Serialize)]
+struct PushResponse {
+    accepted: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullQuery {
+    #[serde(default)]
+    since: i64,
+    limit: Option<i64>,
+    /// 请求方自己的节点 ID，不返回它推送的修改
+    exclude_node: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PullResponse {
+    changes: Vec<StoredChange>,
+    last_seq: i64,
+    has_more: bool,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let addr: SocketAddr = std::env::var("SYNC_HUB_ADDR")
+        .unwrap_or_else(|_| "127.0.0.1:7878".to_string())
+        .parse()
+        .context("SYNC_HUB_ADDR 不是合法的地址")?;
+    let db_path = std::env::var("SYNC_HUB_DB").unwrap_or_else(|_| "sync-hub.db".to_string());
+    let token = std::env::var("SYNC_HUB_TOKEN").ok().filter(|token| !token.is_empty());
+
+    let state = HubState {
+        store: ChangeStore::open(&db_path).await?,
+        token: token.map(Arc::from),
+    };
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("Sync hub listening on {} (database: {})", listener.local_addr()?, db_path);
+    axum::serve(listener, app(state)).await?;
+    Ok(())
+}
+
+fn app(state: HubState) -> Router {
+    Router::new()
+        .route("/api/changes", get(pull).post(push))
+        .route_layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .route("/api/health", get(|| async { "ok" }))
+        .with_state(state)
+}
+
+async fn authorize(State(state): State<HubState>, request: Request, next: Next) -> Response {
+    if let Some(token) = &state.token {
+        let provided = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+        if provided != Some(token.as_ref()) {
+            return (StatusCode::UNAUTHORIZED, "invalid token").into_response();
+        }
+    }
+    next.run(request).await
+}
+
+async fn push(State(state): State<HubState>, Json(request): Json<PushRequest>) -> Result<Json<PushResponse>, HubError> {
+    if request.node_id.is_empty() {
+        return Err(HubError::BadRequest("node_id 不能为空".to_string()));
+    }
+    // 修改必须来自推送它的节点，拉取时才能正确排除请求方自己的修改
+    if let Some(change) = request.changes.iter().find(|change| change.node_id != request.node_id) {
+        return Err(HubError::BadRequest(format!("修改 {} 不属于节点 {}", change.sync_id, request.node_id)));
+    }
+
+    let accepted = state.store.append(&request.changes).await?;
+    Ok(Json(PushResponse { accepted }))
+}
+
+async fn pull(State(state): State<HubState>, Query(query): Query<PullQuery>) -> Result<Json<PullResponse>, HubError> {
+    let limit = query.limit.unwrap_or(MAX_PULL_LIMIT).clamp(1, MAX_PULL_LIMIT);
+    let page = state.store.since(query.since, query.exclude_node.as_deref(), limit).await?;
+
+    Ok(Json(PullResponse {
+        changes: page.changes,
+        last_seq: page.last_seq,
+        has_more: page.has_more,
+    }))
+}
+
+enum HubError {
+    BadRequest(String),
+    Internal(anyhow::Error),
+}
+
+impl From<anyhow::Error> for HubError {
+    fn from(e: anyhow::Error) -> Self {
+        HubError::Internal(e)
+    }
+}
+
+impl IntoResponse for HubError {
+    fn into_response(self) -> Response {
+        match self {
+            HubError::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+            HubError::Internal(e) => {
+                println!("Request failed: {:#}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+}
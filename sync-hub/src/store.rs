@@ -0,0 +1,128 @@
+use std::str::FromStr;
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions};
+use sqlx::{FromRow, SqlitePool};
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS changes (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    node_id TEXT NOT NULL,
+    entity TEXT NOT NULL,
+    sync_id TEXT NOT NULL,
+    hlc TEXT NOT NULL,
+    data TEXT,
+    received_at DATETIME NOT NULL,
+    UNIQUE (entity, sync_id, hlc)
+);
+"#;
+
+/// 一条修改，字段与桌面端推送的格式一致；`data` 原样保存，行被永久删除时为 null
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredChange {
+    pub entity: String,
+    pub sync_id: String,
+    pub hlc: String,
+    pub node_id: String,
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, FromRow)]
+struct ChangeRow {
+    seq: i64,
+    node_id: String,
+    entity: String,
+    sync_id: String,
+    hlc: String,
+    data: Option<String>,
+}
+
+pub struct ChangePage {
+    pub changes: Vec<StoredChange>,
+    pub last_seq: i64,
+    pub has_more: bool,
+}
+
+/// 按收到顺序编号保存的修改日志
+#[derive(Clone)]
+pub struct ChangeStore {
+    pool: SqlitePool,
+}
+
+impl ChangeStore {
+    pub async fn open(path: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::from_str(path)?
+            .create_if_missing(true)
+            .journal_mode(SqliteJournalMode::Wal);
+        let pool = SqlitePoolOptions::new().max_connections(5).connect_with(options).await?;
+        sqlx::query(SCHEMA).execute(&pool).await?;
+        Ok(Self { pool })
+    }
+
+    /// 追加修改，返回新保存的条数；客户端重试时重复推送的修改会被忽略
+    pub async fn append(&self, changes: &[StoredChange]) -> Result<usize> {
+        let mut tx = self.pool.begin().await?;
+        let mut accepted = 0;
+        for change in changes {
+            let data = change.data.as_ref().map(|data| data.to_string());
+            let result = sqlx::query(
+                "INSERT INTO changes (node_id, entity, sync_id, hlc, data, received_at) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT DO NOTHING"
+            )
+            .bind(&change.node_id)
+            .bind(&change.entity)
+            .bind(&change.sync_id)
+            .bind(&change.hlc)
+            .bind(&data)
+            .bind(Utc::now())
+            .execute(&mut *tx)
+            .await?;
+            accepted += result.rows_affected() as usize;
+        }
+        tx.commit().await?;
+        Ok(accepted)
+    }
+
+    /// 序号大于 `since` 的修改，排除 `exclude_node` 推送的
+    pub async fn since(&self, since: i64, exclude_node: Option<&str>, limit: i64) -> Result<ChangePage> {
+        // 在同一个读事务中查询，最大序号与返回的修改来自同一个快照
+        let mut tx = self.pool.begin().await?;
+        let mut rows = sqlx::query_as::<_, ChangeRow>(
+            "SELECT seq, node_id, entity, sync_id, hlc, data FROM changes WHERE seq > $1 AND ($2 IS NULL OR node_id <> $2) ORDER BY seq LIMIT $3"
+        )
+        .bind(since)
+        .bind(exclude_node)
+        .bind(limit + 1)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let has_more = rows.len() as i64 > limit;
+        rows.truncate(limit as usize);
+
+        // 没有更多修改时直接跳到最新序号，之后不必再扫描请求方自己推送的修改
+        let last_seq = match rows.last() {
+            Some(row) if has_more => row.seq,
+            _ => sqlx::query_scalar::<_, Option<i64>>("SELECT MAX(seq) FROM changes")
+                .fetch_one(&mut *tx)
+                .await?
+                .unwrap_or(0)
+                .max(since),
+        };
+        tx.commit().await?;
+
+        let changes = rows
+            .into_iter()
+            .map(|row| {
+                Ok(StoredChange {
+                    entity: row.entity,
+                    sync_id: row.sync_id,
+                    hlc: row.hlc,
+                    node_id: row.node_id,
+                    data: row.data.as_deref().map(serde_json::from_str).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ChangePage { changes, last_seq, has_more })
+    }
+}